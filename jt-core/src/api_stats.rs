@@ -0,0 +1,135 @@
+use std::{
+    collections::BTreeMap,
+    future::Future,
+    sync::{Arc, Mutex},
+    time::Duration,
+    time::Instant,
+};
+
+use reqwest::{Method, RequestBuilder, Response, Url};
+
+use crate::{retry, trace};
+
+///Calls made to JIRA and Tempo by endpoint, shared by every clone so that the clients of a whole
+///run can count into the same place
+#[derive(Clone, Default)]
+pub struct ApiStats(Arc<Mutex<Summary>>);
+
+///Calls made up to some point in a run
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Summary {
+    ///Calls by method and path, with issue keys and IDs replaced by `{id}`
+    pub endpoints: BTreeMap<String, EndpointStats>,
+    ///Time spent waiting for responses, retries included
+    pub network_time: Duration,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct EndpointStats {
+    ///Requests sent
+    pub calls: u32,
+    ///Requests left unsent on a dry run
+    pub skipped: u32,
+}
+
+impl ApiStats {
+    ///The calls recorded so far
+    pub fn summary(&self) -> Summary {
+        self.0.lock().unwrap().clone()
+    }
+}
+
+impl Summary {
+    ///Requests sent to all endpoints together
+    pub fn calls(&self) -> u32 {
+        self.endpoints.values().map(|counts| counts.calls).sum()
+    }
+}
+
+pub trait RecordedRequest {
+    ///Send the request, retrying transient failures, and record the call and the time spent
+    ///waiting for it
+    fn send_recorded(self, stats: &ApiStats) -> impl Future<Output = reqwest::Result<Response>>;
+    ///Record that the request was left unsent on a dry run
+    fn skip_recorded(self, stats: &ApiStats);
+}
+
+impl RecordedRequest for RequestBuilder {
+    async fn send_recorded(self, stats: &ApiStats) -> reqwest::Result<Response> {
+        let (client, request) = self.build_split();
+        let request = request?;
+        let endpoint = endpoint_name(request.method(), request.url());
+        let (method, url) = (request.method().clone(), request.url().clone());
+        trace::request(&request);
+        let started = Instant::now();
+        let res = retry::execute(&client, request).await;
+        let elapsed = started.elapsed();
+        {
+            let mut stats = stats.0.lock().unwrap();
+            stats.network_time += elapsed;
+            stats.endpoints.entry(endpoint).or_default().calls += 1;
+        }
+        trace::response(&method, &url, elapsed, res).await
+    }
+
+    fn skip_recorded(self, stats: &ApiStats) {
+        if let Ok(request) = self.build() {
+            let endpoint = endpoint_name(request.method(), request.url());
+            let mut stats = stats.0.lock().unwrap();
+            stats.endpoints.entry(endpoint).or_default().skipped += 1;
+        }
+    }
+}
+
+///Method and path of a request, with issue keys and IDs replaced so that calls to the same
+///endpoint are counted together
+fn endpoint_name(method: &Method, url: &Url) -> String {
+    let mut previous = "";
+    let segments = url.path_segments().into_iter().flatten().map(|segment| {
+        let version = matches!(previous, "api" | "tempo-timesheets");
+        previous = segment;
+        if !version && segment.contains(|c: char| c.is_ascii_digit()) {
+            "{id}"
+        } else {
+            segment
+        }
+    });
+    format!("{method} /{}", segments.collect::<Vec<_>>().join("/"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_calls_to_an_endpoint_together() {
+        let stats = ApiStats::default();
+        let client = reqwest::Client::new();
+        client
+            .get("https://jira.example.com/rest/api/2/issue/PROJ-1")
+            .skip_recorded(&stats);
+        client
+            .get("https://jira.example.com/rest/api/2/issue/PROJ-22")
+            .skip_recorded(&stats.clone());
+        client
+            .post("https://jira.example.com/rest/tempo-timesheets/4/worklogs")
+            .skip_recorded(&stats);
+
+        let summary = stats.summary();
+        assert_eq!(
+            summary.endpoints.keys().collect::<Vec<_>>(),
+            [
+                "GET /rest/api/2/issue/{id}",
+                "POST /rest/tempo-timesheets/4/worklogs"
+            ]
+        );
+        assert_eq!(
+            summary.endpoints["GET /rest/api/2/issue/{id}"],
+            EndpointStats {
+                calls: 0,
+                skipped: 2
+            }
+        );
+        assert_eq!(summary.calls(), 0);
+    }
+}
@@ -0,0 +1,338 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt::Display,
+};
+
+use chrono::{NaiveDate, TimeDelta};
+
+use crate::{
+    duration::format_duration,
+    error::{JtError, Result},
+};
+
+///Drift from a commitment small enough to count as on target
+const TOLERANCE: TimeDelta = TimeDelta::minutes(15);
+
+///Name under which time on projects without a commitment is totalled
+pub const OTHER: &str = "other";
+
+///Rounds of rescaling projects and then days tried before concluding the commitments cannot be met
+const MAX_ROUNDS: usize = 1000;
+
+///How a project's share of the week compares with what was committed to it
+pub struct Drift {
+    pub project: String,
+    ///Committed share of the week, as a percentage
+    pub committed: f64,
+    pub target: TimeDelta,
+    pub actual: TimeDelta,
+}
+
+impl Drift {
+    pub fn drift(&self) -> TimeDelta {
+        self.actual - self.target
+    }
+
+    pub fn on_target(&self) -> bool {
+        self.drift().abs() < TOLERANCE
+    }
+
+    ///What to change to bring the project back in line with its commitment
+    pub fn suggestion(&self) -> String {
+        let drift = self.drift();
+        if self.on_target() {
+            String::from("on target")
+        } else if drift > TimeDelta::zero() {
+            format!("move {} to other projects", format_duration(drift))
+        } else {
+            format!("log {} more", format_duration(-drift))
+        }
+    }
+}
+
+impl Display for Drift {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{:<12} {:>9} {:>9} {:>9}  {}",
+            self.project,
+            format!("{:.0}%", self.committed),
+            format_duration(self.target),
+            format_duration(self.actual),
+            self.suggestion()
+        )
+    }
+}
+
+///Compare the time on each project with its committed share of the week's total, with time on
+///projects without a commitment compared against whatever share is left over
+pub fn drift(
+    totals: &HashMap<String, TimeDelta>,
+    commitments: &HashMap<String, f64>,
+) -> Vec<Drift> {
+    let week: TimeDelta = totals.values().sum();
+    let share = |percent: f64| {
+        TimeDelta::minutes((week.num_minutes() as f64 * percent / 100.0).round() as i64)
+    };
+    let mut drifts: Vec<Drift> = commitments
+        .iter()
+        .map(|(project, committed)| Drift {
+            project: project.clone(),
+            committed: *committed,
+            target: share(*committed),
+            actual: totals.get(project).copied().unwrap_or_default(),
+        })
+        .collect();
+    drifts.sort_by(|a, b| {
+        b.committed
+            .total_cmp(&a.committed)
+            .then(a.project.cmp(&b.project))
+    });
+    let uncommitted = 100.0 - commitments.values().sum::<f64>();
+    let other: TimeDelta = totals
+        .iter()
+        .filter(|(project, _)| !commitments.contains_key(*project))
+        .map(|(_, time)| *time)
+        .sum();
+    if uncommitted > 0.0 || other > TimeDelta::zero() {
+        drifts.push(Drift {
+            project: String::from(OTHER),
+            committed: uncommitted,
+            target: share(uncommitted),
+            actual: other,
+        });
+    }
+    drifts
+}
+
+///Factor to scale the time on each project by for its share of the week to match its commitment
+///exactly, with projects without a commitment sharing a single factor under `OTHER`
+pub fn scale_factors(drifts: &[Drift]) -> Result<HashMap<String, f64>> {
+    drifts
+        .iter()
+        .map(|drift| {
+            if drift.actual.is_zero() && !drift.target.is_zero() {
+                return Err(JtError::Validation(format!(
+                    "Nothing planned on {} to scale up, add an entry for it first",
+                    drift.project
+                )));
+            }
+            let factor = match drift.actual.num_seconds() {
+                0 => 0.0,
+                actual => drift.target.num_seconds() as f64 / actual as f64,
+            };
+            Ok((drift.project.clone(), factor))
+        })
+        .collect()
+}
+
+///Time planned on a day, spread over the projects of its tasks
+pub struct Planned {
+    pub day: NaiveDate,
+    ///Share of the time on each project, the shares adding up to one
+    pub projects: Vec<(String, f64)>,
+    pub time: TimeDelta,
+}
+
+///Times for the planned entries, in whole minutes and in the same order, with each project's share
+///of the week matching its commitment and each day keeping its total. Projects and then days are
+///rescaled in turn until both hold, which fails if the days leave no room to meet the commitments
+pub fn rebalance(
+    planned: &[Planned],
+    commitments: &HashMap<String, f64>,
+) -> Result<Vec<TimeDelta>> {
+    let mut seconds: Vec<f64> = planned
+        .iter()
+        .map(|entry| entry.time.num_seconds() as f64)
+        .collect();
+    let mut day_totals: BTreeMap<NaiveDate, f64> = BTreeMap::new();
+    for (entry, time) in planned.iter().zip(&seconds) {
+        *day_totals.entry(entry.day).or_default() += time;
+    }
+    for _ in 0..MAX_ROUNDS {
+        let mut totals: HashMap<String, TimeDelta> = HashMap::new();
+        for (entry, time) in planned.iter().zip(&seconds) {
+            for (project, share) in &entry.projects {
+                *totals.entry(project.clone()).or_default() +=
+                    TimeDelta::seconds((time * share).round() as i64);
+            }
+        }
+        let drifts = drift(&totals, commitments);
+        if drifts
+            .iter()
+            .all(|drift| drift.drift().abs() < TimeDelta::minutes(1))
+        {
+            return Ok(whole_minutes(planned, &seconds, &day_totals));
+        }
+
+        let factors = scale_factors(&drifts)?;
+        let mut scaled: BTreeMap<NaiveDate, f64> = BTreeMap::new();
+        for (entry, time) in planned.iter().zip(&mut seconds) {
+            let factor: f64 = entry
+                .projects
+                .iter()
+                .map(|(project, share)| {
+                    let factor = factors.get(project).or(factors.get(OTHER));
+                    share * factor.copied().unwrap_or(1.0)
+                })
+                .sum();
+            *time *= factor;
+            *scaled.entry(entry.day).or_default() += *time;
+        }
+        for (entry, time) in planned.iter().zip(&mut seconds) {
+            let total = day_totals[&entry.day];
+            match scaled[&entry.day] {
+                0.0 if total > 0.0 => {
+                    return Err(JtError::Validation(format!(
+                        "Nothing left planned on {} once rescaled, add entries for other projects that day",
+                        entry.day
+                    )))
+                }
+                0.0 => {}
+                day => *time *= total / day,
+            }
+        }
+    }
+    Err(JtError::Validation(String::from(
+        "Commitments cannot be met without changing how much is planned each day, spread each project over more days",
+    )))
+}
+
+///Times rounded to whole minutes, with any rounding difference on a day taken up by its longest
+///entry so that the day's total is kept
+fn whole_minutes(
+    planned: &[Planned],
+    seconds: &[f64],
+    day_totals: &BTreeMap<NaiveDate, f64>,
+) -> Vec<TimeDelta> {
+    let mut minutes: Vec<i64> = seconds
+        .iter()
+        .map(|time| (time / 60.0).round() as i64)
+        .collect();
+    for (day, total) in day_totals {
+        let today: Vec<usize> = (0..planned.len())
+            .filter(|i| planned[*i].day == *day)
+            .collect();
+        let rounded: i64 = today.iter().map(|i| minutes[*i]).sum();
+        if let Some(longest) = today.iter().copied().max_by_key(|i| minutes[*i]) {
+            minutes[longest] += (total / 60.0).round() as i64 - rounded;
+        }
+    }
+    minutes.into_iter().map(TimeDelta::minutes).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hours(totals: &[(&str, i64)]) -> HashMap<String, TimeDelta> {
+        totals
+            .iter()
+            .map(|(project, hours)| (project.to_string(), TimeDelta::hours(*hours)))
+            .collect()
+    }
+
+    fn commitments(percents: &[(&str, f64)]) -> HashMap<String, f64> {
+        percents
+            .iter()
+            .map(|(project, percent)| (project.to_string(), *percent))
+            .collect()
+    }
+
+    fn planned(day: u32, project: &str, hours: i64) -> Planned {
+        Planned {
+            day: NaiveDate::from_ymd_opt(2024, 5, day).unwrap(),
+            projects: vec![(project.to_owned(), 1.0)],
+            time: TimeDelta::hours(hours),
+        }
+    }
+
+    #[test]
+    fn compares_projects_with_their_share_of_the_week() {
+        let drifts = drift(
+            &hours(&[("ALPHA", 6), ("BETA", 2), ("INT", 2)]),
+            &commitments(&[("ALPHA", 50.0), ("BETA", 30.0)]),
+        );
+        let summary: Vec<(&str, i64, i64)> = drifts
+            .iter()
+            .map(|drift| {
+                (
+                    drift.project.as_str(),
+                    drift.target.num_minutes(),
+                    drift.actual.num_minutes(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            [("ALPHA", 300, 360), ("BETA", 180, 120), (OTHER, 120, 120)]
+        );
+        assert_eq!(drifts[0].suggestion(), "move 1h to other projects");
+        assert_eq!(drifts[1].suggestion(), "log 1h more");
+        assert!(drifts[2].on_target());
+    }
+
+    #[test]
+    fn leaves_out_other_once_commitments_cover_everything() {
+        let drifts = drift(&hours(&[("ALPHA", 4)]), &commitments(&[("ALPHA", 100.0)]));
+        assert_eq!(drifts.len(), 1);
+        assert!(drifts[0].on_target());
+    }
+
+    #[test]
+    fn scales_each_project_to_its_target() {
+        let drifts = drift(
+            &hours(&[("ALPHA", 6), ("BETA", 2)]),
+            &commitments(&[("ALPHA", 50.0), ("BETA", 50.0)]),
+        );
+        let factors = scale_factors(&drifts).unwrap();
+        assert_eq!(factors["ALPHA"], 4.0 / 6.0);
+        assert_eq!(factors["BETA"], 2.0);
+    }
+
+    #[test]
+    fn cannot_scale_up_a_project_with_nothing_planned() {
+        let drifts = drift(
+            &hours(&[("ALPHA", 8)]),
+            &commitments(&[("ALPHA", 50.0), ("BETA", 50.0)]),
+        );
+        assert!(matches!(
+            scale_factors(&drifts),
+            Err(JtError::Validation(message)) if message.contains("BETA")
+        ));
+    }
+
+    #[test]
+    fn rebalances_within_each_day() {
+        let planned = [
+            planned(6, "ALPHA", 6),
+            planned(6, "BETA", 2),
+            planned(7, "ALPHA", 2),
+            planned(7, "BETA", 6),
+            planned(8, "ALPHA", 8),
+        ];
+        let times = rebalance(&planned, &commitments(&[("ALPHA", 50.0), ("BETA", 50.0)])).unwrap();
+
+        let day_total = |day: usize| -> TimeDelta {
+            times
+                .iter()
+                .zip(&planned)
+                .filter(|(_, entry)| entry.day == planned[day].day)
+                .map(|(time, _)| *time)
+                .sum()
+        };
+        assert_eq!(day_total(0), TimeDelta::hours(8));
+        assert_eq!(day_total(2), TimeDelta::hours(8));
+        assert_eq!(day_total(4), TimeDelta::hours(8));
+        assert_eq!(times[4], TimeDelta::hours(8));
+        let alpha: TimeDelta = times[0] + times[2] + times[4];
+        assert!((alpha - TimeDelta::hours(12)).abs() < TimeDelta::minutes(2));
+    }
+
+    #[test]
+    fn fails_when_the_days_leave_no_room() {
+        let planned = [planned(6, "ALPHA", 8), planned(7, "BETA", 8)];
+        let result = rebalance(&planned, &commitments(&[("ALPHA", 75.0), ("BETA", 25.0)]));
+        assert!(matches!(result, Err(JtError::Validation(_))));
+    }
+}
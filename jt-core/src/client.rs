@@ -0,0 +1,1541 @@
+use chrono::{NaiveDate, NaiveTime, TimeDelta};
+use futures_util::{stream, StreamExt, TryStreamExt};
+use reqwest::{Certificate, Client, NoProxy, Proxy, RequestBuilder, Response, StatusCode, Url};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::{collections::HashMap, fmt::Display, future::Future};
+
+use crate::{
+    api_stats::{ApiStats, RecordedRequest},
+    endpoint,
+    error::{JtError, Result},
+    options::{
+        HttpOptions, Performance, RemainingEstimate, TempoVersion, UserIdentifier, Visibility,
+    },
+};
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct CreateWorklogRequest {
+    #[serde(flatten)]
+    worker: Worker,
+    started: String,
+    time_spent_seconds: u64,
+    origin_task_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    comment: Option<String>,
+    attributes: HashMap<String, WorkAttribute>,
+    ///Seconds left on the issue afterwards, reduced by the time spent if not given
+    #[serde(skip_serializing_if = "Option::is_none")]
+    remaining_estimate: Option<u64>,
+    ///Seconds of the time spent that are billable, all of it if not given
+    #[serde(skip_serializing_if = "Option::is_none")]
+    billable_seconds: Option<u64>,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct CreateWorklogRequestV3 {
+    author: User,
+    date_started: String,
+    time_spent_seconds: u64,
+    issue: IssueRef,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    comment: Option<String>,
+    worklog_attributes: Vec<WorklogAttributeV3>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    remaining_estimate: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    billable_seconds: Option<u64>,
+}
+
+#[derive(Serialize, Debug)]
+struct IssueRef {
+    key: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+struct WorklogAttributeV3 {
+    key: String,
+    #[serde(default)]
+    value: String,
+}
+
+#[derive(Serialize, Debug)]
+struct PostApprovalRequest {
+    user: User,
+    period: Period,
+    action: Action,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+enum User {
+    Key(String),
+    AccountId(String),
+}
+
+#[derive(Serialize, Debug)]
+enum Worker {
+    #[serde(rename = "worker")]
+    Key(String),
+    #[serde(rename = "authorAccountId")]
+    AccountId(String),
+}
+
+#[derive(Serialize, Debug)]
+enum WorkerFilter {
+    #[serde(rename = "worker")]
+    Key(Vec<String>),
+    #[serde(rename = "authorIds")]
+    AccountId(Vec<String>),
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct Period {
+    date_from: String,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "lowercase")]
+enum ActionType {
+    Submit,
+    Approve,
+    Reject,
+}
+
+#[derive(Serialize, Debug)]
+struct Action {
+    name: ActionType,
+    comment: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reviewer: Option<User>,
+}
+
+///A reviewer's decision on a submitted timesheet
+#[derive(Clone, Copy, Debug)]
+pub enum Review {
+    Approve,
+    Reject,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct IssueSearchRequest {
+    jql: String,
+    fields: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_results: Option<u32>,
+    ///Index of the first result to return, for reading past the first page
+    #[serde(skip_serializing_if = "Option::is_none")]
+    start_at: Option<usize>,
+    ///"warn" to ignore clauses naming issues that do not exist rather than failing the search
+    #[serde(skip_serializing_if = "Option::is_none")]
+    validate_query: Option<&'static str>,
+}
+
+#[derive(Serialize, Debug)]
+struct WorklogSearchRequest {
+    from: String,
+    to: String,
+    #[serde(flatten)]
+    worker: WorkerFilter,
+}
+
+#[derive(Deserialize)]
+struct UserResponse {
+    key: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UserSearchResult {
+    pub account_id: String,
+    pub display_name: String,
+    #[serde(default)]
+    pub email_address: Option<String>,
+}
+
+impl Display for UserSearchResult {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.email_address {
+            Some(email) => write!(f, "{} <{}>", self.display_name, email),
+            None => write!(f, "{}", self.display_name),
+        }
+    }
+}
+
+///JIRA refusing the credentials jt authenticates with
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuthFailure {
+    ///Not accepted at all, as with an expired, revoked or mistyped token
+    Rejected,
+    ///Too many failed logins, so that JIRA wants a CAPTCHA solved in the browser first
+    CaptchaRequired,
+    ///Accepted, but the account may not use JIRA
+    Forbidden,
+}
+
+impl AuthFailure {
+    fn from_response(res: &Response) -> Option<AuthFailure> {
+        let login_reason = res
+            .headers()
+            .get("X-Seraph-LoginReason")
+            .and_then(|reason| reason.to_str().ok())
+            .unwrap_or_default();
+        match res.status() {
+            StatusCode::UNAUTHORIZED => Some(AuthFailure::Rejected),
+            StatusCode::FORBIDDEN if login_reason.contains("AUTHENTICATION_DENIED") => {
+                Some(AuthFailure::CaptchaRequired)
+            }
+            StatusCode::FORBIDDEN => Some(AuthFailure::Forbidden),
+            _ => None,
+        }
+    }
+}
+
+impl Display for AuthFailure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AuthFailure::Rejected => write!(f, "JIRA did not accept the credentials (401 Unauthorized)"),
+            AuthFailure::CaptchaRequired => write!(f, "JIRA is asking for a CAPTCHA after too many failed logins, log in to JIRA in a browser once and try again"),
+            AuthFailure::Forbidden => write!(f, "JIRA accepted the credentials but refused access (403 Forbidden), the account may be deactivated or lack permission to use JIRA, which an administrator can check"),
+        }
+    }
+}
+
+impl std::error::Error for AuthFailure {}
+
+///A work attribute defined in Tempo, which worklogs can be given a value for
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct TempoWorkAttribute {
+    pub id: u64,
+    pub key: String,
+    pub name: String,
+    #[serde(default)]
+    pub required: bool,
+    #[serde(rename = "type")]
+    pub kind: WorkAttributeType,
+    ///Values to choose between, for attributes that are static lists
+    #[serde(default)]
+    pub static_list_values: Vec<StaticListValue>,
+}
+
+impl TempoWorkAttribute {
+    ///Whether values must be chosen from `static_list_values`
+    pub fn is_static_list(&self) -> bool {
+        self.kind.name == "STATIC_LIST"
+    }
+
+    ///Values that can still be chosen
+    pub fn options(&self) -> Vec<String> {
+        self.static_list_values
+            .iter()
+            .filter(|option| !option.removed)
+            .map(|option| option.value.clone())
+            .collect()
+    }
+}
+
+impl Display for TempoWorkAttribute {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} ({}, {})", self.name, self.key, self.kind.name)?;
+        if self.required {
+            write!(f, " required")?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Deserialize, Debug)]
+pub struct WorkAttributeType {
+    pub name: String,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct StaticListValue {
+    pub value: String,
+    #[serde(default)]
+    pub removed: bool,
+}
+
+///The customer who raised a Jira Service Management request
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct Customer {
+    #[serde(default)]
+    pub email_address: Option<String>,
+    #[serde(default)]
+    pub display_name: Option<String>,
+}
+
+impl Customer {
+    ///Whether the customer has the given email address, email domain or name
+    pub fn is(&self, wanted: &str) -> bool {
+        let email = self.email_address.as_deref().unwrap_or_default();
+        let domain = email.rsplit_once('@').map(|(_, domain)| domain);
+        [Some(email), domain, self.display_name.as_deref()]
+            .into_iter()
+            .flatten()
+            .any(|candidate| !candidate.is_empty() && candidate.eq_ignore_ascii_case(wanted))
+    }
+}
+
+#[derive(Deserialize)]
+struct CustomerRequest {
+    reporter: Customer,
+}
+
+#[derive(Deserialize)]
+struct Field {
+    id: String,
+    #[serde(default)]
+    schema: Option<FieldSchema>,
+}
+
+#[derive(Deserialize)]
+struct FieldSchema {
+    #[serde(default)]
+    custom: Option<String>,
+}
+
+///Custom field type of the organisations of Jira Service Management requests
+const ORGANIZATIONS_FIELD_TYPE: &str = "com.atlassian.servicedesk:sd-customer-organizations";
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Myself {
+    #[serde(default)]
+    pub key: Option<String>,
+    #[serde(default)]
+    pub account_id: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct MyPermissionsResponse {
+    permissions: HashMap<String, Permission>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Permission {
+    #[serde(default)]
+    pub name: String,
+    pub have_permission: bool,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JiraConfiguration {
+    #[serde(default)]
+    pub time_tracking_enabled: bool,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ServerInfo {
+    #[serde(default)]
+    pub deployment_type: Option<String>,
+}
+
+impl ServerInfo {
+    pub fn is_cloud(&self) -> bool {
+        self.deployment_type.as_deref() == Some("Cloud")
+    }
+}
+
+#[derive(Deserialize)]
+pub struct IssueSearchResponse {
+    #[serde(default)]
+    pub issues: Vec<Issue>,
+    ///Number of issues matching the search, which may be more than were returned
+    #[serde(default)]
+    pub total: usize,
+}
+
+///Fields of an issue that jt reads itself
+fn task_fields() -> Vec<String> {
+    ["summary", "duedate", "priority", "updated"]
+        .map(String::from)
+        .to_vec()
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct JiraErrors {
+    #[serde(default)]
+    error_messages: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct Issue {
+    pub key: String,
+    pub fields: HashMap<String, Value>,
+}
+
+impl Issue {
+    pub fn due_date(&self) -> Option<NaiveDate> {
+        let due = self.fields.get("duedate")?.as_str()?;
+        NaiveDate::parse_from_str(due, JIRA_DATE_FORMAT).ok()
+    }
+
+    ///Day the issue was last changed
+    pub fn updated(&self) -> Option<NaiveDate> {
+        let updated = self.fields.get("updated")?.as_str()?;
+        NaiveDate::parse_from_str(updated.get(..10)?, JIRA_DATE_FORMAT).ok()
+    }
+
+    pub fn summary(&self) -> Option<&str> {
+        self.fields.get("summary")?.as_str()
+    }
+
+    ///Name of a field holding an object with a name, such as the status or assignee
+    pub fn named_field(&self, field: &str, name: &str) -> Option<&str> {
+        self.fields.get(field)?.get(name)?.as_str()
+    }
+
+    ///Name of the issue's priority
+    pub fn priority(&self) -> Option<&str> {
+        self.fields.get("priority")?.get("name")?.as_str()
+    }
+
+    ///ID of the issue's priority, whose urgency is its position in [`JtClient::get_priorities`]
+    pub fn priority_id(&self) -> Option<&str> {
+        self.fields.get("priority")?.get("id")?.as_str()
+    }
+}
+
+///A priority issues can be given, as configured on the instance
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Priority {
+    pub id: String,
+    pub name: String,
+}
+
+impl Display for Issue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let summary = self
+            .fields
+            .get("summary")
+            .expect("Task does not contain summary field")
+            .as_str()
+            .expect("Summary field is not string");
+        write!(f, "{} - {}", self.key, summary)
+    }
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct Worklog {
+    pub tempo_worklog_id: u64,
+    #[serde(default)]
+    pub jira_worklog_id: Option<u64>,
+    pub time_spent_seconds: u64,
+    #[serde(default)]
+    pub billable_seconds: Option<u64>,
+    pub started: String,
+    #[serde(default)]
+    pub comment: Option<String>,
+    pub issue: WorklogIssue,
+    #[serde(default)]
+    pub attributes: HashMap<String, WorklogAttribute>,
+}
+
+impl Worklog {
+    pub fn day(&self) -> Result<NaiveDate> {
+        let date = self.started.get(..10).unwrap_or(&self.started);
+        NaiveDate::parse_from_str(date, JIRA_DATE_FORMAT)
+            .map_err(|e| JtError::Validation(format!("Invalid worklog date \"{date}\": {e}")))
+    }
+
+    ///Time of day the worklog starts, if `started` includes one
+    pub fn start_time(&self) -> Option<NaiveTime> {
+        let time = self.started.get(11..16)?;
+        NaiveTime::parse_from_str(time, "%H:%M").ok()
+    }
+}
+
+///The approval state of a timesheet period
+#[derive(Deserialize, Debug)]
+pub struct Approval {
+    pub status: String,
+    #[serde(default)]
+    pub reviewer: Option<Reviewer>,
+    ///The last thing done to the timesheet, such as submitting or rejecting it
+    #[serde(default)]
+    pub action: Option<ApprovalAction>,
+}
+
+impl Approval {
+    ///Whether the timesheet is still open, ie not submitted
+    pub fn is_open(&self) -> bool {
+        self.status.eq_ignore_ascii_case("open")
+    }
+
+    pub fn state(&self) -> ApprovalState {
+        let rejected = self
+            .action
+            .as_ref()
+            .and_then(|action| action.name.as_deref())
+            .is_some_and(|name| name.eq_ignore_ascii_case("reject"));
+        match self.status.to_lowercase().as_str() {
+            "approved" => ApprovalState::Approved,
+            "waiting_for_approval" | "submitted" => ApprovalState::Submitted,
+            "rejected" => ApprovalState::Rejected,
+            _ if rejected => ApprovalState::Rejected,
+            _ => ApprovalState::Open,
+        }
+    }
+
+    ///Comment left with the last action, eg the reason for a rejection
+    pub fn comment(&self) -> Option<&str> {
+        self.action
+            .as_ref()
+            .and_then(|action| action.comment.as_deref())
+            .filter(|comment| !comment.is_empty())
+    }
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ApprovalAction {
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub comment: Option<String>,
+}
+
+///Where a timesheet is in the approval process, simplified from Tempo's statuses
+#[derive(Serialize, Clone, Copy, PartialEq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum ApprovalState {
+    Open,
+    Submitted,
+    Approved,
+    Rejected,
+}
+
+impl Display for ApprovalState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let state = match self {
+            ApprovalState::Open => "open",
+            ApprovalState::Submitted => "submitted",
+            ApprovalState::Approved => "approved",
+            ApprovalState::Rejected => "rejected",
+        };
+        f.write_str(state)
+    }
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct Reviewer {
+    #[serde(default)]
+    pub display_name: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct WorklogV3 {
+    id: u64,
+    time_spent_seconds: u64,
+    #[serde(default)]
+    billable_seconds: Option<u64>,
+    date_started: String,
+    #[serde(default)]
+    comment: Option<String>,
+    issue: WorklogIssue,
+    #[serde(default)]
+    worklog_attributes: Vec<WorklogAttributeV3>,
+}
+
+impl From<WorklogV3> for Worklog {
+    fn from(worklog: WorklogV3) -> Self {
+        let attributes = worklog.worklog_attributes.into_iter().map(|attr| {
+            let attribute = WorklogAttribute {
+                value: attr.value,
+                name: None,
+                work_attribute_id: None,
+            };
+            (attr.key, attribute)
+        });
+        Worklog {
+            tempo_worklog_id: worklog.id,
+            jira_worklog_id: Some(worklog.id),
+            time_spent_seconds: worklog.time_spent_seconds,
+            billable_seconds: worklog.billable_seconds,
+            started: worklog.date_started,
+            comment: worklog.comment,
+            issue: worklog.issue,
+            attributes: HashMap::from_iter(attributes),
+        }
+    }
+}
+
+#[derive(Deserialize, Debug)]
+pub struct WorklogIssue {
+    pub key: String,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct WorklogAttribute {
+    #[serde(default)]
+    pub value: String,
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub work_attribute_id: Option<u64>,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct WorkAttribute {
+    name: String,
+    work_attribute_id: u64,
+    value: String,
+}
+
+///A worklog to be created
+pub struct NewWorklog {
+    pub task_id: String,
+    pub day: NaiveDate,
+    ///Time of day the work started, midnight if not given
+    pub start_time: Option<NaiveTime>,
+    pub time_spent: TimeDelta,
+    pub comment: Option<String>,
+    pub attributes: Vec<crate::options::WorkAttribute>,
+    ///Who can see the worklog in JIRA, anyone who can see the issue if not given
+    pub visibility: Option<Visibility>,
+    pub remaining_estimate: RemainingEstimate,
+    ///Billable part of the time spent, left to Tempo if not given
+    pub billable: Option<TimeDelta>,
+}
+
+const JIRA_DATE_FORMAT: &str = "%Y-%m-%d";
+const TEMPO_V3_DATETIME_FORMAT: &str = "%Y-%m-%dT%H:%M:%S%.3f";
+const TEMPO_V4_DATETIME_FORMAT: &str = "%Y-%m-%d %H:%M:%S%.3f";
+///Most issues looked up by key in a single search
+const ISSUE_BATCH_SIZE: usize = 100;
+///Search for tasks when no JQL is configured, open issues assigned to the user along with any
+///completed since `{from}`
+const DEFAULT_TASK_JQL: &str = "(statusCategory NOT IN (Done) OR status CHANGED AFTER {from}) AND assignee IN (currentUser()) ORDER BY created DESC";
+
+///What requests to JIRA and Tempo are authenticated with
+#[derive(Clone)]
+pub enum Credentials {
+    Token(String),
+    Basic { username: String, password: String },
+}
+
+impl Credentials {
+    ///Authenticate a request with these credentials
+    pub fn authorize(&self, request: RequestBuilder) -> RequestBuilder {
+        match self {
+            Credentials::Token(token) => request.bearer_auth(token),
+            Credentials::Basic { username, password } => {
+                request.basic_auth(username, Some(password))
+            }
+        }
+    }
+}
+
+trait Authorized {
+    fn authorized(self, credentials: &Credentials) -> Self;
+}
+
+impl Authorized for RequestBuilder {
+    fn authorized(self, credentials: &Credentials) -> Self {
+        credentials.authorize(self)
+    }
+}
+
+trait CheckedResponse {
+    ///Fail with the credentials refused or the error JIRA or Tempo gave, if the status is not a
+    ///success
+    fn checked(self) -> impl Future<Output = Result<Self>>
+    where
+        Self: Sized;
+}
+
+impl CheckedResponse for Response {
+    async fn checked(self) -> Result<Self> {
+        match AuthFailure::from_response(&self) {
+            Some(failure @ (AuthFailure::Rejected | AuthFailure::CaptchaRequired)) => {
+                Err(failure.into())
+            }
+            _ if self.status().is_client_error() || self.status().is_server_error() => {
+                Err(JtError::from_response(self).await)
+            }
+            _ => Ok(self),
+        }
+    }
+}
+
+fn approval_period_start(monday: NaiveDate) -> NaiveDate {
+    monday - TimeDelta::days(2) //Tempo seems to want the saturday prior
+}
+
+///An HTTP client going through the configured proxy if there is one and otherwise any given by
+///the `HTTPS_PROXY`, `HTTP_PROXY` and `NO_PROXY` environment variables, and trusting any configured
+///CA certificates alongside the system's
+pub fn http_client(options: &HttpOptions) -> Result<Client> {
+    let mut builder = Client::builder();
+    if let Some(proxy) = &options.proxy {
+        let proxy = Proxy::all(proxy.clone())
+            .map_err(|e| JtError::Config(format!("Invalid proxy {proxy}: {e}")))?
+            .no_proxy(NoProxy::from_env());
+        builder = builder.proxy(proxy);
+    }
+    if let Some(path) = &options.ca_certificate {
+        let pem = std::fs::read(path).map_err(|source| JtError::Io {
+            path: path.clone(),
+            source,
+        })?;
+        let certificates = Certificate::from_pem_bundle(&pem).map_err(|e| {
+            JtError::Config(format!("Invalid CA certificate {}: {e}", path.display()))
+        })?;
+        for certificate in certificates {
+            builder = builder.add_root_certificate(certificate);
+        }
+    }
+    if options.accept_invalid_certs {
+        builder = builder.danger_accept_invalid_certs(true);
+    }
+    builder
+        .build()
+        .map_err(|e| JtError::Config(format!("Unable to set up the HTTP client: {e}")))
+}
+
+pub struct JtClient {
+    credentials: Credentials,
+    internal: Client,
+    base: Url,
+    dry_run: bool,
+    user_identifier: UserIdentifier,
+    tempo_version: TempoVersion,
+    performance: Option<Performance>,
+    stats: ApiStats,
+}
+
+impl JtClient {
+    ///A client for the instance at `base`, sending its requests through [`http_client`]
+    pub fn new(
+        credentials: &Credentials,
+        base: Url,
+        dry_run: bool,
+        user_identifier: UserIdentifier,
+        options: &HttpOptions,
+    ) -> Result<JtClient> {
+        Ok(JtClient {
+            credentials: credentials.clone(),
+            internal: http_client(options)?,
+            base,
+            dry_run,
+            user_identifier,
+            tempo_version: TempoVersion::V4,
+            performance: None,
+            stats: ApiStats::default(),
+        })
+    }
+
+    ///Address of the JIRA instance
+    pub fn base(&self) -> &Url {
+        &self.base
+    }
+
+    ///Whether requests that change anything are skipped
+    pub fn is_dry_run(&self) -> bool {
+        self.dry_run
+    }
+
+    ///Use the given version of the Tempo API, V4 unless told otherwise
+    pub fn with_tempo_version(self, tempo_version: TempoVersion) -> JtClient {
+        JtClient {
+            tempo_version,
+            ..self
+        }
+    }
+
+    ///Limit the task search and concurrency as tuned for a large instance
+    pub fn with_performance(self, performance: Performance) -> JtClient {
+        JtClient {
+            performance: Some(performance),
+            ..self
+        }
+    }
+
+    ///Record calls into `stats`, shared with other clients, rather than into the client's own
+    pub fn with_stats(self, stats: ApiStats) -> JtClient {
+        JtClient { stats, ..self }
+    }
+
+    ///Calls made by the client so far
+    pub fn stats(&self) -> &ApiStats {
+        &self.stats
+    }
+
+    ///Most requests to make to JIRA at once
+    pub fn concurrency(&self) -> usize {
+        self.performance
+            .as_ref()
+            .map_or(1, |performance| performance.concurrency.max(1))
+    }
+
+    fn tempo_url(&self, path: &str) -> Url {
+        self.tempo_url_for(self.tempo_version, path)
+    }
+
+    fn tempo_url_for(&self, version: TempoVersion, path: &str) -> Url {
+        let version = u8::from(version);
+        endpoint::join(
+            &self.base,
+            &format!("rest/tempo-timesheets/{version}/{path}"),
+        )
+    }
+
+    ///Probe which Tempo Timesheets API versions the instance exposes, preferring the newest. The
+    ///probe reads the worklogs collection, which v4 serves only to POSTs, so a version is present
+    ///if the probe succeeds or is refused its method, missing if it is not found, and any other
+    ///failure is returned rather than guessed at
+    pub async fn detect_tempo_version(&self) -> Result<TempoVersion> {
+        for version in [TempoVersion::V4, TempoVersion::V3] {
+            let probe = self.tempo_url_for(version, "worklogs");
+            let res = self
+                .internal
+                .get(probe)
+                .authorized(&self.credentials)
+                .send_recorded(&self.stats)
+                .await?;
+            log::debug!(
+                "Tempo API v{} probe returned {}",
+                u8::from(version),
+                res.status()
+            );
+            match res.status() {
+                StatusCode::NOT_FOUND => continue,
+                StatusCode::METHOD_NOT_ALLOWED => return Ok(version),
+                _ => {}
+            }
+            let res = res.checked().await?;
+            if !res.status().is_success() {
+                return Err(JtError::from_response(res).await);
+            }
+            return Ok(version);
+        }
+        Err(JtError::Config(String::from(
+            "Unable to find a supported Tempo Timesheets API (v3 or v4) on this instance",
+        )))
+    }
+
+    fn user(&self, id: &str) -> User {
+        match self.user_identifier {
+            UserIdentifier::Key => User::Key(id.to_owned()),
+            UserIdentifier::AccountId => User::AccountId(id.to_owned()),
+        }
+    }
+
+    fn worker(&self, id: &str) -> Worker {
+        match self.user_identifier {
+            UserIdentifier::Key => Worker::Key(id.to_owned()),
+            UserIdentifier::AccountId => Worker::AccountId(id.to_owned()),
+        }
+    }
+
+    ///Log work for a worker, returning the worklog Tempo created or None on a dry run
+    pub async fn create_worklog(
+        &self,
+        worker: &str,
+        worklog: NewWorklog,
+    ) -> Result<Option<Worklog>> {
+        let key = worklog.task_id.clone();
+        let visibility = worklog.visibility.clone();
+        let remaining_estimate = match worklog.remaining_estimate {
+            RemainingEstimate::Auto => None,
+            RemainingEstimate::Leave => Some(self.get_remaining_estimate(&key).await?),
+            RemainingEstimate::Set(estimate) => Some(estimate.num_seconds() as u64),
+        };
+        let billable_seconds = worklog
+            .billable
+            .map(|billable| billable.num_seconds() as u64);
+        let req = match self.tempo_version {
+            TempoVersion::V4 => {
+                let attributes = worklog.attributes.into_iter().map(|attr| {
+                    (
+                        attr.key,
+                        WorkAttribute {
+                            name: attr.name,
+                            work_attribute_id: attr.work_attribute_id,
+                            value: attr.value,
+                        },
+                    )
+                });
+                let payload = CreateWorklogRequest {
+                    worker: self.worker(worker),
+                    started: match worklog.start_time {
+                        Some(time) => worklog
+                            .day
+                            .and_time(time)
+                            .format(TEMPO_V4_DATETIME_FORMAT)
+                            .to_string(),
+                        None => worklog.day.format(JIRA_DATE_FORMAT).to_string(),
+                    },
+                    time_spent_seconds: worklog.time_spent.num_seconds() as u64,
+                    origin_task_id: worklog.task_id,
+                    comment: worklog.comment,
+                    attributes: HashMap::from_iter(attributes),
+                    remaining_estimate,
+                    billable_seconds,
+                };
+                log::debug!("Create worklog request contents: {payload:?}");
+                self.internal
+                    .post(self.tempo_url("worklogs"))
+                    .json(&payload)
+            }
+            TempoVersion::V3 => {
+                let attributes = worklog
+                    .attributes
+                    .into_iter()
+                    .map(|attr| WorklogAttributeV3 {
+                        key: attr.key,
+                        value: attr.value,
+                    });
+                let payload = CreateWorklogRequestV3 {
+                    author: self.user(worker),
+                    date_started: worklog
+                        .day
+                        .and_time(worklog.start_time.unwrap_or(NaiveTime::MIN))
+                        .format(TEMPO_V3_DATETIME_FORMAT)
+                        .to_string(),
+                    time_spent_seconds: worklog.time_spent.num_seconds() as u64,
+                    issue: IssueRef {
+                        key: worklog.task_id,
+                    },
+                    comment: worklog.comment,
+                    worklog_attributes: attributes.collect(),
+                    remaining_estimate,
+                    billable_seconds,
+                };
+                log::debug!("Create worklog request contents: {payload:?}");
+                self.internal
+                    .post(self.tempo_url("worklogs/"))
+                    .json(&payload)
+            }
+        }
+        .authorized(&self.credentials);
+
+        if self.dry_run {
+            req.skip_recorded(&self.stats);
+            return Ok(None);
+        }
+        let res = req.send_recorded(&self.stats).await?.checked().await?;
+        let created = match self.tempo_version {
+            TempoVersion::V4 => res.json::<Vec<Worklog>>().await?.into_iter().next(),
+            TempoVersion::V3 => Some(res.json::<WorklogV3>().await?.into()),
+        };
+        if let Some(visibility) = visibility {
+            match created.as_ref().and_then(|worklog| worklog.jira_worklog_id) {
+                Some(id) => self.set_worklog_visibility(&key, id, &visibility).await?,
+                None => log::warn!(
+                    "Tempo did not report a JIRA worklog for {key}, unable to restrict its visibility"
+                ),
+            }
+        }
+        Ok(created)
+    }
+
+    ///Restrict who can see a worklog, which Tempo's own API does not support, through JIRA's
+    async fn set_worklog_visibility(
+        &self,
+        key: &str,
+        jira_worklog_id: u64,
+        visibility: &Visibility,
+    ) -> Result<()> {
+        let url = endpoint::join(
+            &self.base,
+            &format!("rest/api/2/issue/{key}/worklog/{jira_worklog_id}"),
+        );
+        let body = serde_json::json!({ "visibility": visibility });
+        log::debug!("Update worklog request contents: {body}");
+        self.internal
+            .put(url)
+            .json(&body)
+            .authorized(&self.credentials)
+            .send_recorded(&self.stats)
+            .await?
+            .checked()
+            .await?;
+        Ok(())
+    }
+
+    ///Link to an issue in the JIRA web interface, focused on a specific worklog if given
+    pub fn issue_url(&self, key: &str, jira_worklog_id: Option<u64>) -> Url {
+        let mut url = endpoint::join(&self.base, &format!("browse/{key}"));
+        if let Some(id) = jira_worklog_id {
+            url.query_pairs_mut()
+                .append_pair("focusedWorklogId", &id.to_string());
+        }
+        url
+    }
+
+    ///Submit the week's timesheet for approval, returning the approval as Tempo reports it after
+    ///submission, or nothing on dry runs
+    pub async fn submit_timesheet(
+        &self,
+        worker: &str,
+        reviewer: &str,
+        monday: NaiveDate,
+    ) -> Result<Option<Approval>> {
+        let action = Action {
+            name: ActionType::Submit,
+            comment: String::new(),
+            reviewer: Some(self.user(reviewer)),
+        };
+        self.post_approval_action(worker, monday, action).await
+    }
+
+    ///Approve or reject a worker's submitted timesheet for the week, returning the approval as
+    ///Tempo reports it afterwards, or nothing on dry runs
+    pub async fn review_timesheet(
+        &self,
+        worker: &str,
+        monday: NaiveDate,
+        review: Review,
+        comment: String,
+    ) -> Result<Option<Approval>> {
+        let name = match review {
+            Review::Approve => ActionType::Approve,
+            Review::Reject => ActionType::Reject,
+        };
+        let action = Action {
+            name,
+            comment,
+            reviewer: None,
+        };
+        self.post_approval_action(worker, monday, action).await
+    }
+
+    async fn post_approval_action(
+        &self,
+        worker: &str,
+        monday: NaiveDate,
+        action: Action,
+    ) -> Result<Option<Approval>> {
+        let url = self.tempo_url("timesheet-approval");
+        let period_start = approval_period_start(monday);
+        let payload = PostApprovalRequest {
+            user: self.user(worker),
+            period: Period {
+                date_from: period_start.format(JIRA_DATE_FORMAT).to_string(),
+            },
+            action,
+        };
+        log::debug!("Create timesheet approval request contents: {payload:?}");
+        let req = self
+            .internal
+            .post(url)
+            .json(&payload)
+            .authorized(&self.credentials);
+        if self.dry_run {
+            req.skip_recorded(&self.stats);
+            return Ok(None);
+        }
+        req.send_recorded(&self.stats).await?.checked().await?;
+        self.get_timesheet_approval(worker, period_start)
+            .await
+            .map(Some)
+    }
+
+    ///The approval state of the timesheet for the week starting on the given Monday
+    pub async fn get_week_approval(&self, worker: &str, monday: NaiveDate) -> Result<Approval> {
+        self.get_timesheet_approval(worker, approval_period_start(monday))
+            .await
+    }
+
+    async fn get_timesheet_approval(
+        &self,
+        worker: &str,
+        period_start: NaiveDate,
+    ) -> Result<Approval> {
+        let url = self.tempo_url("timesheet-approval/current");
+        let user_param = match self.user_identifier {
+            UserIdentifier::Key => "userKey",
+            UserIdentifier::AccountId => "accountId",
+        };
+        let res = self
+            .internal
+            .get(url)
+            .query(&[
+                (
+                    "periodStartDate",
+                    period_start.format(JIRA_DATE_FORMAT).to_string(),
+                ),
+                (user_param, worker.to_owned()),
+            ])
+            .authorized(&self.credentials)
+            .send_recorded(&self.stats)
+            .await?
+            .checked()
+            .await?;
+        let approval = res.json::<Approval>().await?;
+        Ok(approval)
+    }
+
+    ///Worklogs by a worker between two days inclusive
+    pub async fn get_worklogs(
+        &self,
+        worker: &str,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<Vec<Worklog>> {
+        let from = from.format(JIRA_DATE_FORMAT).to_string();
+        let to = to.format(JIRA_DATE_FORMAT).to_string();
+        match self.tempo_version {
+            TempoVersion::V4 => {
+                let body = WorklogSearchRequest {
+                    from,
+                    to,
+                    worker: match self.user_identifier {
+                        UserIdentifier::Key => WorkerFilter::Key(vec![worker.to_owned()]),
+                        UserIdentifier::AccountId => {
+                            WorkerFilter::AccountId(vec![worker.to_owned()])
+                        }
+                    },
+                };
+                log::debug!("Worklog search request contents: {body:?}");
+                let res = self
+                    .internal
+                    .post(self.tempo_url("worklogs/search"))
+                    .json(&body)
+                    .authorized(&self.credentials)
+                    .send_recorded(&self.stats)
+                    .await?
+                    .checked()
+                    .await?;
+                let worklogs = res.json::<Vec<Worklog>>().await?;
+                Ok(worklogs)
+            }
+            TempoVersion::V3 => {
+                let res = self
+                    .internal
+                    .get(self.tempo_url("worklogs/"))
+                    .query(&[
+                        ("dateFrom", from),
+                        ("dateTo", to),
+                        (
+                            match self.user_identifier {
+                                UserIdentifier::Key => "username",
+                                UserIdentifier::AccountId => "accountId",
+                            },
+                            worker.to_owned(),
+                        ),
+                    ])
+                    .authorized(&self.credentials)
+                    .send_recorded(&self.stats)
+                    .await?
+                    .checked()
+                    .await?;
+                let worklogs = res.json::<Vec<WorklogV3>>().await?;
+                Ok(worklogs.into_iter().map(Worklog::from).collect())
+            }
+        }
+    }
+
+    ///Search for the issues to offer as tasks, with `{from}` in the JQL replaced by the date from
+    ///which recently completed issues are included. Every page of results is read, up to
+    ///`max_tasks` when tuned for performance.
+    pub async fn get_assigned_issues(
+        &self,
+        done_tasks_from: NaiveDate,
+        jql: Option<&str>,
+    ) -> Result<IssueSearchResponse> {
+        let url = endpoint::join(&self.base, "rest/api/2/search");
+        let done_tasks_from = done_tasks_from.format(JIRA_DATE_FORMAT).to_string();
+        let mut fields = task_fields();
+        let max_tasks = match &self.performance {
+            Some(performance) => {
+                fields.extend(performance.fields.iter().cloned());
+                Some(performance.max_tasks)
+            }
+            None => {
+                fields.push(String::from("*navigable"));
+                None
+            }
+        };
+        let mut body = IssueSearchRequest {
+            jql: jql
+                .unwrap_or(DEFAULT_TASK_JQL)
+                .replace("{from}", &done_tasks_from),
+            fields,
+            max_results: max_tasks,
+            start_at: None,
+            validate_query: None,
+        };
+        let mut issues = Vec::new();
+        loop {
+            log::debug!("Search request contents: {body:?}");
+            let res = self
+                .internal
+                .post(url.clone())
+                .json(&body)
+                .authorized(&self.credentials)
+                .send_recorded(&self.stats)
+                .await?;
+            if res.status() == StatusCode::BAD_REQUEST {
+                let errors = res
+                    .json::<JiraErrors>()
+                    .await
+                    .map(|errors| errors.error_messages.join("; "))
+                    .unwrap_or_default();
+                return Err(JtError::Validation(format!(
+                    "Invalid JQL \"{}\": {errors}",
+                    body.jql
+                )));
+            }
+            let page = res.checked().await?.json::<IssueSearchResponse>().await?;
+            let fetched = page.issues.len();
+            issues.extend(page.issues);
+            let remaining = max_tasks.map(|max| (max as usize).saturating_sub(issues.len()));
+            if fetched == 0 || issues.len() >= page.total || remaining == Some(0) {
+                return Ok(IssueSearchResponse {
+                    issues,
+                    total: page.total,
+                });
+            }
+            body.start_at = Some(issues.len());
+            body.max_results = remaining.map(|remaining| remaining as u32);
+        }
+    }
+
+    ///Retrieve several issues, in batched searches when tuned for performance and otherwise one at
+    ///a time
+    pub async fn get_issues(&self, keys: &[&str]) -> Result<Vec<Issue>> {
+        let mut issues = Vec::new();
+        if self.performance.is_some() {
+            let batches: Vec<Vec<Issue>> = stream::iter(keys.chunks(ISSUE_BATCH_SIZE))
+                .map(|batch| self.search_keys(batch))
+                .buffered(self.concurrency())
+                .try_collect()
+                .await?;
+            issues.extend(batches.into_iter().flatten());
+        }
+        let unfound: Vec<&str> = keys
+            .iter()
+            .copied()
+            .filter(|key| !issues.iter().any(|issue| issue.key == *key))
+            .collect();
+        let singles: Vec<Issue> = stream::iter(unfound)
+            .map(|key| self.get_issue(key))
+            .buffered(self.concurrency())
+            .try_collect()
+            .await?;
+        issues.extend(singles);
+        Ok(issues)
+    }
+
+    ///Search for issues by key, leaving out any that do not exist
+    async fn search_keys(&self, keys: &[&str]) -> Result<Vec<Issue>> {
+        let url = endpoint::join(&self.base, "rest/api/2/search");
+        let body = IssueSearchRequest {
+            jql: format!("key in ({}) ORDER BY key", keys.join(", ")),
+            fields: task_fields(),
+            max_results: Some(keys.len() as u32),
+            start_at: None,
+            validate_query: Some("warn"),
+        };
+        log::debug!("Search request contents: {body:?}");
+        let res = self
+            .internal
+            .post(url)
+            .json(&body)
+            .authorized(&self.credentials)
+            .send_recorded(&self.stats)
+            .await?
+            .checked()
+            .await?;
+        let resp = res.json::<IssueSearchResponse>().await?;
+        Ok(resp.issues)
+    }
+
+    ///An issue by key, failing if it does not exist or cannot be seen
+    pub async fn get_issue(&self, key: &str) -> Result<Issue> {
+        let url = endpoint::join(&self.base, &format!("rest/api/2/issue/{key}"));
+        let res = self
+            .internal
+            .get(url)
+            .authorized(&self.credentials)
+            .send_recorded(&self.stats)
+            .await?
+            .checked()
+            .await?;
+        let issue = res.json::<Issue>().await?;
+        Ok(issue)
+    }
+
+    ///Seconds of work left on an issue by its time tracking, zero if it has no estimate
+    async fn get_remaining_estimate(&self, key: &str) -> Result<u64> {
+        let mut url = endpoint::join(&self.base, &format!("rest/api/2/issue/{key}"));
+        url.query_pairs_mut().append_pair("fields", "timetracking");
+        let issue = self
+            .internal
+            .get(url)
+            .authorized(&self.credentials)
+            .send_recorded(&self.stats)
+            .await?
+            .checked()
+            .await?
+            .json::<Issue>()
+            .await?;
+        Ok(issue
+            .fields
+            .get("timetracking")
+            .and_then(|tracking| tracking.get("remainingEstimateSeconds"))
+            .and_then(Value::as_u64)
+            .unwrap_or_default())
+    }
+
+    ///Retrieve an issue, or None if it does not exist. Issues that have been moved are returned
+    ///under their new key
+    pub async fn find_issue(&self, key: &str) -> Result<Option<Issue>> {
+        let url = endpoint::join(&self.base, &format!("rest/api/2/issue/{key}"));
+        let res = self
+            .internal
+            .get(url)
+            .authorized(&self.credentials)
+            .send_recorded(&self.stats)
+            .await?;
+        if res.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        let issue = res.checked().await?.json::<Issue>().await?;
+        Ok(Some(issue))
+    }
+
+    ///Search for issues whose text matches the query, most recently updated first
+    pub async fn search_issues(&self, query: &str, limit: u32) -> Result<Vec<Issue>> {
+        let url = endpoint::join(&self.base, "rest/api/2/search");
+        let query = query.replace('\\', "\\\\").replace('"', "\\\"");
+        let body = IssueSearchRequest {
+            jql: format!("text ~ \"{query}\" ORDER BY updated DESC"),
+            fields: vec![String::from("summary")],
+            max_results: Some(limit),
+            start_at: None,
+            validate_query: None,
+        };
+        log::debug!("Search request contents: {body:?}");
+        let res = self
+            .internal
+            .post(url)
+            .json(&body)
+            .authorized(&self.credentials)
+            .send_recorded(&self.stats)
+            .await?
+            .checked()
+            .await?;
+        let resp = res.json::<IssueSearchResponse>().await?;
+        Ok(resp.issues)
+    }
+
+    ///ID of the field holding the organisations of service desk requests, if there is one
+    pub async fn find_organization_field(&self) -> Result<Option<String>> {
+        let url = endpoint::join(&self.base, "rest/api/2/field");
+        let res = self
+            .internal
+            .get(url)
+            .authorized(&self.credentials)
+            .send_recorded(&self.stats)
+            .await?
+            .checked()
+            .await?;
+        let fields = res.json::<Vec<Field>>().await?;
+        Ok(fields
+            .into_iter()
+            .find(|field| {
+                field
+                    .schema
+                    .as_ref()
+                    .and_then(|schema| schema.custom.as_deref())
+                    == Some(ORGANIZATIONS_FIELD_TYPE)
+            })
+            .map(|field| field.id))
+    }
+
+    ///The customer who raised a service desk request, or None if the issue is not a request
+    pub async fn get_request_customer(&self, key: &str) -> Result<Option<Customer>> {
+        let url = endpoint::join(&self.base, &format!("rest/servicedeskapi/request/{key}"));
+        let res = self
+            .internal
+            .get(url)
+            .authorized(&self.credentials)
+            .send_recorded(&self.stats)
+            .await?;
+        if res.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        let request = res.checked().await?.json::<CustomerRequest>().await?;
+        Ok(Some(request.reporter))
+    }
+
+    ///The user the credentials belong to
+    pub async fn get_myself(&self) -> Result<Myself> {
+        let url = endpoint::join(&self.base, "rest/api/2/myself");
+        let res = self
+            .internal
+            .get(url)
+            .authorized(&self.credentials)
+            .send_recorded(&self.stats)
+            .await?
+            .checked()
+            .await?;
+        let myself = res.json::<Myself>().await?;
+        Ok(myself)
+    }
+
+    ///All permissions the current user holds in a project, keyed by permission key
+    pub async fn get_my_permissions(&self, project: &str) -> Result<HashMap<String, Permission>> {
+        let url = endpoint::join(&self.base, "rest/api/2/mypermissions");
+        let res = self
+            .internal
+            .get(url)
+            .query(&[("projectKey", project)])
+            .authorized(&self.credentials)
+            .send_recorded(&self.stats)
+            .await?
+            .checked()
+            .await?;
+        let permissions = res.json::<MyPermissionsResponse>().await?.permissions;
+        Ok(permissions)
+    }
+
+    ///Settings of the instance, such as whether time tracking is enabled
+    pub async fn get_configuration(&self) -> Result<JiraConfiguration> {
+        let url = endpoint::join(&self.base, "rest/api/2/configuration");
+        let res = self
+            .internal
+            .get(url)
+            .authorized(&self.credentials)
+            .send_recorded(&self.stats)
+            .await?
+            .checked()
+            .await?;
+        let configuration = res.json::<JiraConfiguration>().await?;
+        Ok(configuration)
+    }
+
+    ///Key of the user with a username, for Jira Server
+    pub async fn get_user_key(&self, username: &str) -> Result<String> {
+        let url = endpoint::join(&self.base, "rest/api/2/user");
+        let res = self
+            .internal
+            .get(url)
+            .query(&[("username", username)])
+            .authorized(&self.credentials)
+            .send_recorded(&self.stats)
+            .await?;
+        let key = res.json::<UserResponse>().await?.key;
+        Ok(key)
+    }
+
+    ///Whether a user with the given key or account ID, as configured, exists
+    pub async fn user_exists(&self, id: &str) -> Result<bool> {
+        let url = endpoint::join(&self.base, "rest/api/2/user");
+        let param = match self.user_identifier {
+            UserIdentifier::Key => "key",
+            UserIdentifier::AccountId => "accountId",
+        };
+        let res = self
+            .internal
+            .get(url)
+            .query(&[(param, id)])
+            .authorized(&self.credentials)
+            .send_recorded(&self.stats)
+            .await?;
+        if res.status() == StatusCode::NOT_FOUND {
+            return Ok(false);
+        }
+        res.checked().await?;
+        Ok(true)
+    }
+
+    ///Search for users by email address or display name, as user keys are not available on Jira Cloud
+    pub async fn find_users(&self, query: &str) -> Result<Vec<UserSearchResult>> {
+        let url = endpoint::join(&self.base, "rest/api/2/user/search");
+        let res = self
+            .internal
+            .get(url)
+            .query(&[("query", query)])
+            .authorized(&self.credentials)
+            .send_recorded(&self.stats)
+            .await?
+            .checked()
+            .await?;
+        let users = res.json::<Vec<UserSearchResult>>().await?;
+        Ok(users)
+    }
+
+    ///Work attributes defined in Tempo
+    pub async fn get_work_attributes(&self) -> Result<Vec<TempoWorkAttribute>> {
+        let url = endpoint::join(&self.base, "rest/tempo-core/1/work-attribute");
+        let attributes = self
+            .internal
+            .get(url)
+            .authorized(&self.credentials)
+            .send_recorded(&self.stats)
+            .await?
+            .checked()
+            .await?
+            .json::<Vec<TempoWorkAttribute>>()
+            .await?;
+        Ok(attributes)
+    }
+
+    ///The instance's priorities in its order, most urgent first
+    pub async fn get_priorities(&self) -> Result<Vec<Priority>> {
+        let url = endpoint::join(&self.base, "rest/api/2/priority");
+        let priorities = self
+            .internal
+            .get(url)
+            .authorized(&self.credentials)
+            .send_recorded(&self.stats)
+            .await?
+            .checked()
+            .await?
+            .json::<Vec<Priority>>()
+            .await?;
+        Ok(priorities)
+    }
+
+    ///Fail with an [`AuthFailure`] if JIRA refuses the credentials, before anything is attempted
+    ///with them
+    pub async fn check_credentials(&self) -> Result<()> {
+        let url = endpoint::join(&self.base, "rest/api/2/myself");
+        let res = self
+            .internal
+            .get(url)
+            .authorized(&self.credentials)
+            .send_recorded(&self.stats)
+            .await?;
+        if let Some(failure) = AuthFailure::from_response(&res) {
+            return Err(failure.into());
+        }
+        res.checked().await?;
+        Ok(())
+    }
+
+    ///Version and deployment type of the instance
+    pub async fn health_check(&self) -> Result<ServerInfo> {
+        let url = endpoint::join(&self.base, "rest/api/2/serverInfo");
+        let info = self
+            .internal
+            .get(url)
+            .authorized(&self.credentials)
+            .send_recorded(&self.stats)
+            .await?
+            .checked()
+            .await?
+            .json::<ServerInfo>()
+            .await?;
+        Ok(info)
+    }
+}
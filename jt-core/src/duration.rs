@@ -0,0 +1,288 @@
+use std::collections::HashMap;
+
+use chrono::{TimeDelta, Weekday};
+use serde::{Deserialize, Deserializer, Serialize};
+
+use crate::{
+    error::{JtError, Result},
+    locale::NumberFormat,
+};
+
+///Length of a day when interpreting durations in configuration, where no daily target applies
+pub const STANDARD_DAY: TimeDelta = TimeDelta::hours(8);
+
+///Parse a human-friendly duration such as `90m`, `1h30m`, `1.5h` or `0.5d`, with bare numbers
+///interpreted as minutes and days being `day_length` long
+pub fn parse_duration(
+    input: &str,
+    day_length: TimeDelta,
+    number_format: NumberFormat,
+) -> Result<TimeDelta> {
+    let original = input.trim();
+    if original.is_empty() {
+        return Err(JtError::Validation(String::from("No duration given")));
+    }
+    if let Ok(minutes) = number_format.parse_number(original) {
+        return to_delta(minutes * 60.0, original);
+    }
+    let input = number_format.normalize_duration(original).to_lowercase();
+
+    let mut seconds = 0.0;
+    let mut rest = input.as_str();
+    while !rest.is_empty() {
+        let number_len = rest
+            .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+            .unwrap_or(rest.len());
+        if number_len == 0 {
+            return Err(JtError::Validation(format!(
+                "Invalid duration \"{input}\", expected something like 90m, 1h30m or 1.5h"
+            )));
+        }
+        let value: f64 = rest[..number_len]
+            .parse()
+            .map_err(|_| JtError::Validation(format!("Invalid number in duration \"{input}\"")))?;
+        rest = rest[number_len..].trim_start();
+        let unit_len = rest
+            .find(|c: char| !c.is_alphabetic())
+            .unwrap_or(rest.len());
+        let unit_seconds = match &rest[..unit_len] {
+            "d" | "day" | "days" => day_length.num_seconds() as f64,
+            "h" | "hr" | "hrs" | "hour" | "hours" => 3600.0,
+            "m" | "min" | "mins" | "minute" | "minutes" => 60.0,
+            "" => {
+                return Err(JtError::Validation(format!(
+                    "Missing unit after {value} in duration \"{input}\""
+                )))
+            }
+            unit => {
+                return Err(JtError::Validation(format!(
+                    "Unknown unit \"{unit}\" in duration \"{input}\""
+                )))
+            }
+        };
+        seconds += value * unit_seconds;
+        rest = rest[unit_len..].trim_start();
+    }
+    to_delta(seconds, &input)
+}
+
+fn to_delta(seconds: f64, input: &str) -> Result<TimeDelta> {
+    if !seconds.is_finite() || seconds < 0.0 {
+        return Err(JtError::Validation(format!("Invalid duration \"{input}\"")));
+    }
+    let minutes = (seconds / 60.0).round() as i64;
+    Ok(TimeDelta::minutes(minutes))
+}
+
+pub fn format_duration(duration: TimeDelta) -> String {
+    let hours = duration.num_hours();
+    let minutes = duration.num_minutes() % 60;
+    match (hours, minutes) {
+        (0, minutes) => format!("{minutes}m"),
+        (hours, 0) => format!("{hours}h"),
+        (hours, minutes) => format!("{hours}h {minutes}m"),
+    }
+}
+
+///Split a duration into `parts` shares of whole minutes differing by at most a minute
+pub fn split_evenly(total: TimeDelta, parts: usize) -> Vec<TimeDelta> {
+    let minutes = total.num_minutes();
+    let parts = parts as i64;
+    (0..parts)
+        .map(|i| TimeDelta::minutes(minutes / parts + i64::from(i < minutes % parts)))
+        .collect()
+}
+
+///Which way to round durations to the configured granularity
+#[derive(Serialize, Deserialize, Clone, Copy, Default, PartialEq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum Rounding {
+    Up,
+    Down,
+    #[default]
+    Nearest,
+}
+
+///Round a duration to a whole number of `step`s, with halves rounding up when rounding to nearest
+pub fn round_duration(duration: TimeDelta, step: TimeDelta, rounding: Rounding) -> TimeDelta {
+    let step_minutes = step.num_minutes();
+    if step_minutes <= 0 {
+        return duration;
+    }
+    let minutes = duration.num_minutes();
+    let steps = match rounding {
+        Rounding::Up => (minutes + step_minutes - 1).div_euclid(step_minutes),
+        Rounding::Down => minutes.div_euclid(step_minutes),
+        Rounding::Nearest => (minutes + step_minutes / 2).div_euclid(step_minutes),
+    };
+    TimeDelta::minutes(steps * step_minutes)
+}
+
+///A number of minutes given either as an integer or a duration string
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Minutes {
+    Integer(u64),
+    Duration(String),
+}
+
+impl Minutes {
+    fn minutes(self) -> Result<u64> {
+        match self {
+            Minutes::Integer(minutes) => Ok(minutes),
+            Minutes::Duration(duration) => {
+                parse_duration(&duration, STANDARD_DAY, NumberFormat::default())
+                    .map(|delta| delta.num_minutes() as u64)
+            }
+        }
+    }
+}
+
+///Deserialize a number of minutes given either as an integer or a duration string
+pub fn deserialize_minutes<'de, D>(deserializer: D) -> Result<Option<u64>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Option::<Minutes>::deserialize(deserializer)?
+        .map(Minutes::minutes)
+        .transpose()
+        .map_err(serde::de::Error::custom)
+}
+
+///Deserialize a map of weekday names, eg `fri`, to numbers of minutes
+pub fn deserialize_weekday_minutes<'de, D>(
+    deserializer: D,
+) -> Result<HashMap<Weekday, u64>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    HashMap::<Weekday, Minutes>::deserialize(deserializer)?
+        .into_iter()
+        .map(|(weekday, minutes)| Ok((weekday, minutes.minutes()?)))
+        .collect::<Result<_>>()
+        .map_err(serde::de::Error::custom)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(input: &str) -> Result<i64> {
+        parse_duration(input, TimeDelta::hours(7), NumberFormat::default())
+            .map(|duration| duration.num_minutes())
+    }
+
+    #[test]
+    fn parses_units_and_bare_minutes() {
+        assert_eq!(parse("90m").unwrap(), 90);
+        assert_eq!(parse("1h30m").unwrap(), 90);
+        assert_eq!(parse(" 1h 30 mins ").unwrap(), 90);
+        assert_eq!(parse("1.5h").unwrap(), 90);
+        assert_eq!(parse("0.5d").unwrap(), 210);
+        assert_eq!(parse("1 day").unwrap(), 420);
+        assert_eq!(parse("45").unwrap(), 45);
+        assert_eq!(parse("0").unwrap(), 0);
+        assert_eq!(parse("0h").unwrap(), 0);
+    }
+
+    #[test]
+    fn rounds_to_whole_minutes() {
+        assert_eq!(parse("0.01h").unwrap(), 1);
+        assert_eq!(parse("0.005h").unwrap(), 0);
+        assert_eq!(parse("2.5").unwrap(), 3);
+    }
+
+    #[test]
+    fn rejects_durations_it_cannot_read() {
+        for input in ["", "  ", "h", "1x", "1h30", "-1h", "-5", "1..5h", "1,5h"] {
+            assert!(
+                matches!(parse(input), Err(JtError::Validation(_))),
+                "{input:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn reads_decimal_commas_in_the_locale() {
+        let de = NumberFormat::for_locale("de_DE");
+        let parse = |input| {
+            parse_duration(input, STANDARD_DAY, de)
+                .unwrap()
+                .num_minutes()
+        };
+        assert_eq!(parse("1,5h"), 90);
+        assert_eq!(parse("1.5h"), 90);
+        assert_eq!(parse("1,5"), 2);
+    }
+
+    #[test]
+    fn formats_hours_and_minutes() {
+        assert_eq!(format_duration(TimeDelta::zero()), "0m");
+        assert_eq!(format_duration(TimeDelta::minutes(45)), "45m");
+        assert_eq!(format_duration(TimeDelta::minutes(120)), "2h");
+        assert_eq!(format_duration(TimeDelta::minutes(450)), "7h 30m");
+    }
+
+    #[test]
+    fn reads_minutes_from_numbers_or_durations() {
+        #[derive(Deserialize)]
+        struct Config {
+            #[serde(default, deserialize_with = "deserialize_minutes")]
+            minutes: Option<u64>,
+        }
+        let minutes = |toml: &str| toml::from_str::<Config>(toml).map(|config| config.minutes);
+        assert_eq!(minutes("minutes = 90").unwrap(), Some(90));
+        assert_eq!(minutes("minutes = \"7h30m\"").unwrap(), Some(450));
+        assert_eq!(minutes("").unwrap(), None);
+        assert!(minutes("minutes = \"soon\"").is_err());
+    }
+
+    #[test]
+    fn rounds_to_the_granularity_each_way() {
+        let round = |minutes, rounding| {
+            round_duration(
+                TimeDelta::minutes(minutes),
+                TimeDelta::minutes(15),
+                rounding,
+            )
+            .num_minutes()
+        };
+        assert_eq!(round(0, Rounding::Up), 0);
+        assert_eq!(round(1, Rounding::Up), 15);
+        assert_eq!(round(15, Rounding::Up), 15);
+        assert_eq!(round(14, Rounding::Down), 0);
+        assert_eq!(round(29, Rounding::Down), 15);
+        assert_eq!(round(7, Rounding::Nearest), 0);
+        assert_eq!(round(8, Rounding::Nearest), 15);
+        assert_eq!(round(52, Rounding::Nearest), 45);
+        assert_eq!(round(53, Rounding::Nearest), 60);
+    }
+
+    #[test]
+    fn leaves_durations_alone_without_a_step() {
+        let duration = TimeDelta::minutes(37);
+        assert_eq!(
+            round_duration(duration, TimeDelta::zero(), Rounding::Up),
+            duration
+        );
+        assert_eq!(
+            round_duration(duration, TimeDelta::seconds(30), Rounding::Nearest),
+            duration
+        );
+    }
+
+    #[test]
+    fn splits_leftover_minutes_between_the_first_shares() {
+        let minutes = |total, parts| -> Vec<i64> {
+            split_evenly(TimeDelta::minutes(total), parts)
+                .iter()
+                .map(TimeDelta::num_minutes)
+                .collect()
+        };
+        assert_eq!(minutes(480, 3), [160, 160, 160]);
+        assert_eq!(minutes(100, 3), [34, 33, 33]);
+        assert_eq!(minutes(2, 3), [1, 1, 0]);
+        assert_eq!(minutes(0, 2), [0, 0]);
+        assert!(minutes(60, 0).is_empty());
+    }
+}
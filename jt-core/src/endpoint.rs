@@ -0,0 +1,122 @@
+use reqwest::Url;
+
+use crate::error::{JtError, Result};
+
+///Base URL of a Jira instance, with any context root (eg `/jira`) appended to the configured
+///endpoint unless the endpoint already ends with it
+pub fn base_url(endpoint: &Url, context_root: Option<&str>) -> Result<Url> {
+    if !matches!(endpoint.scheme(), "http" | "https") {
+        return Err(JtError::Config(format!(
+            "Instance URL {endpoint} must use http or https"
+        )));
+    }
+    if endpoint.cannot_be_a_base() || endpoint.host().is_none() {
+        return Err(JtError::Config(format!(
+            "Instance URL {endpoint} has no host"
+        )));
+    }
+    if endpoint.query().is_some() || endpoint.fragment().is_some() {
+        return Err(JtError::Config(format!(
+            "Instance URL {endpoint} must not contain a query or fragment"
+        )));
+    }
+
+    let mut segments = path_segments(endpoint.path());
+    let root = context_root.map(path_segments).unwrap_or_default();
+    if !segments.ends_with(&root) {
+        segments.extend(root);
+    }
+
+    let mut base = endpoint.clone();
+    base.set_path(&format!("/{}", segments.join("/")));
+    if !segments.is_empty() {
+        base.path_segments_mut().unwrap().push("");
+    }
+    Ok(base)
+}
+
+///Append a relative API path such as `rest/api/2/myself` to a base URL, keeping any context
+///root regardless of whether the base ends with a slash
+pub fn join(base: &Url, path: &str) -> Url {
+    let mut url = base.clone();
+    url.path_segments_mut()
+        .expect("Instance URL cannot be a base")
+        .pop_if_empty()
+        .extend(path.split('/').filter(|segment| !segment.is_empty()));
+    url
+}
+
+fn path_segments(path: &str) -> Vec<String> {
+    path.split('/')
+        .filter(|segment| !segment.is_empty())
+        .map(str::to_owned)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn url(s: &str) -> Url {
+        Url::parse(s).unwrap()
+    }
+
+    #[test]
+    fn joins_onto_root() {
+        let base = base_url(&url("https://jira.company.com"), None).unwrap();
+        assert_eq!(
+            join(&base, "rest/api/2/myself").as_str(),
+            "https://jira.company.com/rest/api/2/myself"
+        );
+    }
+
+    #[test]
+    fn keeps_nested_path_with_or_without_trailing_slash() {
+        for endpoint in [
+            "https://tools.company.com/jira",
+            "https://tools.company.com/jira/",
+        ] {
+            let base = base_url(&url(endpoint), None).unwrap();
+            assert_eq!(base.as_str(), "https://tools.company.com/jira/");
+            assert_eq!(
+                join(&base, "rest/api/2/serverInfo").as_str(),
+                "https://tools.company.com/jira/rest/api/2/serverInfo"
+            );
+        }
+    }
+
+    #[test]
+    fn join_does_not_depend_on_normalisation() {
+        assert_eq!(
+            join(&url("https://tools.company.com/a/b"), "browse/PROJ-1").as_str(),
+            "https://tools.company.com/a/b/browse/PROJ-1"
+        );
+        assert_eq!(
+            join(&url("https://tools.company.com/a/b/"), "/browse/PROJ-1").as_str(),
+            "https://tools.company.com/a/b/browse/PROJ-1"
+        );
+    }
+
+    #[test]
+    fn appends_context_root() {
+        let base = base_url(&url("https://tools.company.com"), Some("/apps/jira/")).unwrap();
+        assert_eq!(base.as_str(), "https://tools.company.com/apps/jira/");
+        assert_eq!(
+            join(&base, "rest/tempo-timesheets/4/worklogs").as_str(),
+            "https://tools.company.com/apps/jira/rest/tempo-timesheets/4/worklogs"
+        );
+    }
+
+    #[test]
+    fn does_not_repeat_context_root_already_in_endpoint() {
+        let base = base_url(&url("https://tools.company.com/jira"), Some("jira")).unwrap();
+        assert_eq!(base.as_str(), "https://tools.company.com/jira/");
+    }
+
+    #[test]
+    fn rejects_unusable_endpoints() {
+        assert!(base_url(&url("ftp://tools.company.com/jira"), None).is_err());
+        assert!(base_url(&url("https://tools.company.com/jira?x=1"), None).is_err());
+        assert!(base_url(&url("mailto:jira@company.com"), None).is_err());
+    }
+}
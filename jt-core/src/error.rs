@@ -0,0 +1,139 @@
+use std::{fmt::Display, io, path::PathBuf};
+
+use reqwest::{StatusCode, Url};
+use serde_json::Value;
+
+use crate::client::AuthFailure;
+
+pub type Result<T, E = JtError> = std::result::Result<T, E>;
+
+///Everything that can go wrong using the client or planning a week, by the kind of failure so that
+///callers can react to each differently
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum JtError {
+    ///Options the client was set up with that cannot be used, such as an invalid proxy or instance
+    ///URL
+    Config(String),
+    ///JIRA refusing the credentials
+    Auth(AuthFailure),
+    ///JIRA or Tempo answering a request with an error status
+    Api {
+        status: StatusCode,
+        url: Url,
+        ///The response body as sent, usually JSON listing what was wrong
+        body: String,
+    },
+    ///Input that cannot be understood, such as a malformed duration, week or plan entry
+    Validation(String),
+    ///A request that could not be sent or whose response could not be read, the reason being its
+    ///source
+    Http(reqwest::Error),
+    ///A file that could not be read or written, the reason being its source
+    Io { path: PathBuf, source: io::Error },
+}
+
+impl JtError {
+    ///The error for a request answered with an error status, reading the body for the details
+    pub(crate) async fn from_response(res: reqwest::Response) -> JtError {
+        let (status, url) = (res.status(), res.url().clone());
+        JtError::Api {
+            status,
+            url,
+            body: res.text().await.unwrap_or_default(),
+        }
+    }
+}
+
+impl Display for JtError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            JtError::Config(message) | JtError::Validation(message) => write!(f, "{message}"),
+            JtError::Auth(failure) => write!(f, "{failure}"),
+            JtError::Api { status, url, body } => match messages(body) {
+                Some(messages) => write!(f, "{url} returned {status}: {messages}"),
+                None => write!(f, "{url} returned {status}"),
+            },
+            JtError::Http(e) => match (e.url(), e.is_decode()) {
+                (Some(url), true) => write!(f, "Unable to read the response from {url}"),
+                (Some(url), false) => write!(f, "Request to {url} failed"),
+                (None, _) => write!(f, "Request failed"),
+            },
+            JtError::Io { path, .. } => write!(f, "Unable to access {}", path.display()),
+        }
+    }
+}
+
+impl std::error::Error for JtError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            JtError::Http(e) => Some(e),
+            JtError::Io { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+impl From<reqwest::Error> for JtError {
+    fn from(e: reqwest::Error) -> Self {
+        JtError::Http(e)
+    }
+}
+
+impl From<AuthFailure> for JtError {
+    fn from(failure: AuthFailure) -> Self {
+        JtError::Auth(failure)
+    }
+}
+
+///The messages in a JIRA or Tempo error body joined into one line, if it has any
+fn messages(body: &str) -> Option<String> {
+    let body: Value = serde_json::from_str(body).ok()?;
+    let mut messages: Vec<String> = body
+        .get("errorMessages")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(|message| message.as_str().map(String::from))
+        .collect();
+    match body.get("errors") {
+        Some(Value::Array(errors)) => messages.extend(
+            errors
+                .iter()
+                .filter_map(|error| error.get("message")?.as_str().map(String::from)),
+        ),
+        Some(Value::Object(fields)) => messages.extend(
+            fields
+                .iter()
+                .filter_map(|(field, message)| Some(format!("{field}: {}", message.as_str()?))),
+        ),
+        _ => {}
+    }
+    if let Some(message) = body.get("message").and_then(Value::as_str) {
+        messages.push(message.to_owned());
+    }
+    (!messages.is_empty()).then(|| messages.join("; "))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+
+    use super::*;
+
+    #[test]
+    fn exposes_the_underlying_error_as_its_source() {
+        let io = JtError::Io {
+            path: PathBuf::from("plan.toml"),
+            source: io::Error::from(io::ErrorKind::NotFound),
+        };
+        let source = io.source().unwrap().downcast_ref::<io::Error>().unwrap();
+        assert_eq!(source.kind(), io::ErrorKind::NotFound);
+
+        let http = JtError::from(reqwest::Client::new().get("not a url").build().unwrap_err());
+        assert!(http.source().unwrap().is::<reqwest::Error>());
+        assert!(JtError::Validation(String::from("Unknown task"))
+            .source()
+            .is_none());
+    }
+}
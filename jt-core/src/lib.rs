@@ -0,0 +1,98 @@
+//!The JIRA and Tempo client and the week-planning logic behind `jt`, for tools that log time
+//!without going through its command line.
+//!
+//![`client::JtClient`] reads issues and worklogs and creates, submits and reviews timesheets:
+//!
+//!```no_run
+//!use jt_core::{
+//!    client::{Credentials, JtClient},
+//!    options::{HttpOptions, UserIdentifier},
+//!};
+//!
+//!# async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//!let client = JtClient::new(
+//!    &Credentials::Token(String::from("token")),
+//!    "https://jira.example.com".parse()?,
+//!    false,
+//!    UserIdentifier::Key,
+//!    &HttpOptions::default(),
+//!)?;
+//!let issue = client.get_issue("PROJ-1").await?;
+//!println!("{}: {:?}", issue.key, issue.fields.get("summary"));
+//!# Ok(())
+//!# }
+//!```
+//!
+//![`plan`] describes the tasks each day's time goes to and divides the daily target between them,
+//!entries without a time sharing whatever is left:
+//!
+//!```
+//!use chrono::TimeDelta;
+//!use jt_core::{
+//!    locale::NumberFormat,
+//!    plan::{day_time_spent, PlanEntry},
+//!};
+//!
+//!let entry = |task: &str, minutes| PlanEntry {
+//!    task: task.to_owned(),
+//!    minutes,
+//!    duration: None,
+//!    percent: None,
+//!    comment: None,
+//!    tags: Vec::new(),
+//!};
+//!let times = day_time_spent(
+//!    &[entry("PROJ-1", Some(90)), entry("PROJ-2", None), entry("PROJ-3", None)],
+//!    TimeDelta::hours(8),
+//!    TimeDelta::zero(),
+//!    NumberFormat::default(),
+//!)?;
+//!assert_eq!(times[1], TimeDelta::minutes(195));
+//!# Ok::<(), jt_core::error::JtError>(())
+//!```
+//!
+//!Failures are [`error::JtError`]s, telling apart bad configuration, refused credentials, errors
+//!reported by JIRA or Tempo along with the body they sent, and input that cannot be understood:
+//!
+//!```no_run
+//!# use jt_core::client::JtClient;
+//!use jt_core::error::JtError;
+//!
+//!# async fn example(client: JtClient) -> Result<(), JtError> {
+//!match client.get_issue("PROJ-1").await {
+//!    Ok(issue) => println!("{}", issue.key),
+//!    Err(JtError::Api { status, .. }) if status == 404 => println!("No such issue"),
+//!    Err(e) => return Err(e),
+//!}
+//!# Ok(())
+//!# }
+//!```
+
+///Counts of the requests made over a run, by endpoint
+pub mod api_stats;
+///How far logged time has drifted from the shares a plan asks for
+pub mod balance;
+///Requests to the JIRA and Tempo REST APIs
+pub mod client;
+///Parsing, formatting, splitting and rounding amounts of time
+pub mod duration;
+///Addresses of JIRA instances and the paths below them
+pub mod endpoint;
+///Failures, by the kind of failure
+pub mod error;
+///Whether numbers are written with a decimal point or comma
+pub mod locale;
+///How the client reaches JIRA and what it sets on worklogs
+pub mod options;
+///Tasks to log time against on each day, and the time each gets
+pub mod plan;
+///Retrying requests that fail transiently
+pub mod retry;
+///Logging of each request and response, enabled through the `jt_core::trace` log target: debug
+///level shows methods, URLs, statuses, timings and headers with credentials redacted, trace level
+///adds bodies
+pub mod trace;
+///Days, weeks and months in the user's timezone
+pub mod week;
+///Dividing each day's time between tasks, from allocations, plans or evenly
+pub mod work;
@@ -0,0 +1,145 @@
+use std::env;
+
+use crate::error::{JtError, Result};
+
+///Languages that write decimals with a comma, eg `1,5`
+const COMMA_DECIMAL_LANGUAGES: [&str; 28] = [
+    "bg", "ca", "cs", "da", "de", "el", "es", "et", "eu", "fi", "fr", "gl", "hr", "hu", "id", "is",
+    "it", "lt", "lv", "nb", "nl", "nn", "no", "pl", "pt", "ro", "ru", "sk",
+];
+///Regions that write decimals with a point despite their language usually using a comma
+const POINT_DECIMAL_REGIONS: [&str; 2] = ["ch", "li"];
+///Environment variables consulted, in order, when no locale is configured
+const LOCALE_ENV_VARS: [&str; 3] = ["LC_ALL", "LC_NUMERIC", "LANG"];
+
+///How numbers are written in a locale
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct NumberFormat {
+    pub decimal: char,
+    pub grouping: char,
+}
+
+impl Default for NumberFormat {
+    fn default() -> Self {
+        NumberFormat {
+            decimal: '.',
+            grouping: ',',
+        }
+    }
+}
+
+impl NumberFormat {
+    ///Number format for a locale identifier such as `de-DE`, `fr_FR.UTF-8` or `en`
+    pub fn for_locale(locale: &str) -> NumberFormat {
+        let locale = locale.split('.').next().unwrap_or(locale).to_lowercase();
+        let mut parts = locale.split(['-', '_']);
+        let language = parts.next().unwrap_or_default();
+        let region = parts.next().unwrap_or_default();
+        if COMMA_DECIMAL_LANGUAGES.contains(&language) && !POINT_DECIMAL_REGIONS.contains(&region) {
+            let grouping = match language {
+                "fr" | "cs" | "sk" | "pl" | "ru" | "fi" | "nb" | "nn" | "no" | "bg" | "et" => ' ',
+                _ => '.',
+            };
+            NumberFormat {
+                decimal: ',',
+                grouping,
+            }
+        } else {
+            NumberFormat::default()
+        }
+    }
+
+    ///Number format for the configured locale, or the environment's if none is configured
+    pub fn resolve(configured: Option<&str>) -> NumberFormat {
+        let from_env = || {
+            LOCALE_ENV_VARS
+                .iter()
+                .filter_map(|var| env::var(var).ok())
+                .find(|value| !value.is_empty() && value != "C" && value != "POSIX")
+        };
+        match configured.map(str::to_owned).or_else(from_env) {
+            Some(locale) => NumberFormat::for_locale(&locale),
+            None => NumberFormat::default(),
+        }
+    }
+
+    ///Rewrite a duration so that its decimal separator is a point; grouping separators are
+    ///meaningless in durations so a point is always accepted as a decimal too
+    pub fn normalize_duration(&self, input: &str) -> String {
+        input.replace(self.decimal, ".")
+    }
+
+    ///Parse a number written in this format, accepting grouping separators only between groups of
+    ///three digits so that a misplaced decimal separator, like `1,5` where the decimal is a point,
+    ///is rejected rather than read as fifteen
+    pub fn parse_number(&self, input: &str) -> Result<f64> {
+        let input = input.trim();
+        let invalid = || JtError::Validation(format!("Invalid number \"{input}\""));
+        let (whole, fraction) = match input.split_once(self.decimal) {
+            Some((whole, fraction)) => (whole, Some(fraction)),
+            None => (input, None),
+        };
+        let groups: Vec<&str> = whole.split([self.grouping, '\u{a0}']).collect();
+        if let [first, rest @ ..] = groups.as_slice() {
+            let leading = first.trim_start_matches(['-', '+']);
+            let digits = |group: &str| group.chars().all(|c| c.is_ascii_digit());
+            let grouped = (1..=3).contains(&leading.len())
+                && digits(leading)
+                && rest.iter().all(|group| group.len() == 3 && digits(group));
+            if !rest.is_empty() && !grouped {
+                return Err(invalid());
+            }
+        }
+        let mut normalized = groups.concat();
+        if let Some(fraction) = fraction {
+            normalized.push('.');
+            normalized.push_str(fraction);
+        }
+        normalized.parse().map_err(|_| invalid())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_numbers_with_a_decimal_point() {
+        let en = NumberFormat::for_locale("en_GB.UTF-8");
+        assert_eq!(en.parse_number("1.5").unwrap(), 1.5);
+        assert_eq!(en.parse_number(" 90 ").unwrap(), 90.0);
+        assert_eq!(en.parse_number("1,500").unwrap(), 1500.0);
+        assert_eq!(en.parse_number("12,345,678.25").unwrap(), 12345678.25);
+        for ambiguous in [
+            "1,5",
+            "1,50",
+            "1,5000",
+            "1234,567",
+            ",500",
+            "1,500.2,5",
+            "1.2.3",
+        ] {
+            assert!(en.parse_number(ambiguous).is_err(), "{ambiguous}");
+        }
+    }
+
+    #[test]
+    fn parses_numbers_with_a_decimal_comma() {
+        let de = NumberFormat::for_locale("de-DE");
+        assert_eq!(de.parse_number("1,5").unwrap(), 1.5);
+        assert_eq!(de.parse_number("1.500").unwrap(), 1500.0);
+        assert_eq!(de.parse_number("1.500,75").unwrap(), 1500.75);
+        assert_eq!(de.parse_number("-2,25").unwrap(), -2.25);
+        for ambiguous in ["1.5", "1.50", "12.34,5", "1.500,5.0", "1,5,0"] {
+            assert!(de.parse_number(ambiguous).is_err(), "{ambiguous}");
+        }
+    }
+
+    #[test]
+    fn groups_with_spaces_in_french() {
+        let fr = NumberFormat::for_locale("fr_FR");
+        assert_eq!(fr.parse_number("1 500,5").unwrap(), 1500.5);
+        assert_eq!(fr.parse_number("1\u{a0}500").unwrap(), 1500.0);
+        assert!(fr.parse_number("1 5").is_err());
+    }
+}
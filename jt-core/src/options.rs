@@ -0,0 +1,151 @@
+use std::{path::PathBuf, str::FromStr};
+
+use chrono::TimeDelta;
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    duration::{format_duration, parse_duration, STANDARD_DAY},
+    error::{JtError, Result},
+    locale::NumberFormat,
+};
+
+///How `worker` and `reviewer` identify users: Jira Server uses user keys, Jira Cloud account IDs
+#[derive(Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum UserIdentifier {
+    #[default]
+    Key,
+    AccountId,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(try_from = "u8", into = "u8")]
+pub enum TempoVersion {
+    V3,
+    V4,
+}
+
+impl TryFrom<u8> for TempoVersion {
+    type Error = String;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            3 => Ok(TempoVersion::V3),
+            4 => Ok(TempoVersion::V4),
+            other => Err(format!("unsupported Tempo API version {other}")),
+        }
+    }
+}
+
+impl From<TempoVersion> for u8 {
+    fn from(value: TempoVersion) -> Self {
+        match value {
+            TempoVersion::V3 => 3,
+            TempoVersion::V4 => 4,
+        }
+    }
+}
+
+///What happens to an issue's remaining estimate when work is logged against it
+#[derive(Serialize, Deserialize, Clone, Copy, Default, PartialEq, Debug)]
+#[serde(try_from = "String", into = "String")]
+pub enum RemainingEstimate {
+    ///Reduce it by the time logged, as Tempo does by default
+    #[default]
+    Auto,
+    ///Keep it as it was
+    Leave,
+    ///Set it to a fixed amount
+    Set(TimeDelta),
+}
+
+impl FromStr for RemainingEstimate {
+    type Err = JtError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "auto" => Ok(RemainingEstimate::Auto),
+            "leave" => Ok(RemainingEstimate::Leave),
+            _ => parse_duration(s, STANDARD_DAY, NumberFormat::default())
+                .map(RemainingEstimate::Set)
+                .map_err(|e| {
+                    JtError::Validation(format!(
+                        "Expected auto, leave or a duration, not \"{s}\": {e}"
+                    ))
+                }),
+        }
+    }
+}
+
+impl TryFrom<String> for RemainingEstimate {
+    type Error = JtError;
+
+    fn try_from(value: String) -> Result<Self> {
+        value.parse()
+    }
+}
+
+impl From<RemainingEstimate> for String {
+    fn from(value: RemainingEstimate) -> Self {
+        match value {
+            RemainingEstimate::Auto => String::from("auto"),
+            RemainingEstimate::Leave => String::from("leave"),
+            RemainingEstimate::Set(estimate) => format_duration(estimate).replace(' ', ""),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct WorkAttribute {
+    pub key: String,
+    pub name: String,
+    pub work_attribute_id: u64,
+    pub value: String,
+}
+
+///A restriction of who can see a worklog, in the form JIRA expects
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Visibility {
+    #[serde(rename = "type")]
+    pub kind: VisibilityKind,
+    ///Name of the group or project role able to see the worklog
+    pub value: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum VisibilityKind {
+    Group,
+    Role,
+}
+
+///How requests reach the Jira instance
+#[derive(Default)]
+pub struct HttpOptions {
+    pub proxy: Option<Url>,
+    pub ca_certificate: Option<PathBuf>,
+    pub accept_invalid_certs: bool,
+}
+
+///Settings trading completeness of the task list for startup time on very large instances
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Performance {
+    ///Most issues retrieved by the task search, and so offered in the task picker
+    #[serde(default = "default_max_tasks")]
+    pub max_tasks: u32,
+    ///Most requests made to JIRA at once
+    #[serde(default = "default_concurrency")]
+    pub concurrency: usize,
+    ///Fields retrieved for each task in addition to those jt reads itself
+    #[serde(default)]
+    pub fields: Vec<String>,
+}
+
+fn default_max_tasks() -> u32 {
+    100
+}
+
+fn default_concurrency() -> usize {
+    8
+}
@@ -0,0 +1,200 @@
+use std::{fmt::Display, fs, path::Path, str::FromStr};
+
+use chrono::{NaiveDate, TimeDelta, Weekday};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    duration::parse_duration,
+    error::{JtError, Result},
+    locale::NumberFormat,
+};
+
+///A declarative description of a week's work, used to fill a timesheet without prompting
+#[derive(Serialize, Deserialize, Default)]
+pub struct Plan {
+    #[serde(default)]
+    pub days: Vec<PlanDay>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct PlanDay {
+    pub day: DaySpec,
+    #[serde(default)]
+    pub entries: Vec<PlanEntry>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct PlanEntry {
+    ///Issue key, static task key or static task group name
+    pub task: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub minutes: Option<u64>,
+    ///Duration such as `1h30m`, as an alternative to a number of minutes
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub duration: Option<String>,
+    ///Share of the daily target, as an alternative to a fixed number of minutes
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub percent: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub comment: Option<String>,
+    ///Tags, without their leading `#`, as mapped in the configuration
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+}
+
+impl PlanEntry {
+    ///Time given for the entry, or None if it takes whatever remains of the day
+    pub fn time_spent(
+        &self,
+        target_per_day: TimeDelta,
+        number_format: NumberFormat,
+    ) -> Result<Option<TimeDelta>> {
+        match (self.minutes, &self.duration, self.percent) {
+            (None, None, None) => Ok(None),
+            (Some(minutes), None, None) => Ok(Some(TimeDelta::minutes(minutes as i64))),
+            (None, Some(duration), None) => {
+                parse_duration(duration, target_per_day, number_format).map(Some)
+            }
+            (None, None, Some(percent)) => {
+                let seconds = target_per_day.num_seconds() as f64 * percent / 100.0;
+                Ok(Some(TimeDelta::seconds(seconds.round() as i64)))
+            }
+            _ => Err(JtError::Validation(format!(
+                "Plan entry for {} must specify at most one of minutes, duration or percent",
+                self.task
+            ))),
+        }
+    }
+}
+
+///Time spent on each of a day's entries, those without a time sharing equally whatever remains of
+///the target once the others and any time `already_spent` are taken away
+pub fn day_time_spent(
+    entries: &[PlanEntry],
+    target_per_day: TimeDelta,
+    already_spent: TimeDelta,
+    number_format: NumberFormat,
+) -> Result<Vec<TimeDelta>> {
+    let given = entries
+        .iter()
+        .map(|entry| entry.time_spent(target_per_day, number_format))
+        .collect::<Result<Vec<_>>>()?;
+    let rest_count = given.iter().filter(|time| time.is_none()).count() as i32;
+    let rest = if rest_count == 0 {
+        TimeDelta::zero()
+    } else {
+        let fixed: TimeDelta = given.iter().flatten().sum();
+        (target_per_day - already_spent - fixed).max(TimeDelta::zero()) / rest_count
+    };
+    Ok(given.into_iter().map(|time| time.unwrap_or(rest)).collect())
+}
+
+///A day in a plan, either a weekday relative to the week being filled or a specific date
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum DaySpec {
+    Weekday(Weekday),
+    Date(NaiveDate),
+}
+
+impl DaySpec {
+    pub fn resolve(&self, monday: NaiveDate) -> NaiveDate {
+        match self {
+            DaySpec::Weekday(weekday) => {
+                monday + TimeDelta::days(weekday.num_days_from_monday() as i64)
+            }
+            DaySpec::Date(date) => *date,
+        }
+    }
+}
+
+impl FromStr for DaySpec {
+    type Err = JtError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        if let Ok(weekday) = s.parse::<Weekday>() {
+            return Ok(DaySpec::Weekday(weekday));
+        }
+        NaiveDate::from_str(s).map(DaySpec::Date).map_err(|_| {
+            JtError::Validation(format!(
+                "\"{s}\" is neither a weekday nor a date (YYYY-MM-DD)"
+            ))
+        })
+    }
+}
+
+impl Display for DaySpec {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DaySpec::Weekday(weekday) => write!(f, "{}", weekday.to_string().to_lowercase()),
+            DaySpec::Date(date) => write!(f, "{date}"),
+        }
+    }
+}
+
+impl Serialize for DaySpec {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for DaySpec {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let buf = String::deserialize(deserializer)?;
+        buf.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+///Weekdays given as a range such as `mon..fri`, a list such as `mon,wed,fri`, or both
+#[derive(Clone)]
+pub struct Weekdays(pub Vec<Weekday>);
+
+impl FromStr for Weekdays {
+    type Err = JtError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let parse = |name: &str| {
+            name.trim()
+                .parse::<Weekday>()
+                .map_err(|_| JtError::Validation(format!("\"{name}\" is not a weekday")))
+        };
+        let mut weekdays = Vec::new();
+        for part in s.split(',') {
+            match part.split_once("..") {
+                Some((first, last)) => {
+                    let (mut day, last) = (parse(first)?, parse(last)?);
+                    weekdays.push(day);
+                    while day != last {
+                        day = day.succ();
+                        weekdays.push(day);
+                    }
+                }
+                None => weekdays.push(parse(part)?),
+            }
+        }
+        Ok(Weekdays(weekdays))
+    }
+}
+
+pub fn load_plan(path: &Path) -> Result<Plan> {
+    let content = fs::read_to_string(path).map_err(|source| JtError::Io {
+        path: path.to_owned(),
+        source,
+    })?;
+    toml::from_str(&content)
+        .map_err(|e| JtError::Validation(format!("Invalid plan file {}: {e}", path.display())))
+}
+
+pub fn write_plan(plan: &Plan, path: &Path) -> Result<()> {
+    let contents = toml::to_string_pretty(plan)
+        .map_err(|e| JtError::Validation(format!("Unable to write plan: {e}")))?;
+    fs::write(path, contents).map_err(|source| JtError::Io {
+        path: path.to_owned(),
+        source,
+    })
+}
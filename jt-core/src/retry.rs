@@ -0,0 +1,128 @@
+use std::time::Duration;
+
+use reqwest::{header::RETRY_AFTER, Client, Method, Request, Response, StatusCode};
+
+///Attempts made at a request before giving up on it
+const MAX_ATTEMPTS: u32 = 4;
+///Wait before the first retry, doubled for each one after
+const INITIAL_BACKOFF: Duration = Duration::from_millis(500);
+///Longest wait asked for by a Retry-After header that will be honoured
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+///Execute a request, retrying with exponential backoff when it fails in a way that is likely to
+///be transient. Requests that are not idempotent, such as creating a worklog, are only retried
+///when the server cannot have acted on them, that is when the connection could not be made or
+///the request was turned away by rate limiting, so that a retry never logs work twice.
+pub async fn execute(client: &Client, request: Request) -> reqwest::Result<Response> {
+    let idempotent = matches!(
+        *request.method(),
+        Method::GET | Method::HEAD | Method::PUT | Method::DELETE
+    );
+    let mut backoff = INITIAL_BACKOFF;
+    let mut attempt = 1;
+    loop {
+        let retry = request.try_clone().filter(|_| attempt < MAX_ATTEMPTS);
+        let Some(next) = retry else {
+            return client.execute(request).await;
+        };
+        let wait = match client.execute(next).await {
+            Ok(res) if should_retry(res.status(), idempotent) => {
+                log::warn!(
+                    "{} {} returned {}",
+                    request.method(),
+                    request.url(),
+                    res.status()
+                );
+                retry_after(&res).unwrap_or(backoff)
+            }
+            Err(e) if e.is_connect() || (idempotent && e.is_timeout()) => {
+                log::warn!("{} {} failed: {e}", request.method(), request.url());
+                backoff
+            }
+            result => return result,
+        };
+        log::warn!(
+            "Retrying in {wait:?}, attempt {} of {MAX_ATTEMPTS}",
+            attempt + 1
+        );
+        tokio::time::sleep(wait).await;
+        backoff *= 2;
+        attempt += 1;
+    }
+}
+
+///Whether a response is worth retrying. A 503 or gateway error may come from a proxy after the
+///request has already been acted on, so only rate limiting is retried for requests that are not
+///idempotent.
+fn should_retry(status: StatusCode, idempotent: bool) -> bool {
+    match status {
+        StatusCode::TOO_MANY_REQUESTS => true,
+        StatusCode::SERVICE_UNAVAILABLE
+        | StatusCode::INTERNAL_SERVER_ERROR
+        | StatusCode::BAD_GATEWAY
+        | StatusCode::GATEWAY_TIMEOUT => idempotent,
+        _ => false,
+    }
+}
+
+///How long the server asked to be left alone for, when given in seconds
+fn retry_after(res: &Response) -> Option<Duration> {
+    let seconds: u64 = res
+        .headers()
+        .get(RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .parse()
+        .ok()?;
+    Some(Duration::from_secs(seconds).min(MAX_BACKOFF))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn response(retry_after: Option<&str>) -> Response {
+        let mut builder = http::Response::builder().status(StatusCode::SERVICE_UNAVAILABLE);
+        if let Some(value) = retry_after {
+            builder = builder.header(RETRY_AFTER, value);
+        }
+        Response::from(builder.body("").unwrap())
+    }
+
+    #[test]
+    fn retries_only_rate_limiting_when_not_idempotent() {
+        for status in [
+            StatusCode::SERVICE_UNAVAILABLE,
+            StatusCode::INTERNAL_SERVER_ERROR,
+            StatusCode::BAD_GATEWAY,
+            StatusCode::GATEWAY_TIMEOUT,
+        ] {
+            assert!(should_retry(status, true), "{status}");
+            assert!(!should_retry(status, false), "{status}");
+        }
+        assert!(should_retry(StatusCode::TOO_MANY_REQUESTS, true));
+        assert!(should_retry(StatusCode::TOO_MANY_REQUESTS, false));
+        for status in [
+            StatusCode::OK,
+            StatusCode::BAD_REQUEST,
+            StatusCode::NOT_FOUND,
+        ] {
+            assert!(!should_retry(status, true), "{status}");
+        }
+    }
+
+    #[test]
+    fn honours_retry_after_in_seconds_up_to_a_limit() {
+        assert_eq!(
+            retry_after(&response(Some("5"))),
+            Some(Duration::from_secs(5))
+        );
+        assert_eq!(retry_after(&response(Some("0"))), Some(Duration::ZERO));
+        assert_eq!(retry_after(&response(Some("3600"))), Some(MAX_BACKOFF));
+        assert_eq!(
+            retry_after(&response(Some("Wed, 21 Oct 2015 07:28:00 GMT"))),
+            None
+        );
+        assert_eq!(retry_after(&response(None)), None);
+    }
+}
@@ -0,0 +1,92 @@
+use std::time::Duration;
+
+use log::{debug, log_enabled, trace, Level};
+use reqwest::{
+    header::{HeaderMap, HeaderName, AUTHORIZATION, COOKIE, PROXY_AUTHORIZATION, SET_COOKIE},
+    Method, Request, Response, ResponseBuilderExt, Url,
+};
+
+///Headers whose values are never logged, as they carry credentials
+const SECRET_HEADERS: [HeaderName; 4] = [AUTHORIZATION, PROXY_AUTHORIZATION, COOKIE, SET_COOKIE];
+
+///Log a request about to be sent: its method, URL and headers at debug level and its body at
+///trace level
+pub fn request(request: &Request) {
+    if !log_enabled!(Level::Debug) {
+        return;
+    }
+    debug!(
+        "{} {}{}",
+        request.method(),
+        request.url(),
+        headers(request.headers())
+    );
+    if let Some(body) = request.body().and_then(|body| body.as_bytes()) {
+        trace!("{}", String::from_utf8_lossy(body));
+    }
+}
+
+///Log the response to a request along with how long it took, reading the body to log it at trace
+///level and handing back a response that can still be read
+pub async fn response(
+    method: &Method,
+    url: &Url,
+    elapsed: Duration,
+    res: reqwest::Result<Response>,
+) -> reqwest::Result<Response> {
+    if !log_enabled!(Level::Debug) {
+        return res;
+    }
+    let res = match res {
+        Ok(res) => res,
+        Err(e) => {
+            debug!("{method} {url} failed after {elapsed:.0?}: {e}");
+            return Err(e);
+        }
+    };
+    debug!(
+        "{method} {url} returned {} in {elapsed:.0?}{}",
+        res.status(),
+        headers(res.headers())
+    );
+    if !log_enabled!(Level::Trace) {
+        return Ok(res);
+    }
+    let mut builder = http::Response::builder()
+        .status(res.status())
+        .version(res.version())
+        .url(res.url().clone());
+    if let Some(headers) = builder.headers_mut() {
+        *headers = res.headers().clone();
+    }
+    let body = res.bytes().await?;
+    trace!("{}", String::from_utf8_lossy(&body));
+    Ok(Response::from(
+        builder
+            .body(body)
+            .expect("Response parts were already valid"),
+    ))
+}
+
+///Headers one per line, with the values of those carrying credentials replaced
+fn headers(headers: &HeaderMap) -> String {
+    headers
+        .iter()
+        .map(|(name, value)| {
+            let value = if SECRET_HEADERS.contains(name) {
+                redact(value.to_str().unwrap_or_default())
+            } else {
+                String::from_utf8_lossy(value.as_bytes()).into_owned()
+            };
+            format!("\n  {name}: {value}")
+        })
+        .collect()
+}
+
+///Keep just the scheme of a credential, eg `Bearer`, so it is clear which kind was sent
+fn redact(value: &str) -> String {
+    match value.split_once(' ') {
+        Some((scheme, _)) => format!("{scheme} [redacted]"),
+        None => String::from("[redacted]"),
+    }
+}
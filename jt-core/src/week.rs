@@ -0,0 +1,305 @@
+use std::{fmt::Display, str::FromStr};
+
+use chrono::{
+    DateTime, Datelike, FixedOffset, Local, NaiveDate, NaiveDateTime, TimeDelta, Utc, Weekday,
+};
+use chrono_tz::Tz;
+
+use crate::error::{JtError, Result};
+
+///The timezone dates and times of day are worked out in
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Timezone {
+    ///The system's timezone
+    #[default]
+    System,
+    ///A timezone from the IANA database, eg `Europe/Berlin`
+    Named(Tz),
+    ///A fixed offset from UTC
+    Offset(FixedOffset),
+}
+
+impl Timezone {
+    ///Today's date in the timezone
+    pub fn today(&self) -> NaiveDate {
+        self.local_time(Utc::now()).date()
+    }
+
+    ///A moment as the date and time on the clock in the timezone
+    pub fn local_time(&self, time: DateTime<Utc>) -> NaiveDateTime {
+        match self {
+            Timezone::System => time.with_timezone(&Local).naive_local(),
+            Timezone::Named(tz) => time.with_timezone(tz).naive_local(),
+            Timezone::Offset(offset) => time.with_timezone(offset).naive_local(),
+        }
+    }
+}
+
+impl Display for Timezone {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Timezone::System => write!(f, "system"),
+            Timezone::Named(tz) => write!(f, "{tz}"),
+            Timezone::Offset(offset) => write!(f, "{offset}"),
+        }
+    }
+}
+
+///Parse a timezone given as an IANA name, eg `Europe/Berlin`, or as an offset from UTC, eg `UTC`,
+///`+02:00`, `UTC-5` or `+0530`
+pub fn parse_timezone(s: &str) -> Result<Timezone> {
+    if let Ok(tz) = s.trim().parse::<Tz>() {
+        return Ok(Timezone::Named(tz));
+    }
+    let invalid = || {
+        JtError::Validation(format!(
+            "\"{s}\" is neither a timezone such as Europe/Berlin nor an offset from UTC such as +02:00 or UTC-5"
+        ))
+    };
+    let offset = s.trim();
+    let offset = offset
+        .strip_prefix("UTC")
+        .or_else(|| offset.strip_prefix("GMT"))
+        .unwrap_or(offset);
+    if offset.is_empty() || offset == "Z" {
+        return Ok(Timezone::Offset(FixedOffset::east_opt(0).unwrap()));
+    }
+    let (sign, offset) = match offset.split_at_checked(1) {
+        Some(("+", rest)) => (1, rest),
+        Some(("-", rest)) => (-1, rest),
+        _ => return Err(invalid()),
+    };
+    let (hours, minutes) = match offset.split_once(':') {
+        Some((hours, minutes)) => (hours, minutes),
+        None if offset.len() > 2 => offset.split_at(offset.len() - 2),
+        None => (offset, "0"),
+    };
+    let hours: i32 = hours.parse().map_err(|_| invalid())?;
+    let minutes: i32 = minutes.parse().map_err(|_| invalid())?;
+    if minutes >= 60 {
+        return Err(invalid());
+    }
+    FixedOffset::east_opt(sign * (hours * 3600 + minutes * 60))
+        .map(Timezone::Offset)
+        .ok_or_else(invalid)
+}
+
+///A week, either relative to the current one or an ISO week such as `2024-W18`
+#[derive(Clone, Copy)]
+pub enum WeekSpec {
+    Offset(i64),
+    Iso(NaiveDate),
+}
+
+impl WeekSpec {
+    ///The Monday of the week, counting any offset from the week `today` falls in
+    pub fn monday(&self, today: NaiveDate) -> NaiveDate {
+        match self {
+            WeekSpec::Offset(offset) => week_start(today, *offset),
+            WeekSpec::Iso(monday) => *monday,
+        }
+    }
+}
+
+impl FromStr for WeekSpec {
+    type Err = JtError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        if let Ok(offset) = s.parse() {
+            return Ok(WeekSpec::Offset(offset));
+        }
+        let invalid = || {
+            JtError::Validation(format!(
+                "\"{s}\" is neither a week offset (eg -2) nor an ISO week (eg 2024-W18)"
+            ))
+        };
+        let (year, week) = s.split_once("-W").ok_or_else(invalid)?;
+        let year = year.parse().map_err(|_| invalid())?;
+        let week = week.parse().map_err(|_| invalid())?;
+        NaiveDate::from_isoywd_opt(year, week, Weekday::Mon)
+            .map(WeekSpec::Iso)
+            .ok_or_else(invalid)
+    }
+}
+
+///A calendar month, either relative to the current one or given as eg `2024-05`
+#[derive(Clone, Copy)]
+pub enum MonthSpec {
+    Offset(i32),
+    Calendar(NaiveDate),
+}
+
+impl MonthSpec {
+    ///The first day of the month, counting any offset from the month `today` falls in
+    pub fn first_day(&self, today: NaiveDate) -> NaiveDate {
+        match self {
+            MonthSpec::Offset(offset) => {
+                let months = today.year() * 12 + today.month0() as i32 + offset;
+                NaiveDate::from_ymd_opt(months.div_euclid(12), months.rem_euclid(12) as u32 + 1, 1)
+                    .expect("first of the month is always a valid date")
+            }
+            MonthSpec::Calendar(first) => *first,
+        }
+    }
+
+    pub fn last_day(&self, today: NaiveDate) -> NaiveDate {
+        let first = self.first_day(today);
+        let next = first
+            .checked_add_months(chrono::Months::new(1))
+            .expect("month within the supported range");
+        next.pred_opt().unwrap()
+    }
+}
+
+impl FromStr for MonthSpec {
+    type Err = JtError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        if let Ok(offset) = s.parse() {
+            return Ok(MonthSpec::Offset(offset));
+        }
+        NaiveDate::parse_from_str(&format!("{s}-01"), "%Y-%m-%d")
+            .map(MonthSpec::Calendar)
+            .map_err(|_| {
+                JtError::Validation(format!(
+                    "\"{s}\" is neither a month offset (eg -1) nor a month (eg 2024-05)"
+                ))
+            })
+    }
+}
+
+///The Monday of the week `offset` weeks from the one `today` falls in
+pub fn week_start(today: NaiveDate, offset: i64) -> NaiveDate {
+    let monday = today - TimeDelta::days(today.weekday().num_days_from_monday() as i64);
+    monday + TimeDelta::weeks(offset)
+}
+
+///Monday to Friday, the working days unless configured otherwise
+pub const DEFAULT_WORKING_DAYS: [Weekday; 5] = [
+    Weekday::Mon,
+    Weekday::Tue,
+    Weekday::Wed,
+    Weekday::Thu,
+    Weekday::Fri,
+];
+
+///The working days of the week starting on `monday`
+pub fn week_days(monday: NaiveDate, working_days: &[Weekday]) -> Vec<NaiveDate> {
+    monday
+        .iter_days()
+        .take(7)
+        .filter(|day| working_days.contains(&day.weekday()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn reads_week_offsets_and_iso_weeks() {
+        assert!(matches!("-2".parse(), Ok(WeekSpec::Offset(-2))));
+        assert!(matches!("0".parse(), Ok(WeekSpec::Offset(0))));
+        let monday = |s: &str| s.parse::<WeekSpec>().unwrap().monday(date(2024, 5, 9));
+        assert_eq!(monday("2024-W19"), date(2024, 5, 6));
+        assert_eq!(monday("2021-W01"), date(2021, 1, 4));
+        assert_eq!(monday("2020-W53"), date(2020, 12, 28));
+        for invalid in [
+            "", "last", "2024-W54", "2021-W53", "2024-W0", "2024W19", "W19",
+        ] {
+            assert!(invalid.parse::<WeekSpec>().is_err(), "{invalid:?}");
+        }
+    }
+
+    #[test]
+    fn reads_month_offsets_and_calendar_months() {
+        assert!(matches!("-1".parse(), Ok(MonthSpec::Offset(-1))));
+        let month = "2024-02".parse::<MonthSpec>().unwrap();
+        assert_eq!(
+            (
+                month.first_day(date(2024, 5, 9)),
+                month.last_day(date(2024, 5, 9))
+            ),
+            (date(2024, 2, 1), date(2024, 2, 29))
+        );
+        let december = "2023-12".parse::<MonthSpec>().unwrap();
+        assert_eq!(december.last_day(date(2024, 5, 9)), date(2023, 12, 31));
+        for invalid in ["", "2024-13", "2024-5-1", "May"] {
+            assert!(invalid.parse::<MonthSpec>().is_err(), "{invalid:?}");
+        }
+    }
+
+    #[test]
+    fn counts_relative_months_across_years() {
+        let today = date(2024, 5, 9);
+        assert_eq!(MonthSpec::Offset(0).first_day(today), date(2024, 5, 1));
+        assert_eq!(MonthSpec::Offset(-13).first_day(today), date(2023, 4, 1));
+        assert_eq!(MonthSpec::Offset(8).last_day(today), date(2025, 1, 31));
+    }
+
+    #[test]
+    fn counts_week_offsets_from_today() {
+        assert_eq!(
+            WeekSpec::Offset(0).monday(date(2024, 5, 12)),
+            date(2024, 5, 6)
+        );
+        assert_eq!(
+            WeekSpec::Offset(-1).monday(date(2024, 5, 6)),
+            date(2024, 4, 29)
+        );
+        assert_eq!(week_start(date(2024, 12, 31), 1), date(2025, 1, 6));
+    }
+
+    #[test]
+    fn lists_the_working_days_of_a_week() {
+        assert_eq!(
+            week_days(date(2024, 5, 6), &DEFAULT_WORKING_DAYS),
+            (6..=10).map(|day| date(2024, 5, day)).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            week_days(date(2024, 5, 6), &[Weekday::Sun, Weekday::Mon]),
+            [date(2024, 5, 6), date(2024, 5, 12)]
+        );
+        assert!(week_days(date(2024, 5, 6), &[]).is_empty());
+    }
+
+    #[test]
+    fn reads_offsets_from_utc() {
+        let offset = |s| match parse_timezone(s).unwrap() {
+            Timezone::Offset(offset) => offset.local_minus_utc(),
+            other => panic!("{s} read as {other}"),
+        };
+        assert_eq!(offset("UTC+0"), 0);
+        assert_eq!(offset("+02:00"), 7200);
+        assert_eq!(offset("UTC-5"), -18000);
+        assert_eq!(offset("+0530"), 19800);
+        for invalid in ["CET+", "+02:60", "2", "+x", "Europe/Atlantis"] {
+            assert!(parse_timezone(invalid).is_err(), "{invalid:?}");
+        }
+    }
+
+    #[test]
+    fn reads_named_timezones_with_their_daylight_saving() {
+        let berlin = parse_timezone("Europe/Berlin").unwrap();
+        assert_eq!(berlin, Timezone::Named(chrono_tz::Europe::Berlin));
+        let at = |s: &str| s.parse::<DateTime<Utc>>().unwrap();
+        assert_eq!(
+            berlin.local_time(at("2024-01-15T23:30:00Z")),
+            date(2024, 1, 16).and_hms_opt(0, 30, 0).unwrap()
+        );
+        assert_eq!(
+            berlin.local_time(at("2024-07-15T22:30:00Z")),
+            date(2024, 7, 16).and_hms_opt(0, 30, 0).unwrap()
+        );
+        assert_eq!(
+            parse_timezone("UTC")
+                .unwrap()
+                .local_time(at("2024-07-15T22:30:00Z")),
+            date(2024, 7, 15).and_hms_opt(22, 30, 0).unwrap()
+        );
+    }
+}
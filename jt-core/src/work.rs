@@ -0,0 +1,644 @@
+use chrono::{NaiveDate, NaiveTime, TimeDelta};
+
+use crate::{
+    duration::split_evenly,
+    error::{JtError, Result},
+    locale::NumberFormat,
+    plan::{self, Plan, PlanEntry},
+};
+
+///What dividing a day's time needs to know about a task, whether it is a configured static task
+///or an issue found by a search
+pub trait Plannable {
+    ///Key the task is chosen by in plans, templates and `--tasks`
+    fn key(&self) -> String;
+
+    ///Group the task was configured in, through which it can be chosen along with the rest of the
+    ///group
+    fn group(&self) -> Option<&str> {
+        None
+    }
+
+    ///Whether the task was configured rather than found by a search
+    fn is_static(&self) -> bool {
+        false
+    }
+
+    ///Share of each day's target, in percent, logged against the task without prompting
+    fn percent(&self) -> Option<f64> {
+        None
+    }
+
+    fn default_comment(&self) -> Option<String> {
+        None
+    }
+
+    ///Whether time on the task is billable, left to Tempo if not given
+    fn billable(&self) -> Option<bool> {
+        None
+    }
+
+    ///Time logged against this task every day without prompting, if it has an allocation
+    fn allocated_time(&self, target_per_day: TimeDelta) -> Option<TimeDelta> {
+        let seconds = target_per_day.num_seconds() as f64 * self.percent()? / 100.0;
+        Some(TimeDelta::minutes((seconds / 60.0).round() as i64))
+    }
+}
+
+///Time spent on a task on a given day, as selected by the user or read from a plan
+pub struct Entry<'a, T> {
+    pub day: NaiveDate,
+    pub task: &'a T,
+    pub time_spent: TimeDelta,
+    pub comment: Option<String>,
+    pub tags: Vec<String>,
+    ///Billable part of the time spent, if chosen separately from the task's default
+    pub billable: Option<TimeDelta>,
+    ///Time of day the work started, if given rather than left to `workday_start`
+    pub start_time: Option<NaiveTime>,
+}
+
+impl<'a, T: Plannable> Entry<'a, T> {
+    pub fn new(day: NaiveDate, task: &'a T, time_spent: TimeDelta) -> Entry<'a, T> {
+        Entry {
+            day,
+            task,
+            time_spent,
+            comment: task.default_comment(),
+            tags: Vec::new(),
+            billable: None,
+            start_time: None,
+        }
+    }
+
+    ///Billable time to log, left to Tempo when neither the entry nor its task say
+    pub fn billable_time(&self) -> Option<TimeDelta> {
+        self.billable.or(match self.task.billable() {
+            Some(true) => Some(self.time_spent),
+            Some(false) => Some(TimeDelta::zero()),
+            None => None,
+        })
+    }
+}
+
+///Separate `#tags` from the rest of a comment, returning the remaining comment, if any, and the
+///tags without their leading `#`
+pub fn split_tags(text: &str) -> (Option<String>, Vec<String>) {
+    let (tags, words): (Vec<&str>, Vec<&str>) = text
+        .split_whitespace()
+        .partition(|word| word.len() > 1 && word.starts_with('#'));
+    let comment = Some(words.join(" ")).filter(|comment| !comment.is_empty());
+    let tags = tags
+        .into_iter()
+        .map(|tag| tag.trim_start_matches('#').to_lowercase())
+        .collect();
+    (comment, tags)
+}
+
+///Tasks matching a name, either a single task by key or all the tasks in a group
+pub fn tasks_named<'a, T: Plannable>(tasks: &'a [T], name: &str) -> Vec<&'a T> {
+    let by_key: Vec<&T> = tasks.iter().filter(|task| task.key() == name).collect();
+    if !by_key.is_empty() {
+        return by_key.into_iter().take(1).collect();
+    }
+    tasks
+        .iter()
+        .filter(|task| task.group() == Some(name))
+        .collect()
+}
+
+///Tasks to split days between with `--even`, those named or otherwise all the static tasks
+pub fn even_tasks<'a, T: Plannable>(tasks: &'a [T], names: &[String]) -> Result<Vec<&'a T>> {
+    let chosen: Vec<&T> = if names.is_empty() {
+        tasks.iter().filter(|task| task.is_static()).collect()
+    } else {
+        names
+            .iter()
+            .flat_map(|name| tasks_named(tasks, name))
+            .collect()
+    };
+    if chosen.is_empty() {
+        return Err(JtError::Validation(String::from(
+            "No tasks to split the day between, configure static tasks or pass --tasks",
+        )));
+    }
+    Ok(chosen)
+}
+
+///Entries for the tasks with an allocation, along with the tasks left without one
+pub fn allocate<'a, T: Plannable>(
+    day: NaiveDate,
+    tasks: impl IntoIterator<Item = &'a T>,
+    target_per_day: TimeDelta,
+) -> (Vec<Entry<'a, T>>, Vec<&'a T>) {
+    let mut allocated = Vec::new();
+    let mut unallocated = Vec::new();
+    for task in tasks {
+        match task.allocated_time(target_per_day) {
+            Some(time_spent) => allocated.push(Entry::new(day, task, time_spent)),
+            None => unallocated.push(task),
+        }
+    }
+    (allocated, unallocated)
+}
+
+///Entries splitting a day evenly between tasks, after giving any with an allocation their share
+pub fn even_entries<'a, T: Plannable>(
+    day: NaiveDate,
+    tasks: &[&'a T],
+    target_per_day: TimeDelta,
+) -> Vec<Entry<'a, T>> {
+    let (mut today, unallocated) = allocate(day, tasks.iter().copied(), target_per_day);
+    let remaining = target_per_day - today.iter().map(|entry| entry.time_spent).sum();
+    if remaining > TimeDelta::zero() && !unallocated.is_empty() {
+        let shares = split_evenly(remaining, unallocated.len());
+        today.extend(
+            unallocated
+                .into_iter()
+                .zip(shares)
+                .map(|(task, share)| Entry::new(day, task, share)),
+        );
+    }
+    today
+}
+
+///Entries for every day of a plan, in order of day, with each day's target given by
+///`target_per_day`
+pub fn plan_work<'a, T: Plannable>(
+    plan: &Plan,
+    tasks: &'a [T],
+    first_day: NaiveDate,
+    target_per_day: impl Fn(NaiveDate) -> TimeDelta,
+    number_format: NumberFormat,
+) -> Result<Vec<Entry<'a, T>>> {
+    let mut work = Vec::new();
+    for planned in &plan.days {
+        let day = planned.day.resolve(first_day);
+        let entries = planned_entries(
+            day,
+            &planned.entries,
+            tasks,
+            target_per_day(day),
+            TimeDelta::zero(),
+            number_format,
+        )
+        .map_err(|e| JtError::Validation(format!("Invalid plan for {}: {e}", planned.day)))?;
+        work.extend(entries);
+    }
+    work.sort_by_key(|entry| entry.day);
+    Ok(work)
+}
+
+///Entries for a day's plan or template entries, with time already spent on other entries that day
+///taken away from the entries that fill the rest of the day
+pub fn planned_entries<'a, T: Plannable>(
+    day: NaiveDate,
+    entries: &[PlanEntry],
+    tasks: &'a [T],
+    target_per_day: TimeDelta,
+    already_spent: TimeDelta,
+    number_format: NumberFormat,
+) -> Result<Vec<Entry<'a, T>>> {
+    let times = plan::day_time_spent(entries, target_per_day, already_spent, number_format)?;
+    let mut planned_entries = Vec::new();
+    for (entry, time_spent) in entries.iter().zip(times) {
+        let named = tasks_named(tasks, &entry.task);
+        if named.is_empty() {
+            return Err(JtError::Validation(format!("Unknown task {}", entry.task)));
+        }
+        let shares = split_evenly(time_spent, named.len());
+        planned_entries.extend(named.into_iter().zip(shares).map(|(task, share)| {
+            let mut planned = Entry::new(day, task, share);
+            if let Some(comment) = &entry.comment {
+                (planned.comment, planned.tags) = split_tags(comment);
+            }
+            planned
+                .tags
+                .extend(entry.tags.iter().map(|tag| tag.to_lowercase()));
+            planned
+        }));
+    }
+    Ok(planned_entries)
+}
+
+///Entries with their time rounded, dropping any rounded away to nothing
+pub fn round_work<'a, T>(
+    work: Vec<Entry<'a, T>>,
+    round: impl Fn(TimeDelta) -> TimeDelta,
+) -> Vec<Entry<'a, T>> {
+    work.into_iter()
+        .filter_map(|mut entry| {
+            entry.time_spent = round(entry.time_spent);
+            (entry.time_spent > TimeDelta::zero()).then_some(entry)
+        })
+        .collect()
+}
+
+///Split an entry into several on the same day if it exceeds the maximum worklog duration
+pub fn split_entry<T: Plannable>(
+    entry: Entry<'_, T>,
+    max_time_spent: Option<TimeDelta>,
+) -> Vec<Entry<'_, T>> {
+    let Some(max) = max_time_spent.filter(|max| *max > TimeDelta::zero()) else {
+        return vec![entry];
+    };
+    let mut remaining = entry.time_spent;
+    let mut billable = entry.billable;
+    let mut parts = Vec::new();
+    while remaining > TimeDelta::zero() {
+        let time_spent = remaining.min(max);
+        let part_billable = billable.map(|billable| billable.min(time_spent));
+        parts.push(Entry {
+            time_spent,
+            comment: entry.comment.clone(),
+            tags: entry.tags.clone(),
+            billable: part_billable,
+            start_time: entry
+                .start_time
+                .map(|start| start + (entry.time_spent - remaining)),
+            ..entry
+        });
+        remaining -= time_spent;
+        billable = billable
+            .zip(part_billable)
+            .map(|(total, part)| total - part);
+    }
+    if parts.len() > 1 {
+        log::info!(
+            "Split {} on {} into {} worklogs",
+            entry.task.key(),
+            entry.day,
+            parts.len()
+        );
+    }
+    parts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plan::{DaySpec, PlanDay};
+
+    struct Task {
+        key: &'static str,
+        group: Option<&'static str>,
+        percent: Option<f64>,
+        billable: Option<bool>,
+    }
+
+    impl Plannable for Task {
+        fn key(&self) -> String {
+            self.key.to_owned()
+        }
+
+        fn group(&self) -> Option<&str> {
+            self.group
+        }
+
+        fn is_static(&self) -> bool {
+            self.group.is_some() || self.percent.is_some()
+        }
+
+        fn percent(&self) -> Option<f64> {
+            self.percent
+        }
+
+        fn billable(&self) -> Option<bool> {
+            self.billable
+        }
+    }
+
+    fn task(key: &'static str) -> Task {
+        Task {
+            key,
+            group: None,
+            percent: None,
+            billable: None,
+        }
+    }
+
+    fn monday() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 5, 6).unwrap()
+    }
+
+    fn planned(task: &str, minutes: Option<u64>, comment: Option<&str>) -> PlanEntry {
+        PlanEntry {
+            task: task.to_owned(),
+            minutes,
+            duration: None,
+            percent: None,
+            comment: comment.map(String::from),
+            tags: Vec::new(),
+        }
+    }
+
+    fn times<T: Plannable>(work: &[Entry<T>]) -> Vec<(String, i64)> {
+        work.iter()
+            .map(|entry| (entry.task.key(), entry.time_spent.num_minutes()))
+            .collect()
+    }
+
+    #[test]
+    fn separates_tags_from_comments() {
+        assert_eq!(
+            split_tags("Sprint review #Meeting # #ops"),
+            (
+                Some(String::from("Sprint review #")),
+                vec![String::from("meeting"), String::from("ops")]
+            )
+        );
+        assert_eq!(split_tags("#ops"), (None, vec![String::from("ops")]));
+    }
+
+    #[test]
+    fn names_a_task_by_key_before_a_group() {
+        let tasks = [
+            Task {
+                group: Some("ADMIN"),
+                ..task("ADMIN-1")
+            },
+            Task {
+                group: Some("ADMIN"),
+                ..task("ADMIN-2")
+            },
+            task("ADMIN"),
+        ];
+        let keys = |name| -> Vec<String> {
+            tasks_named(&tasks, name)
+                .iter()
+                .map(|task| task.key())
+                .collect()
+        };
+        assert_eq!(keys("ADMIN"), ["ADMIN"]);
+        assert_eq!(keys("ADMIN-2"), ["ADMIN-2"]);
+        assert!(keys("PROJ-1").is_empty());
+
+        let grouped = &tasks[..2];
+        assert_eq!(tasks_named(grouped, "ADMIN").len(), 2);
+    }
+
+    #[test]
+    fn allocates_shares_of_the_target() {
+        let tasks = [
+            Task {
+                percent: Some(12.5),
+                ..task("ADMIN-1")
+            },
+            task("PROJ-1"),
+        ];
+        let (allocated, unallocated) = allocate(monday(), &tasks, TimeDelta::hours(8));
+        assert_eq!(times(&allocated), [(String::from("ADMIN-1"), 60)]);
+        assert_eq!(unallocated.len(), 1);
+        assert_eq!(unallocated[0].key, "PROJ-1");
+    }
+
+    #[test]
+    fn fills_a_plan_day_by_day_and_splits_groups() {
+        let tasks = [
+            Task {
+                group: Some("Meetings"),
+                ..task("ADMIN-1")
+            },
+            Task {
+                group: Some("Meetings"),
+                ..task("ADMIN-2")
+            },
+            task("PROJ-1"),
+        ];
+        let plan = Plan {
+            days: vec![
+                PlanDay {
+                    day: DaySpec::Weekday(chrono::Weekday::Tue),
+                    entries: vec![planned("PROJ-1", None, None)],
+                },
+                PlanDay {
+                    day: DaySpec::Weekday(chrono::Weekday::Mon),
+                    entries: vec![
+                        planned("Meetings", Some(60), Some("Standup #daily")),
+                        planned("PROJ-1", None, None),
+                    ],
+                },
+            ],
+        };
+        let work = plan_work(
+            &plan,
+            &tasks,
+            monday(),
+            |day| {
+                if day == monday() {
+                    TimeDelta::hours(8)
+                } else {
+                    TimeDelta::hours(4)
+                }
+            },
+            NumberFormat::default(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            times(&work),
+            [
+                (String::from("ADMIN-1"), 30),
+                (String::from("ADMIN-2"), 30),
+                (String::from("PROJ-1"), 420),
+                (String::from("PROJ-1"), 240),
+            ]
+        );
+        assert_eq!(work[0].comment.as_deref(), Some("Standup"));
+        assert_eq!(work[1].tags, ["daily"]);
+        assert_eq!(work[3].day, monday().succ_opt().unwrap());
+    }
+
+    #[test]
+    fn splits_a_group_into_whole_minutes_adding_up_to_its_time() {
+        let tasks: Vec<Task> = [
+            "ADMIN-1", "ADMIN-2", "ADMIN-3", "ADMIN-4", "ADMIN-5", "ADMIN-6", "ADMIN-7",
+        ]
+        .into_iter()
+        .map(|key| Task {
+            group: Some("Meetings"),
+            ..task(key)
+        })
+        .collect();
+        let work = planned_entries(
+            monday(),
+            &[planned("Meetings", Some(60), None)],
+            &tasks,
+            TimeDelta::hours(8),
+            TimeDelta::zero(),
+            NumberFormat::default(),
+        )
+        .unwrap();
+
+        assert!(work
+            .iter()
+            .all(|entry| entry.time_spent.num_seconds() % 60 == 0));
+        let total: TimeDelta = work.iter().map(|entry| entry.time_spent).sum();
+        assert_eq!(total, TimeDelta::hours(1));
+    }
+
+    #[test]
+    fn rejects_plans_naming_unknown_tasks() {
+        let tasks = [task("PROJ-1")];
+        let plan = Plan {
+            days: vec![PlanDay {
+                day: DaySpec::Weekday(chrono::Weekday::Mon),
+                entries: vec![planned("PROJ-2", Some(60), None)],
+            }],
+        };
+        let result = plan_work(
+            &plan,
+            &tasks,
+            monday(),
+            |_| TimeDelta::hours(8),
+            NumberFormat::default(),
+        );
+        assert!(matches!(
+            result,
+            Err(JtError::Validation(message)) if message.ends_with("Unknown task PROJ-2")
+        ));
+    }
+
+    #[test]
+    fn bills_the_whole_entry_as_its_task_says() {
+        let billable = Task {
+            billable: Some(true),
+            ..task("PROJ-1")
+        };
+        let mut entry = Entry::new(monday(), &billable, TimeDelta::minutes(90));
+        assert_eq!(entry.billable_time(), Some(TimeDelta::minutes(90)));
+        entry.billable = Some(TimeDelta::minutes(30));
+        assert_eq!(entry.billable_time(), Some(TimeDelta::minutes(30)));
+        assert_eq!(
+            Entry::new(monday(), &task("PROJ-2"), TimeDelta::hours(1)).billable_time(),
+            None
+        );
+    }
+
+    #[test]
+    fn drops_entries_rounded_away() {
+        let tasks = [task("PROJ-1"), task("PROJ-2"), task("PROJ-3")];
+        let work = tasks
+            .iter()
+            .zip([7, 8, 0])
+            .map(|(task, minutes)| Entry::new(monday(), task, TimeDelta::minutes(minutes)))
+            .collect();
+        let rounded = round_work(work, |time_spent| {
+            crate::duration::round_duration(
+                time_spent,
+                TimeDelta::minutes(15),
+                crate::duration::Rounding::Nearest,
+            )
+        });
+        assert_eq!(times(&rounded), [(String::from("PROJ-2"), 15)]);
+    }
+
+    #[test]
+    fn splits_the_day_evenly_after_allocations() {
+        let tasks = [
+            Task {
+                percent: Some(25.0),
+                ..task("ADMIN-1")
+            },
+            task("PROJ-1"),
+            task("PROJ-2"),
+            task("PROJ-3"),
+        ];
+        let chosen: Vec<&Task> = tasks.iter().collect();
+        let today = even_entries(monday(), &chosen, TimeDelta::minutes(451));
+        assert_eq!(
+            times(&today),
+            [
+                (String::from("ADMIN-1"), 113),
+                (String::from("PROJ-1"), 113),
+                (String::from("PROJ-2"), 113),
+                (String::from("PROJ-3"), 112),
+            ]
+        );
+        assert_eq!(
+            today
+                .iter()
+                .map(|entry| entry.time_spent)
+                .sum::<TimeDelta>(),
+            TimeDelta::minutes(451)
+        );
+    }
+
+    #[test]
+    fn leaves_nothing_to_share_once_allocations_fill_the_day() {
+        let tasks = [
+            Task {
+                percent: Some(100.0),
+                ..task("ADMIN-1")
+            },
+            task("PROJ-1"),
+        ];
+        let chosen: Vec<&Task> = tasks.iter().collect();
+        let today = even_entries(monday(), &chosen, TimeDelta::hours(8));
+        assert_eq!(times(&today), [(String::from("ADMIN-1"), 480)]);
+        assert!(even_entries(monday(), &chosen, TimeDelta::zero())
+            .iter()
+            .all(|entry| entry.time_spent.is_zero()));
+    }
+
+    #[test]
+    fn chooses_static_tasks_unless_told_otherwise() {
+        let tasks = [
+            Task {
+                group: Some("Meetings"),
+                ..task("ADMIN-1")
+            },
+            task("PROJ-1"),
+        ];
+        let keys = |names: &[&str]| -> Result<Vec<String>> {
+            let names: Vec<String> = names.iter().map(|name| name.to_string()).collect();
+            Ok(even_tasks(&tasks, &names)?
+                .iter()
+                .map(|task| task.key())
+                .collect())
+        };
+        assert_eq!(keys(&[]).unwrap(), ["ADMIN-1"]);
+        assert_eq!(
+            keys(&["PROJ-1", "Meetings"]).unwrap(),
+            ["PROJ-1", "ADMIN-1"]
+        );
+        assert!(matches!(keys(&["PROJ-2"]), Err(JtError::Validation(_))));
+        assert!(even_tasks(&tasks[1..], &[]).is_err());
+    }
+
+    #[test]
+    fn splits_oversized_entries_keeping_start_and_billable_time() {
+        let task = task("PROJ-1");
+        let mut entry = Entry::new(monday(), &task, TimeDelta::minutes(750));
+        entry.start_time = NaiveTime::from_hms_opt(8, 0, 0);
+        entry.billable = Some(TimeDelta::minutes(400));
+        let parts = split_entry(entry, Some(TimeDelta::hours(6)));
+        assert_eq!(
+            parts
+                .iter()
+                .map(|part| (
+                    part.time_spent.num_minutes(),
+                    part.billable.unwrap().num_minutes(),
+                    part.start_time.unwrap().format("%H:%M").to_string()
+                ))
+                .collect::<Vec<_>>(),
+            [
+                (360, 360, String::from("08:00")),
+                (360, 40, String::from("14:00")),
+                (30, 0, String::from("20:00")),
+            ]
+        );
+    }
+
+    #[test]
+    fn leaves_entries_within_the_limit_whole() {
+        let task = task("PROJ-1");
+        let whole =
+            |time_spent, max| split_entry(Entry::new(monday(), &task, time_spent), max).len();
+        assert_eq!(whole(TimeDelta::hours(6), Some(TimeDelta::hours(6))), 1);
+        assert_eq!(whole(TimeDelta::hours(20), None), 1);
+        assert_eq!(whole(TimeDelta::hours(20), Some(TimeDelta::zero())), 1);
+        assert_eq!(whole(TimeDelta::hours(12), Some(TimeDelta::hours(6))), 2);
+        assert_eq!(whole(TimeDelta::zero(), Some(TimeDelta::hours(6))), 0);
+    }
+}
@@ -0,0 +1,339 @@
+use std::fs;
+
+use chrono::{NaiveDate, NaiveTime, TimeDelta};
+use jt_core::{
+    client::{AuthFailure, Credentials, JtClient, NewWorklog},
+    error::JtError,
+    options::{HttpOptions, RemainingEstimate, TempoVersion, UserIdentifier, WorkAttribute},
+};
+use serde_json::Value;
+use wiremock::{
+    matchers::{body_json, header, method, path, query_param},
+    Mock, MockServer, ResponseTemplate,
+};
+
+///A recorded request or response body from `tests/fixtures`
+fn fixture(name: &str) -> Value {
+    let path = format!("{}/tests/fixtures/{name}.json", env!("CARGO_MANIFEST_DIR"));
+    serde_json::from_str(&fs::read_to_string(path).unwrap()).unwrap()
+}
+
+fn client(server: &MockServer, dry_run: bool) -> JtClient {
+    JtClient::new(
+        &Credentials::Token(String::from("secret")),
+        server.uri().parse().unwrap(),
+        dry_run,
+        UserIdentifier::Key,
+        &HttpOptions::default(),
+    )
+    .unwrap()
+}
+
+fn monday() -> NaiveDate {
+    NaiveDate::from_ymd_opt(2024, 5, 6).unwrap()
+}
+
+fn account(value: &str) -> WorkAttribute {
+    WorkAttribute {
+        key: String::from("_Account_"),
+        name: String::from("Account"),
+        work_attribute_id: 1,
+        value: value.to_owned(),
+    }
+}
+
+fn pairing() -> NewWorklog {
+    NewWorklog {
+        task_id: String::from("PROJ-1"),
+        day: monday(),
+        start_time: NaiveTime::from_hms_opt(9, 30, 0),
+        time_spent: TimeDelta::minutes(90),
+        comment: Some(String::from("Pairing")),
+        attributes: vec![account("INTERNAL")],
+        visibility: None,
+        remaining_estimate: RemainingEstimate::Auto,
+        billable: Some(TimeDelta::hours(1)),
+    }
+}
+
+#[tokio::test]
+async fn creates_worklog_with_tempo_v4_payload() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/rest/tempo-timesheets/4/worklogs"))
+        .and(header("Authorization", "Bearer secret"))
+        .and(body_json(fixture("create-worklog-v4.request")))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(fixture("create-worklog-v4.response")),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let created = client(&server, false)
+        .create_worklog("jdoe", pairing())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(created.tempo_worklog_id, 101);
+    assert_eq!(created.issue.key, "PROJ-1");
+}
+
+#[tokio::test]
+async fn creates_worklog_with_tempo_v3_payload() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/rest/tempo-timesheets/3/worklogs"))
+        .and(body_json(fixture("create-worklog-v3.request")))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(fixture("create-worklog-v3.response")),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let worklog = NewWorklog {
+        task_id: String::from("PROJ-2"),
+        start_time: None,
+        time_spent: TimeDelta::minutes(30),
+        comment: None,
+        remaining_estimate: RemainingEstimate::Set(TimeDelta::hours(2)),
+        billable: None,
+        ..pairing()
+    };
+    let created = client(&server, false)
+        .with_tempo_version(TempoVersion::V3)
+        .create_worklog("jdoe", worklog)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(created.jira_worklog_id, Some(301));
+    assert_eq!(created.attributes["_Account_"].value, "INTERNAL");
+}
+
+#[tokio::test]
+async fn detects_tempo_v4_refusing_get_on_worklogs() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/rest/tempo-timesheets/4/worklogs"))
+        .respond_with(ResponseTemplate::new(405))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/rest/tempo-timesheets/3/worklogs"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(0)
+        .mount(&server)
+        .await;
+    let version = client(&server, false).detect_tempo_version().await.unwrap();
+    assert_eq!(version, TempoVersion::V4);
+}
+
+#[tokio::test]
+async fn falls_back_to_tempo_v3_only_when_v4_is_not_found() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/rest/tempo-timesheets/4/worklogs"))
+        .respond_with(ResponseTemplate::new(404))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/rest/tempo-timesheets/3/worklogs"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(Value::Array(Vec::new())))
+        .mount(&server)
+        .await;
+    let version = client(&server, false).detect_tempo_version().await.unwrap();
+    assert_eq!(version, TempoVersion::V3);
+
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/rest/tempo-timesheets/4/worklogs"))
+        .respond_with(ResponseTemplate::new(403))
+        .mount(&server)
+        .await;
+    let error = client(&server, false)
+        .detect_tempo_version()
+        .await
+        .unwrap_err();
+    assert!(
+        matches!(error, JtError::Api { status, .. } if status == 403),
+        "{error:?}"
+    );
+}
+
+#[tokio::test]
+async fn reads_tempo_v3_worklogs_by_account_id() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/rest/tempo-timesheets/3/worklogs"))
+        .and(query_param("dateFrom", "2024-05-06"))
+        .and(query_param("accountId", "5b10a2844c20165700ede21g"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(Value::Array(Vec::new())))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let client = JtClient::new(
+        &Credentials::Token(String::from("secret")),
+        server.uri().parse().unwrap(),
+        false,
+        UserIdentifier::AccountId,
+        &HttpOptions::default(),
+    )
+    .unwrap()
+    .with_tempo_version(TempoVersion::V3);
+    let worklogs = client
+        .get_worklogs(
+            "5b10a2844c20165700ede21g",
+            monday(),
+            monday() + TimeDelta::days(6),
+        )
+        .await
+        .unwrap();
+    assert!(worklogs.is_empty());
+}
+
+#[tokio::test]
+async fn dry_run_sends_nothing() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(0)
+        .mount(&server)
+        .await;
+
+    let client = client(&server, true);
+    assert!(client
+        .create_worklog("jdoe", pairing())
+        .await
+        .unwrap()
+        .is_none());
+    assert!(client
+        .submit_timesheet("jdoe", "manager", monday())
+        .await
+        .unwrap()
+        .is_none());
+}
+
+#[tokio::test]
+async fn submits_timesheet_from_the_saturday_before() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/rest/tempo-timesheets/4/timesheet-approval"))
+        .and(body_json(fixture("submit-timesheet.request")))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/rest/tempo-timesheets/4/timesheet-approval/current"))
+        .and(query_param("periodStartDate", "2024-05-04"))
+        .and(query_param("userKey", "jdoe"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(fixture("timesheet-approval.response")),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let approval = client(&server, false)
+        .submit_timesheet("jdoe", "manager", monday())
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(!approval.is_open());
+}
+
+#[tokio::test]
+async fn explains_refused_credentials() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/rest/api/2/myself"))
+        .respond_with(ResponseTemplate::new(401))
+        .up_to_n_times(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/rest/api/2/myself"))
+        .respond_with(
+            ResponseTemplate::new(403)
+                .insert_header("X-Seraph-LoginReason", "AUTHENTICATION_DENIED"),
+        )
+        .mount(&server)
+        .await;
+
+    let client = client(&server, false);
+    for expected in [AuthFailure::Rejected, AuthFailure::CaptchaRequired] {
+        let error = client.check_credentials().await.unwrap_err();
+        assert!(matches!(error, JtError::Auth(failure) if failure == expected));
+    }
+}
+
+#[tokio::test]
+async fn retries_reads_while_busy() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/rest/api/2/issue/PROJ-1"))
+        .respond_with(ResponseTemplate::new(503).insert_header("Retry-After", "0"))
+        .up_to_n_times(2)
+        .expect(2)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/rest/api/2/issue/PROJ-1"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "key": "PROJ-1",
+            "fields": { "summary": "Fix the build" }
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let issue = client(&server, false).get_issue("PROJ-1").await.unwrap();
+    assert_eq!(issue.summary(), Some("Fix the build"));
+}
+
+#[tokio::test]
+async fn never_retries_a_failed_worklog() {
+    for status in [500, 503] {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/rest/tempo-timesheets/4/worklogs"))
+            .respond_with(ResponseTemplate::new(status).insert_header("Retry-After", "0"))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let error = client(&server, false)
+            .create_worklog("jdoe", pairing())
+            .await
+            .unwrap_err();
+        assert!(matches!(error, JtError::Api { status: actual, .. } if actual == status));
+    }
+}
+
+#[tokio::test]
+async fn reports_tempo_errors_with_their_body() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/rest/tempo-timesheets/4/worklogs"))
+        .respond_with(ResponseTemplate::new(400).set_body_json(serde_json::json!({
+            "errors": [{ "message": "Issue PROJ-1 is closed" }]
+        })))
+        .mount(&server)
+        .await;
+
+    let error = client(&server, false)
+        .create_worklog("jdoe", pairing())
+        .await
+        .unwrap_err();
+    assert!(error
+        .to_string()
+        .ends_with("400 Bad Request: Issue PROJ-1 is closed"));
+    let JtError::Api { status, body, .. } = error else {
+        panic!("expected an API error, got {error:?}");
+    };
+    assert_eq!(status, 400);
+    assert!(body.contains("Issue PROJ-1 is closed"));
+}
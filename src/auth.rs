@@ -0,0 +1,237 @@
+use std::{
+    env,
+    process::{Command, Stdio},
+};
+
+use anyhow::{bail, Context, Result};
+use keyring::Entry;
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+
+use crate::client::Credentials;
+
+const KEYRING_SERVICE: &str = "jt";
+const KEYRING_USER: &str = "jira-token";
+const KEYRING_USERNAME: &str = "jira-username";
+const KEYRING_OAUTH: &str = "jira-oauth";
+const TOKEN_ENV_VAR: &str = "JIRA_TOKEN";
+const USERNAME_ENV_VAR: &str = "JIRA_USERNAME";
+
+///How requests to JIRA are authenticated: with a personal access token, with a username and
+///password for instances that predate tokens, or with tokens obtained by signing in through the
+///browser
+#[derive(Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum AuthKind {
+    #[default]
+    Bearer,
+    Basic,
+    Oauth,
+}
+
+///Tokens are stored per profile, as each profile may point at a different instance
+fn keyring_entry(profile: Option<&str>) -> Result<Entry> {
+    profile_entry(KEYRING_USER, profile)
+}
+
+fn username_entry(profile: Option<&str>) -> Result<Entry> {
+    profile_entry(KEYRING_USERNAME, profile)
+}
+
+fn profile_entry(user: &str, profile: Option<&str>) -> Result<Entry> {
+    Entry::new(KEYRING_SERVICE, &profile_user(user, profile)).map_err(|e| e.into())
+}
+
+fn profile_user(user: &str, profile: Option<&str>) -> String {
+    match profile {
+        Some(profile) => format!("{user}:{profile}"),
+        None => user.to_owned(),
+    }
+}
+
+///Keyring name of the refresh token kept when signing in with OAuth
+pub fn oauth_credential(profile: Option<&str>) -> String {
+    profile_user(KEYRING_OAUTH, profile)
+}
+
+///Tokens for other instances are stored by instance name, shared between profiles
+fn instance_entry(instance: &str) -> Result<Entry> {
+    profile_entry(KEYRING_USER, Some(&format!("instance:{instance}")))
+}
+
+///The token for another instance, from its token command or the keyring
+pub fn instance_token(instance: &str, token_command: Option<&str>) -> Result<Credentials> {
+    let token = match token_command {
+        Some(command) => command_token(command)?,
+        None => instance_entry(instance)?.get_password().with_context(|| {
+            format!("No token for instance {instance} found in the keyring, run `jt auth login --instance {instance}`")
+        })?,
+    };
+    Ok(Credentials::Token(token))
+}
+
+pub fn store_instance_token(instance: &str, token: &str) -> Result<()> {
+    instance_entry(instance)?
+        .set_password(token)
+        .context("Unable to store token in keyring")
+}
+
+pub fn delete_instance_token(instance: &str) -> Result<()> {
+    match instance_entry(instance)?.delete_credential() {
+        Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+        Err(e) => Err(e).context("Unable to remove token from keyring"),
+    }
+}
+
+///Resolve the credentials for the kind of authentication configured, the password for basic
+///authentication being found wherever a token would be, from the token command if there is one
+pub fn resolve_credentials(
+    profile: Option<&str>,
+    kind: AuthKind,
+    token_command: Option<&str>,
+) -> Result<Credentials> {
+    let secret = || match token_command {
+        Some(command) => command_token(command),
+        None => resolve_token(profile),
+    };
+    match kind {
+        AuthKind::Bearer | AuthKind::Oauth => secret().map(Credentials::Token),
+        AuthKind::Basic => Ok(Credentials::Basic {
+            username: resolve_username(profile)?,
+            password: secret()?,
+        }),
+    }
+}
+
+///How to replace credentials that JIRA no longer accepts
+pub fn renewal_instructions(kind: AuthKind, token_command: bool, base: &Url) -> String {
+    match (kind, token_command) {
+        (AuthKind::Oauth, _) => String::from("The sign-in has lapsed, run `jt auth login` to sign in again"),
+        (_, true) => String::from("Check that token_command prints a current token"),
+        (AuthKind::Basic, false) => String::from(
+            "Check the username and password, then store them again with `jt auth login --basic`",
+        ),
+        (AuthKind::Bearer, false) => format!(
+            "The token has probably expired or been revoked. Create a new one at {base}secure/ViewProfile.jspa?selectedTab=com.atlassian.pats.pats-plugin:jira-user-personal-access-tokens, then store it with `jt auth login` or set {TOKEN_ENV_VAR}"
+        ),
+    }
+}
+
+///The first line a command such as `pass show work/jira` prints, letting it prompt for a
+///passphrase on the terminal
+fn command_token(command: &str) -> Result<String> {
+    let mut words = shell_words::split(command)?;
+    if words.is_empty() {
+        bail!("No program given in token_command");
+    }
+    let program = words.remove(0);
+    let output = Command::new(&program)
+        .args(words)
+        .stdin(Stdio::inherit())
+        .stderr(Stdio::inherit())
+        .output()
+        .with_context(|| format!("Unable to run token command \"{command}\""))?;
+    if !output.status.success() {
+        bail!("Token command \"{command}\" exited with {}", output.status);
+    }
+    let stdout = String::from_utf8(output.stdout)
+        .with_context(|| format!("Token command \"{command}\" printed invalid UTF-8"))?;
+    match stdout.lines().next().map(str::trim) {
+        Some(token) if !token.is_empty() => Ok(token.to_owned()),
+        _ => bail!("Token command \"{command}\" printed no token"),
+    }
+}
+
+fn resolve_username(profile: Option<&str>) -> Result<String> {
+    match username_entry(profile).and_then(|entry| entry.get_password().map_err(|e| e.into())) {
+        Ok(username) => return Ok(username),
+        Err(e) => log::debug!("No username available from keyring: {e}"),
+    }
+    env::var(USERNAME_ENV_VAR).with_context(|| {
+        format!("No username found in the keyring and {USERNAME_ENV_VAR} is not set, run `jt auth login --basic`")
+    })
+}
+
+///Basic authentication if a username has been stored or given in the environment, otherwise a
+///token
+pub fn detect_kind(profile: Option<&str>) -> AuthKind {
+    if resolve_username(profile).is_ok() {
+        AuthKind::Basic
+    } else {
+        AuthKind::Bearer
+    }
+}
+
+///Resolve the JIRA token, preferring the OS keyring and falling back to the environment
+pub fn resolve_token(profile: Option<&str>) -> Result<String> {
+    match keyring_entry(profile).and_then(|entry| entry.get_password().map_err(|e| e.into())) {
+        Ok(token) => return Ok(token),
+        Err(e) => log::debug!("No token available from keyring: {e}"),
+    }
+    env::var(TOKEN_ENV_VAR).with_context(|| {
+        format!("No token found in the keyring and {TOKEN_ENV_VAR} is not set, run `jt auth login`")
+    })
+}
+
+///Where the token would be resolved from, without reading it out
+pub fn token_source(profile: Option<&str>, token_command: Option<&str>) -> Option<&'static str> {
+    if token_command.is_some() {
+        Some("token_command")
+    } else if keyring_entry(profile).is_ok_and(|entry| entry.get_password().is_ok()) {
+        Some("the OS keyring")
+    } else if env::var(TOKEN_ENV_VAR).is_ok() {
+        Some("the JIRA_TOKEN environment variable")
+    } else {
+        None
+    }
+}
+
+pub fn store_token(token: &str, profile: Option<&str>) -> Result<()> {
+    keyring_entry(profile)?
+        .set_password(token)
+        .context("Unable to store token in keyring")
+}
+
+pub fn store_username(username: &str, profile: Option<&str>) -> Result<()> {
+    username_entry(profile)?
+        .set_password(username)
+        .context("Unable to store username in keyring")
+}
+
+///Forget the token, username and password or OAuth refresh token stored for a profile
+pub fn delete_token(profile: Option<&str>) -> Result<()> {
+    let oauth_entry = profile_entry(KEYRING_OAUTH, profile)?;
+    for entry in [
+        keyring_entry(profile)?,
+        username_entry(profile)?,
+        oauth_entry,
+    ] {
+        match entry.delete_credential() {
+            Ok(()) | Err(keyring::Error::NoEntry) => {}
+            Err(e) => return Err(e).context("Unable to remove credentials from keyring"),
+        }
+    }
+    Ok(())
+}
+
+///Other credentials, such as those for importing time, are shared between profiles
+fn credential_entry(name: &str) -> Result<Entry> {
+    Entry::new(KEYRING_SERVICE, name).map_err(|e| e.into())
+}
+
+///A credential stored in the OS keyring, if there is one
+pub fn load_credential(name: &str) -> Option<String> {
+    match credential_entry(name).and_then(|entry| entry.get_password().map_err(|e| e.into())) {
+        Ok(credential) => Some(credential),
+        Err(e) => {
+            log::debug!("No {name} available from keyring: {e}");
+            None
+        }
+    }
+}
+
+pub fn store_credential(name: &str, credential: &str) -> Result<()> {
+    credential_entry(name)?
+        .set_password(credential)
+        .with_context(|| format!("Unable to store {name} in keyring"))
+}
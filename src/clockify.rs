@@ -0,0 +1,116 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, NaiveDate, TimeDelta, Utc};
+use reqwest::{Client, RequestBuilder};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use crate::{
+    import::{self, TrackedTime, TrackerMapping},
+    plan::Plan,
+    week::Timezone,
+};
+
+const CLOCKIFY_API: &str = "https://api.clockify.me/api/v1";
+const PAGE_SIZE: usize = 200;
+
+///How time tracked in Clockify maps to tasks
+#[derive(Serialize, Deserialize)]
+pub struct ClockifyConfig {
+    ///Workspace to read time from, defaulting to the one last used in Clockify
+    pub workspace_id: Option<String>,
+    #[serde(flatten)]
+    pub mapping: TrackerMapping,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct User {
+    id: String,
+    active_workspace: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TimeEntry {
+    #[serde(default)]
+    description: String,
+    time_interval: TimeInterval,
+    project: Option<Named>,
+    #[serde(default)]
+    tags: Vec<Named>,
+}
+
+#[derive(Deserialize)]
+struct TimeInterval {
+    start: DateTime<Utc>,
+    ///Absent while the timer is still running
+    end: Option<DateTime<Utc>>,
+}
+
+#[derive(Deserialize)]
+struct Named {
+    name: String,
+}
+
+///A plan logging the user's time in Clockify between two days inclusive against the tasks it maps
+///to
+pub async fn import(
+    config: &ClockifyConfig,
+    from: NaiveDate,
+    to: NaiveDate,
+    timezone: Timezone,
+) -> Result<Plan> {
+    let token = import::api_token("clockify-api-key", "Clockify API key")?;
+    let client = Client::new();
+    let request = |path: &str| {
+        client
+            .get(format!("{CLOCKIFY_API}/{path}"))
+            .header("X-Api-Key", &token)
+    };
+    let user: User = send(request("user")).await?;
+    let workspace = config
+        .workspace_id
+        .as_deref()
+        .unwrap_or(&user.active_workspace);
+    let path = format!("workspaces/{workspace}/user/{}/time-entries", user.id);
+    let start = format!("{}T00:00:00Z", from - TimeDelta::days(1));
+    let end = format!("{}T00:00:00Z", to + TimeDelta::days(2));
+    let page_size = PAGE_SIZE.to_string();
+    let mut tracked = Vec::new();
+    for page in 1.. {
+        let page = page.to_string();
+        let entries: Vec<TimeEntry> = send(request(&path).query(&[
+            ("start", start.as_str()),
+            ("end", end.as_str()),
+            ("hydrated", "true"),
+            ("page", page.as_str()),
+            ("page-size", page_size.as_str()),
+        ]))
+        .await?;
+        let last_page = entries.len() < PAGE_SIZE;
+        tracked.extend(entries.into_iter().filter_map(|entry| {
+            let interval = entry.time_interval;
+            Some(TrackedTime {
+                day: timezone.local_time(interval.start).date(),
+                time_spent: interval.end? - interval.start,
+                description: entry.description,
+                project: entry.project.map(|project| project.name),
+                tags: entry.tags.into_iter().map(|tag| tag.name).collect(),
+            })
+        }));
+        if last_page {
+            break;
+        }
+    }
+    Ok(import::tracked_plan(tracked, &config.mapping, from, to))
+}
+
+async fn send<T: DeserializeOwned>(request: RequestBuilder) -> Result<T> {
+    request
+        .send()
+        .await?
+        .error_for_status()
+        .context("Unable to read from Clockify, check the API key")?
+        .json()
+        .await
+        .map_err(|e| e.into())
+}
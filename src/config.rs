@@ -1,43 +1,627 @@
-use std::{fs, path::PathBuf};
+use std::{collections::HashMap, fmt, fs, path::PathBuf, sync::OnceLock};
 
-use anyhow::Result;
+use anyhow::{bail, Context, Result};
+use chrono::{Datelike, NaiveDate, NaiveTime, TimeDelta, Weekday};
+use jt_core::options::{
+    HttpOptions, Performance, RemainingEstimate, TempoVersion, UserIdentifier, Visibility,
+    WorkAttribute,
+};
 use reqwest::Url;
-use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde::{
+    de::{MapAccess, Visitor},
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+use toml::{Table, Value};
+use toml_edit::{value, DocumentMut, Item, TableLike};
+
+use crate::{
+    auth::AuthKind,
+    client::{Customer, Issue},
+    duration::{deserialize_minutes, deserialize_weekday_minutes, round_duration, Rounding},
+    endpoint,
+    git::GitConfig,
+    import::ImportConfig,
+    locale::NumberFormat,
+    oauth::OauthConfig,
+    plan::PlanEntry,
+    state::StorageKind,
+    template::Template,
+    week::{parse_timezone, Timezone, DEFAULT_WORKING_DAYS},
+};
 
 #[derive(Serialize, Deserialize)]
 pub struct Config {
     #[serde(deserialize_with = "deserialize_url", serialize_with = "serialize_url")]
     pub api_endpoint: Url,
+    ///Path Jira is served below, eg "/jira", if not already part of the endpoint
+    pub context_root: Option<String>,
     pub worker: String,
     pub reviewer: Option<String>,
+    ///Workers whose timesheets you review, offered by `approve` and `reject`
+    #[serde(default, skip_serializing)]
+    pub team: Vec<String>,
+    #[serde(default)]
+    pub user_identifier: UserIdentifier,
+    ///Whether to authenticate with a `bearer` token, `basic` username and password or by signing in
+    ///with `oauth`
+    #[serde(default)]
+    pub auth: AuthKind,
+    ///Command printing the token, or password, eg "pass show work/jira", run in place of reading it
+    ///from the keyring or the environment
+    pub token_command: Option<String>,
+    ///The OAuth client to sign in with when `auth = "oauth"`
+    pub oauth: Option<OauthConfig>,
+    ///Tempo Timesheets REST API version, detected automatically when not set
+    pub tempo_api_version: Option<TempoVersion>,
+    #[serde(default, deserialize_with = "deserialize_minutes")]
     pub daily_target_time_spent_minutes: Option<u64>,
+    ///Targets for particular weekdays, eg `fri = "6h"`, overriding the daily target
+    #[serde(
+        default,
+        skip_serializing,
+        deserialize_with = "deserialize_weekday_minutes"
+    )]
+    pub daily_targets: HashMap<Weekday, u64>,
+    #[serde(default, deserialize_with = "deserialize_minutes")]
     pub default_time_spent_minutes: Option<u64>,
+    ///Locale used to interpret numbers and durations typed at prompts or given in plan files,
+    ///eg "de-DE", defaulting to the environment's locale
+    pub locale: Option<String>,
+    ///Days of the week worked, eg ["mon", "tue", "wed", "thu"], defaulting to Monday to Friday
+    #[serde(default = "default_working_days")]
+    pub working_days: Vec<Weekday>,
+    ///Timezone, eg "Europe/Berlin", or offset from UTC, eg "+02:00", in which to work out today's
+    ///date and the current week, defaulting to the system's timezone
+    pub timezone: Option<String>,
+    ///Country code, eg "GB", whose public holidays are skipped when filling a week
+    pub holiday_country: Option<String>,
+    ///ICS calendar file whose events are skipped as holidays when filling a week
+    pub holiday_calendar: Option<PathBuf>,
+    ///Where state kept between runs, such as remembered attributes and receipts, is stored
+    #[serde(default)]
+    pub storage: StorageKind,
+    ///Always notify when finished or waiting for input, as with `fill --notify`
+    #[serde(default)]
+    pub notify: bool,
+    ///Most distinct issues that should be logged against on a single day
+    pub max_issues_per_day: Option<usize>,
+    ///Time of day, eg "09:00", from which each day's worklogs are given consecutive start times
+    ///rather than all starting at midnight
+    pub workday_start: Option<NaiveTime>,
+    ///Longest single worklog the Tempo instance accepts, longer entries are split
+    #[serde(default, deserialize_with = "deserialize_minutes")]
+    pub max_worklog_minutes: Option<u64>,
+    ///Granularity, eg "15m", that every duration is rounded to before being logged
+    #[serde(default, deserialize_with = "deserialize_minutes")]
+    pub rounding_minutes: Option<u64>,
+    ///Whether durations are rounded `up`, `down` or to the `nearest` multiple of `rounding_minutes`
+    #[serde(default)]
+    pub rounding: Rounding,
+    ///What to do to the remaining estimate of issues logged against
+    #[serde(default)]
+    pub remaining_estimate: RemainingEstimate,
+    ///JQL selecting the issues offered as tasks, with `{from}` standing for the date from which
+    ///recently completed issues are still wanted
+    pub jql: Option<String>,
+    ///Alternative searches for tasks, selected by name with `fill --filter`
     #[serde(default, skip_serializing)]
-    pub static_tasks: Vec<StaticTask>,
+    pub filters: HashMap<String, Filter>,
+    ///Proxy, eg "http://proxy.corp:3128", through which to reach Jira, overriding `HTTPS_PROXY`
+    #[serde(
+        default,
+        deserialize_with = "deserialize_optional_url",
+        serialize_with = "serialize_optional_url"
+    )]
+    pub proxy: Option<Url>,
+    ///PEM file of extra root certificates to trust, eg an internal CA that signed Jira's certificate
+    pub ca_certificate: Option<PathBuf>,
+    ///Accept any certificate from Jira, leaving connections open to interception
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub danger_accept_invalid_certs: bool,
+    ///Tuning for instances with very many issues, off unless configured
+    pub performance: Option<Performance>,
+    ///Local repositories whose commits bring issues to the top of the task picker when filling
+    pub git: Option<GitConfig>,
+    ///Calendars and time trackers that `import` reads time from
+    #[serde(default)]
+    pub import: ImportConfig,
+    ///Other JIRA instances whose assigned issues are offered as tasks, read only
     #[serde(default, skip_serializing)]
+    pub instances: Vec<Instance>,
+    #[serde(
+        default,
+        skip_serializing_if = "Vec::is_empty",
+        deserialize_with = "deserialize_static_tasks"
+    )]
+    pub static_tasks: Vec<StaticTask>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub static_attributes: Vec<WorkAttribute>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub dynamic_attributes: Vec<DynamicAttribute>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub prompted_attributes: Vec<PromptedAttribute>,
+    ///Days away from work, skipped or logged against `absence_tasks` when filling a week
+    #[serde(default, skip_serializing)]
+    pub absences: Vec<Absence>,
+    ///Task to log the full daily target against for each kind of absence, eg `vacation = "TEMPO-1"`
+    #[serde(default, skip_serializing)]
+    pub absence_tasks: HashMap<String, String>,
+    ///Who can see worklogs in JIRA, by project key, eg to keep client projects' time internal
+    #[serde(default, skip_serializing)]
+    pub worklog_visibility: HashMap<String, Visibility>,
+    ///Attributes added to worklogs on every issue in a project, by project key, eg for an account
+    ///that differs between projects
+    #[serde(default, skip_serializing)]
+    pub project_attributes: HashMap<String, Vec<WorkAttribute>>,
+    ///What `#tags` on entries mean, by tag name without the `#`
+    #[serde(default, skip_serializing)]
+    pub tags: HashMap<String, TagMapping>,
+    ///Share of each week committed to each project, as percentages by project key, checked by
+    ///`balance`
+    #[serde(default, skip_serializing)]
+    pub commitments: HashMap<String, f64>,
+    ///Rules choosing attributes for worklogs on Jira Service Management requests by customer
+    #[serde(default, skip_serializing)]
+    pub service_desk: ServiceDesk,
+    ///Entries each weekday starts with when filling interactively, for the user to confirm or adjust
     #[serde(default, skip_serializing)]
-    pub dynamic_attributes: Vec<WorkAttribute>,
+    pub template: HashMap<Weekday, Vec<PlanEntry>>,
 }
 
-#[derive(Serialize, Deserialize)]
+const DEFAULT_DAILY_TARGET: TimeDelta = TimeDelta::hours(8);
+
+fn default_working_days() -> Vec<Weekday> {
+    DEFAULT_WORKING_DAYS.to_vec()
+}
+
+impl Config {
+    ///A configuration logging as `worker` on the instance at `api_endpoint`, with everything else
+    ///left at its default
+    pub fn new(
+        api_endpoint: Url,
+        worker: String,
+        user_identifier: UserIdentifier,
+        auth: AuthKind,
+    ) -> Config {
+        Config {
+            api_endpoint,
+            context_root: None,
+            worker,
+            reviewer: None,
+            user_identifier,
+            auth,
+            token_command: None,
+            oauth: None,
+            tempo_api_version: None,
+            locale: None,
+            timezone: None,
+            working_days: default_working_days(),
+            holiday_country: None,
+            holiday_calendar: None,
+            notify: false,
+            max_issues_per_day: None,
+            workday_start: None,
+            daily_target_time_spent_minutes: None,
+            daily_targets: HashMap::new(),
+            default_time_spent_minutes: None,
+            max_worklog_minutes: None,
+            rounding_minutes: None,
+            rounding: Default::default(),
+            remaining_estimate: Default::default(),
+            jql: None,
+            filters: HashMap::new(),
+            proxy: None,
+            ca_certificate: None,
+            danger_accept_invalid_certs: false,
+            performance: None,
+            git: None,
+            import: Default::default(),
+            instances: Vec::new(),
+            static_tasks: Vec::new(),
+            static_attributes: Vec::new(),
+            dynamic_attributes: Vec::new(),
+            prompted_attributes: Vec::new(),
+            absences: Vec::new(),
+            absence_tasks: HashMap::new(),
+            worklog_visibility: HashMap::new(),
+            project_attributes: HashMap::new(),
+            tags: HashMap::new(),
+            template: HashMap::new(),
+            storage: Default::default(),
+            commitments: HashMap::new(),
+            team: Vec::new(),
+            service_desk: Default::default(),
+        }
+    }
+
+    ///Target time spent for a day, from the per-weekday targets if the day has one
+    pub fn daily_target(&self, day: NaiveDate) -> TimeDelta {
+        self.daily_targets
+            .get(&day.weekday())
+            .or(self.daily_target_time_spent_minutes.as_ref())
+            .map(|minutes| TimeDelta::minutes(*minutes as i64))
+            .unwrap_or(DEFAULT_DAILY_TARGET)
+    }
+
+    ///Network settings for the Jira client
+    pub fn http_options(&self) -> HttpOptions {
+        HttpOptions {
+            proxy: self.proxy.clone(),
+            ca_certificate: self.ca_certificate.clone(),
+            accept_invalid_certs: self.danger_accept_invalid_certs,
+        }
+    }
+
+    ///Base URL of the Jira instance, including any context root
+    pub fn base_url(&self) -> Result<Url> {
+        Ok(endpoint::base_url(
+            &self.api_endpoint,
+            self.context_root.as_deref(),
+        )?)
+    }
+
+    ///A duration rounded as configured, unchanged if no rounding is
+    pub fn round(&self, duration: TimeDelta) -> TimeDelta {
+        match self.rounding_minutes {
+            Some(minutes) => {
+                round_duration(duration, TimeDelta::minutes(minutes as i64), self.rounding)
+            }
+            None => duration,
+        }
+    }
+
+    ///The configured timezone, the system's if none is configured
+    pub fn timezone(&self) -> Result<Timezone> {
+        Ok(self
+            .timezone
+            .as_deref()
+            .map(parse_timezone)
+            .transpose()?
+            .unwrap_or_default())
+    }
+
+    pub fn number_format(&self) -> NumberFormat {
+        NumberFormat::resolve(self.locale.as_deref())
+    }
+
+    ///Search for tasks using the named filter rather than the default JQL
+    pub fn use_filter(&mut self, name: &str) -> Result<()> {
+        let Some(filter) = self.filters.get(name) else {
+            let mut names: Vec<&str> = self.filters.keys().map(String::as_str).collect();
+            names.sort();
+            if names.is_empty() {
+                bail!("No filter named \"{name}\", no filters are configured");
+            }
+            bail!(
+                "No filter named \"{name}\", expected one of {}",
+                names.join(", ")
+            );
+        };
+        self.jql = Some(filter.jql.clone());
+        Ok(())
+    }
+
+    ///Name and ID of a work attribute, from wherever the configuration sets it, if it does
+    pub fn configured_attribute(&self, key: &str) -> Option<(&str, u64)> {
+        let set = self
+            .static_attributes
+            .iter()
+            .chain(self.project_attributes.values().flatten())
+            .chain(self.static_tasks.iter().flat_map(|task| &task.attributes))
+            .chain(self.tags.values().flat_map(|mapping| &mapping.attributes))
+            .chain(
+                self.service_desk
+                    .rules
+                    .iter()
+                    .flat_map(|rule| &rule.attributes),
+            )
+            .chain(
+                self.dynamic_attributes
+                    .iter()
+                    .map(|dynamic| &dynamic.attribute),
+            )
+            .find(|attribute| attribute.key == key)
+            .map(|attribute| (attribute.name.as_str(), attribute.work_attribute_id));
+        set.or_else(|| {
+            self.prompted_attributes
+                .iter()
+                .find(|prompted| prompted.key == key)
+                .map(|prompted| (prompted.name.as_str(), prompted.work_attribute_id))
+        })
+    }
+
+    pub fn absence_on(&self, day: NaiveDate) -> Option<&Absence> {
+        self.absences.iter().find(|absence| absence.covers(day))
+    }
+
+    pub fn default_time_spent(&self) -> Option<TimeDelta> {
+        self.default_time_spent_minutes
+            .map(|minutes| TimeDelta::minutes(minutes as i64))
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
 pub struct StaticTask {
     pub key: String,
     #[serde(default)]
     pub description: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub attributes: Vec<WorkAttribute>,
+    ///Comment attached to worklogs for this task unless another is given
+    pub comment: Option<String>,
+    ///Share of the daily target, in percent, logged against this task every day without prompting
+    pub percent: Option<f64>,
+    ///Time offered when the task is selected, eg "30m"
+    #[serde(default, deserialize_with = "deserialize_minutes")]
+    pub default_time_spent_minutes: Option<u64>,
+    ///Who can see worklogs against this task, overriding any set for its project
+    pub visibility: Option<Visibility>,
+    ///Whether time on this task is billable, left to Tempo's default if not given
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub billable: Option<bool>,
+    ///Leave the task out entirely, eg once its issue has been deleted
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub disabled: bool,
+    ///Name of the group this task was declared in, if any
+    #[serde(skip)]
+    pub group: Option<String>,
+}
+
+///A fix for a static task whose issue no longer exists
+pub enum StaticTaskRepair {
+    ///Point the task at another issue
+    Rekey(String),
+    Disable,
+}
+
+///A named search for the issues to offer as tasks
+#[derive(Deserialize)]
+pub struct Filter {
+    ///JQL in the same form as the top level `jql`
+    pub jql: String,
+}
+
+///Effects of tagging an entry, eg with `#oncall`
+#[derive(Deserialize, Default)]
+pub struct TagMapping {
+    ///Attributes set on worklogs for tagged entries
     #[serde(default)]
     pub attributes: Vec<WorkAttribute>,
+    ///Text appended to the comment of tagged entries
+    pub comment: Option<String>,
+    ///Name to total tagged work under in the preview, defaulting to the tag itself
+    pub group: Option<String>,
+}
+
+///A range of days away from work, such as a vacation or sickness
+#[derive(Deserialize)]
+pub struct Absence {
+    #[serde(deserialize_with = "deserialize_date")]
+    pub from: NaiveDate,
+    ///Last day of the absence, defaulting to a single day
+    #[serde(default, deserialize_with = "deserialize_optional_date")]
+    pub to: Option<NaiveDate>,
+    ///Kind of absence, eg "vacation" or "sick"
+    pub kind: String,
+}
+
+impl Absence {
+    pub fn covers(&self, day: NaiveDate) -> bool {
+        day >= self.from && day <= self.to.unwrap_or(self.from)
+    }
+}
+
+///How worklogs on Jira Service Management requests are attributed to customers
+#[derive(Deserialize, Default)]
+pub struct ServiceDesk {
+    ///Field holding the organisations of each request, eg `customfield_10002`, found
+    ///automatically when not set
+    pub organization_field: Option<String>,
+    #[serde(default)]
+    pub rules: Vec<ServiceDeskRule>,
+}
+
+///Attributes for requests from an organisation or customer, where the first matching rule applies
+#[derive(Deserialize)]
+pub struct ServiceDeskRule {
+    ///Name of an organisation the request belongs to
+    pub organization: Option<String>,
+    ///Customer who raised the request, by email address, email domain (eg "acme.com") or name
+    pub customer: Option<String>,
+    pub attributes: Vec<WorkAttribute>,
+}
+
+impl ServiceDeskRule {
+    pub fn matches(&self, organizations: &[String], customer: Option<&Customer>) -> bool {
+        let organization_matches = self.organization.as_ref().map(|wanted| {
+            organizations
+                .iter()
+                .any(|organization| organization.eq_ignore_ascii_case(wanted))
+        });
+        let customer_matches = self
+            .customer
+            .as_ref()
+            .map(|wanted| customer.is_some_and(|customer| customer.is(wanted)));
+        match (organization_matches, customer_matches) {
+            (None, None) => false,
+            (organization, customer) => organization.unwrap_or(true) && customer.unwrap_or(true),
+        }
+    }
+}
+
+///Another JIRA instance whose assigned issues are offered as tasks, time on them being logged
+///against an issue on the main instance with the other issue named in the comment
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Instance {
+    ///Name tagging the instance's issues in the task picker, and its token in the keyring
+    pub name: String,
+    #[serde(deserialize_with = "deserialize_url", serialize_with = "serialize_url")]
+    pub api_endpoint: Url,
+    pub context_root: Option<String>,
+    ///Issue on the main instance that time on this instance's issues is logged against
+    pub task: String,
+    ///JQL selecting the issues offered, defaulting to those assigned to the user
+    pub jql: Option<String>,
+    ///Command printing the token for this instance, read from the keyring if not given
+    pub token_command: Option<String>,
+}
+
+impl Instance {
+    pub fn base_url(&self) -> Result<Url> {
+        Ok(endpoint::base_url(
+            &self.api_endpoint,
+            self.context_root.as_deref(),
+        )?)
+    }
+}
+
+///An attribute whose value is read from each issue using the JSON pointer in `value`, or composed
+///from several of its fields by a template such as `{fields.project.key}-{fields.issuetype.name}`
+#[derive(Serialize, Deserialize, Clone)]
+pub struct DynamicAttribute {
+    #[serde(flatten)]
+    pub attribute: WorkAttribute,
+    ///Value used for issues the pointer cannot be resolved on, eg where the field is hidden
+    pub fallback: Option<String>,
+}
+
+impl DynamicAttribute {
+    fn template(&self) -> Option<Result<Template>> {
+        let value = &self.attribute.value;
+        Template::is_template(value).then(|| value.parse())
+    }
+
+    ///The issue fields the attribute reads from, eg `customfield_12345`
+    pub fn fields(&self) -> Vec<String> {
+        match self.template() {
+            Some(Ok(template)) => template.fields().map(String::from).collect(),
+            Some(Err(_)) => Vec::new(),
+            None => self
+                .attribute
+                .value
+                .trim_start_matches('/')
+                .split('/')
+                .next()
+                .filter(|field| !field.is_empty())
+                .map(String::from)
+                .into_iter()
+                .collect(),
+        }
+    }
+
+    ///What is wrong with the attribute's JSON pointer or template, if it is malformed
+    pub fn value_problem(&self) -> Option<String> {
+        if let Some(template) = self.template() {
+            return template.err().map(|e| format!("{e:#}"));
+        }
+        let pointer = &self.attribute.value;
+        if !pointer.starts_with('/') {
+            return Some(format!("\"{pointer}\" does not start with /"));
+        }
+        pointer
+            .match_indices('~')
+            .find(|(i, _)| !matches!(pointer.as_bytes().get(i + 1), Some(b'0' | b'1')))
+            .map(|_| format!("\"{pointer}\" has a ~ not followed by 0 or 1"))
+    }
+
+    ///The attribute's value for an issue, or None if a field it reads is missing
+    pub fn resolve(&self, issue: &Issue) -> Result<Option<String>> {
+        if let Some(template) = self.template() {
+            return template?
+                .render(&serde_json::to_value(issue)?)
+                .with_context(|| {
+                    format!(
+                        "Unable to fill in {} for {}",
+                        self.attribute.name, issue.key
+                    )
+                });
+        }
+        let pointer = &self.attribute.value;
+        let fields = serde_json::to_value(&issue.fields)?;
+        fields
+            .pointer(pointer)
+            .filter(|value| !value.is_null())
+            .map(|pointed| {
+                pointed.as_str().map(String::from).with_context(|| {
+                    format!(
+                        "JSON pointer {pointer} does not point to string value on {}",
+                        issue.key
+                    )
+                })
+            })
+            .transpose()
+    }
 }
 
+///An attribute whose value is chosen interactively the first time an issue is logged against
 #[derive(Serialize, Deserialize, Clone)]
-pub struct WorkAttribute {
+pub struct PromptedAttribute {
     pub key: String,
     pub name: String,
     pub work_attribute_id: u64,
-    pub value: String,
+    ///Values to choose between, any value may be entered if empty
+    #[serde(default)]
+    pub options: Vec<String>,
 }
 
 const CONFIG_FILE_NAME: &str = "jt.toml";
+const PROFILES_KEY: &str = "profiles";
+///Parts of key names that mark their values as secret
+const SECRET_KEYS: [&str; 4] = ["token", "password", "secret", "credential"];
+const REDACTED: &str = "REDACTED";
+
+///How the value of an environment variable override is read
+#[derive(Clone, Copy)]
+enum EnvValue {
+    Text,
+    ///A whole number, or a duration such as `7h30m` for settings in minutes
+    Number,
+    Flag,
+}
+
+///Environment variables that override values in the configuration file, and the keys they set
+const ENV_OVERRIDES: [(&str, &str, EnvValue); 20] = [
+    ("JT_API_ENDPOINT", "api_endpoint", EnvValue::Text),
+    ("JT_CONTEXT_ROOT", "context_root", EnvValue::Text),
+    ("JT_WORKER", "worker", EnvValue::Text),
+    ("JT_REVIEWER", "reviewer", EnvValue::Text),
+    ("JT_USER_IDENTIFIER", "user_identifier", EnvValue::Text),
+    ("JT_AUTH", "auth", EnvValue::Text),
+    ("JT_TOKEN_COMMAND", "token_command", EnvValue::Text),
+    (
+        "JT_TEMPO_API_VERSION",
+        "tempo_api_version",
+        EnvValue::Number,
+    ),
+    (
+        "JT_DAILY_TARGET_MINUTES",
+        "daily_target_time_spent_minutes",
+        EnvValue::Number,
+    ),
+    (
+        "JT_DEFAULT_TIME_SPENT_MINUTES",
+        "default_time_spent_minutes",
+        EnvValue::Number,
+    ),
+    ("JT_LOCALE", "locale", EnvValue::Text),
+    ("JT_TIMEZONE", "timezone", EnvValue::Text),
+    ("JT_HOLIDAY_COUNTRY", "holiday_country", EnvValue::Text),
+    ("JT_STORAGE", "storage", EnvValue::Text),
+    ("JT_NOTIFY", "notify", EnvValue::Flag),
+    (
+        "JT_MAX_ISSUES_PER_DAY",
+        "max_issues_per_day",
+        EnvValue::Number,
+    ),
+    (
+        "JT_MAX_WORKLOG_MINUTES",
+        "max_worklog_minutes",
+        EnvValue::Number,
+    ),
+    ("JT_ROUNDING_MINUTES", "rounding_minutes", EnvValue::Number),
+    ("JT_ROUNDING", "rounding", EnvValue::Text),
+    ("JT_JQL", "jql", EnvValue::Text),
+];
 
 fn deserialize_url<'de, D>(deserializer: D) -> Result<Url, D::Error>
 where
@@ -47,6 +631,97 @@ where
     Url::parse(&buf).map_err(serde::de::Error::custom)
 }
 
+fn deserialize_optional_url<'de, D>(deserializer: D) -> Result<Option<Url>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Option::<String>::deserialize(deserializer)?
+        .map(|buf| Url::parse(&buf).map_err(serde::de::Error::custom))
+        .transpose()
+}
+
+///Dates may be given either as TOML dates or as strings
+fn deserialize_date<'de, D>(deserializer: D) -> Result<NaiveDate, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let date = match Value::deserialize(deserializer)? {
+        Value::Datetime(datetime) => datetime.date.and_then(|date| {
+            NaiveDate::from_ymd_opt(date.year.into(), date.month.into(), date.day.into())
+        }),
+        Value::String(date) => date.parse().ok(),
+        _ => None,
+    };
+    date.ok_or_else(|| serde::de::Error::custom("expected a date such as 2024-08-05"))
+}
+
+fn deserialize_optional_date<'de, D>(deserializer: D) -> Result<Option<NaiveDate>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    deserialize_date(deserializer).map(Some)
+}
+
+///Static tasks may either be a flat list or organised into named groups
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum StaticTasks {
+    Flat(Vec<StaticTask>),
+    Grouped(GroupedStaticTasks),
+}
+
+///Groups in the order they are declared in the configuration file
+struct GroupedStaticTasks(Vec<(String, Vec<StaticTask>)>);
+
+impl<'de> Deserialize<'de> for GroupedStaticTasks {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct GroupsVisitor;
+
+        impl<'de> Visitor<'de> for GroupsVisitor {
+            type Value = GroupedStaticTasks;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a table of static task groups")
+            }
+
+            fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+            where
+                A: MapAccess<'de>,
+            {
+                let mut groups = Vec::new();
+                while let Some(group) = map.next_entry::<String, Vec<StaticTask>>()? {
+                    groups.push(group);
+                }
+                Ok(GroupedStaticTasks(groups))
+            }
+        }
+
+        deserializer.deserialize_map(GroupsVisitor)
+    }
+}
+
+fn deserialize_static_tasks<'de, D>(deserializer: D) -> Result<Vec<StaticTask>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let tasks = match StaticTasks::deserialize(deserializer)? {
+        StaticTasks::Flat(tasks) => tasks,
+        StaticTasks::Grouped(GroupedStaticTasks(groups)) => groups
+            .into_iter()
+            .flat_map(|(group, tasks)| {
+                tasks.into_iter().map(move |mut task| {
+                    task.group = Some(group.clone());
+                    task
+                })
+            })
+            .collect(),
+    };
+    Ok(tasks)
+}
+
 fn serialize_url<S>(url: &Url, s: S) -> Result<S::Ok, S::Error>
 where
     S: Serializer,
@@ -54,17 +729,421 @@ where
     s.serialize_str(url.as_str())
 }
 
+fn serialize_optional_url<S>(url: &Option<Url>, s: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    match url {
+        Some(url) => s.serialize_some(url.as_str()),
+        None => s.serialize_none(),
+    }
+}
+
+static CONFIG_FILE: OnceLock<PathBuf> = OnceLock::new();
+
+///Read and write the configuration at the given path for the rest of the run, rather than in the
+///platform's configuration directory
+pub fn use_config_file(path: PathBuf) {
+    if CONFIG_FILE.set(path).is_err() {
+        log::warn!("Configuration file already chosen for this run");
+    }
+}
+
 pub fn config_file_location() -> PathBuf {
+    if let Some(path) = CONFIG_FILE.get() {
+        return path.clone();
+    }
     let dir = dirs::config_dir().expect("Unable to determine configuration directory");
     dir.join(CONFIG_FILE_NAME)
 }
 
-pub fn load_config() -> Result<Config> {
-    let content = fs::read_to_string(config_file_location())?;
-    toml::from_str(&content).map_err(|e| e.into())
+///The configuration file's values, with any from the named profile overriding those at the top level
+///and any from the environment overriding both
+fn merged_table(profile: Option<&str>) -> Result<Table> {
+    let overrides = env_overrides()?;
+    let location = config_file_location();
+    let mut table: Table = match fs::read_to_string(&location) {
+        Ok(content) => toml::from_str(&content)?,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound && !overrides.is_empty() => Table::new(),
+        Err(e) => return Err(e).with_context(|| format!("Unable to read {}", location.display())),
+    };
+    let profiles = table.remove(PROFILES_KEY);
+    if let Some(name) = profile {
+        let overrides = profiles
+            .as_ref()
+            .and_then(|profiles| profiles.get(name))
+            .and_then(Value::as_table)
+            .with_context(|| format!("No profile named \"{name}\" in configuration"))?;
+        table.extend(overrides.clone());
+    }
+    table.extend(overrides);
+    Ok(table)
+}
+
+///Names of the `JT_` environment variables currently overriding the configuration
+pub fn env_override_vars() -> Vec<&'static str> {
+    ENV_OVERRIDES
+        .iter()
+        .map(|(var, _, _)| *var)
+        .filter(|var| std::env::var(var).is_ok())
+        .collect()
+}
+
+///Values set by `JT_` environment variables, which take precedence over the file and any profile
+fn env_overrides() -> Result<Table> {
+    let mut table = Table::new();
+    for (var, key, kind) in ENV_OVERRIDES {
+        let Ok(raw) = std::env::var(var) else {
+            continue;
+        };
+        let value = match kind {
+            EnvValue::Text => Value::String(raw),
+            EnvValue::Number => raw
+                .parse()
+                .map_or_else(|_| Value::String(raw), Value::Integer),
+            EnvValue::Flag => Value::Boolean(
+                raw.parse()
+                    .with_context(|| format!("{var} must be true or false, not \"{raw}\""))?,
+            ),
+        };
+        table.insert(String::from(key), value);
+    }
+    Ok(table)
+}
+
+///The configuration in effect for the named profile, with defaults filled in and anything
+///sensitive redacted
+pub fn effective_table(profile: Option<&str>, config: &Config) -> Result<Table> {
+    let mut table = merged_table(profile)?;
+    table.extend(Table::try_from(config)?);
+    table.retain(|_, value| match value {
+        Value::Array(values) => !values.is_empty(),
+        Value::Table(values) => !values.is_empty(),
+        _ => true,
+    });
+    redact_table(&mut table);
+    Ok(table)
+}
+
+fn redact_table(table: &mut Table) {
+    for (key, value) in table.iter_mut() {
+        let key = key.to_lowercase();
+        if SECRET_KEYS.iter().any(|secret| key.contains(secret)) {
+            *value = Value::String(String::from(REDACTED));
+        } else {
+            redact(value);
+        }
+    }
+}
+
+///Hide secrets wherever they appear: values of keys that name one, and passwords in URLs
+fn redact(value: &mut Value) {
+    match value {
+        Value::Table(table) => redact_table(table),
+        Value::Array(values) => values.iter_mut().for_each(redact),
+        Value::String(text) => {
+            if let Ok(mut url) = Url::parse(text) {
+                if url.password().is_some() && url.set_password(Some(REDACTED)).is_ok() {
+                    *text = url.to_string();
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+///Load the configuration, with any values from the named profile overriding those at the top level
+pub fn load_config(profile: Option<&str>) -> Result<Config> {
+    let mut config: Config = merged_table(profile)?.try_into()?;
+    config.static_tasks.retain(|task| !task.disabled);
+    let allocated: f64 = config.static_tasks.iter().filter_map(|t| t.percent).sum();
+    if allocated > 100.0 {
+        bail!("Static task allocations add up to {allocated}%, more than the whole day");
+    }
+    config.timezone()?;
+    if config.working_days.is_empty() {
+        bail!("No working days configured");
+    }
+    let committed: f64 = config.commitments.values().sum();
+    if committed > 100.0 {
+        bail!("Project commitments add up to {committed}%, more than the whole week");
+    }
+    if let Some(i) = config
+        .service_desk
+        .rules
+        .iter()
+        .position(|rule| rule.organization.is_none() && rule.customer.is_none())
+    {
+        bail!(
+            "Service desk rule {} needs an organization or customer to match on",
+            i + 1
+        );
+    }
+    Ok(config)
+}
+
+///Apply a repair to every declaration of the static task with the given key, at the top level and
+///in the named profile, editing the configuration file in place so its layout and comments are kept
+pub fn repair_static_task(
+    profile: Option<&str>,
+    key: &str,
+    repair: &StaticTaskRepair,
+) -> Result<()> {
+    let location = config_file_location();
+    let mut document: DocumentMut = fs::read_to_string(&location)?.parse()?;
+    let mut repaired = 0;
+    let mut apply = |task: &mut dyn TableLike| {
+        let Some(current) = task
+            .get_mut("key")
+            .and_then(Item::as_value_mut)
+            .filter(|current| current.as_str() == Some(key))
+        else {
+            return;
+        };
+        match repair {
+            StaticTaskRepair::Rekey(new_key) => {
+                let decor = current.decor().clone();
+                *current = new_key.as_str().into();
+                *current.decor_mut() = decor;
+            }
+            StaticTaskRepair::Disable => {
+                task.insert("disabled", value(true));
+            }
+        }
+        repaired += 1;
+    };
+    if let Some(tasks) = document.get_mut("static_tasks") {
+        visit_static_tasks(tasks, &mut apply);
+    }
+    if let Some(tasks) = profile.and_then(|name| {
+        document
+            .get_mut(PROFILES_KEY)?
+            .get_mut(name)?
+            .get_mut("static_tasks")
+    }) {
+        visit_static_tasks(tasks, &mut apply);
+    }
+    if repaired == 0 {
+        bail!("Static task {key} not found in {}", location.display());
+    }
+    fs::write(location, document.to_string()).map_err(|e| e.into())
+}
+
+///Append a static task to the list in effect for the named profile, its own if it declares one and
+///otherwise the top level's, into `group` if static tasks are declared in groups. The file is
+///edited in place so its layout and comments are kept.
+pub fn add_static_task(
+    profile: Option<&str>,
+    task: &StaticTask,
+    group: Option<&str>,
+) -> Result<()> {
+    let location = config_file_location();
+    let mut document: DocumentMut = fs::read_to_string(&location)?.parse()?;
+    let task: DocumentMut = toml::to_string(task)?.parse()?;
+    let mut task = task.as_table().clone();
+    let nested: Vec<String> = task
+        .iter()
+        .filter(|(_, item)| item.is_array_of_tables())
+        .map(|(key, _)| key.to_owned())
+        .collect();
+    for key in nested {
+        if let Some(Item::ArrayOfTables(tables)) = task.remove(&key) {
+            task.insert(&key, value(tables.into_array()));
+        }
+    }
+    let own_tasks = profile.is_some_and(|name| {
+        document
+            .get(PROFILES_KEY)
+            .and_then(|profiles| profiles.get(name))
+            .and_then(Item::as_table_like)
+            .is_some_and(|profile| profile.contains_key("static_tasks"))
+    });
+    let into_profile = profile.filter(|_| own_tasks || !document.contains_key("static_tasks"));
+    let mut parent = document.as_table_mut();
+    if let Some(name) = into_profile {
+        parent = parent
+            .entry(PROFILES_KEY)
+            .or_insert_with(implicit_table)
+            .as_table_mut()
+            .context("Profiles are not a table")?
+            .entry(name)
+            .or_insert_with(implicit_table)
+            .as_table_mut()
+            .with_context(|| format!("Profile \"{name}\" is not a table"))?;
+    }
+    let mut tasks = parent
+        .entry("static_tasks")
+        .or_insert(Item::ArrayOfTables(Default::default()));
+    if let Item::Table(groups) = tasks {
+        let Some(group) = group else {
+            bail!("Static tasks are declared in groups, give a group to add the task to");
+        };
+        tasks = groups
+            .entry(group)
+            .or_insert(Item::ArrayOfTables(Default::default()));
+    }
+    match tasks {
+        Item::ArrayOfTables(tasks) => tasks.push(task),
+        Item::Value(toml_edit::Value::Array(tasks)) => tasks.push(task.into_inline_table()),
+        _ => bail!("Unable to add to static_tasks in {}", location.display()),
+    }
+    fs::write(location, document.to_string()).map_err(|e| e.into())
+}
+
+///A table only written out as the header of the tables inside it
+fn implicit_table() -> Item {
+    let mut table = toml_edit::Table::new();
+    table.set_implicit(true);
+    Item::Table(table)
 }
 
-pub fn write_config(config: Config) -> Result<()> {
-    let contents = toml::to_string_pretty(&config)?;
+///Call `f` on each static task declared in either the flat or the grouped layout
+fn visit_static_tasks(tasks: &mut Item, f: &mut dyn FnMut(&mut dyn TableLike)) {
+    match tasks {
+        Item::ArrayOfTables(tasks) => tasks.iter_mut().for_each(|task| f(task)),
+        Item::Value(toml_edit::Value::Array(tasks)) => tasks
+            .iter_mut()
+            .filter_map(toml_edit::Value::as_inline_table_mut)
+            .for_each(|task| f(task)),
+        Item::Table(groups) => groups
+            .iter_mut()
+            .for_each(|(_, group)| visit_static_tasks(group, f)),
+        _ => (),
+    }
+}
+
+///Write the configuration, either as the top level configuration or as a named profile,
+///preserving any other profiles already present
+pub fn write_config(config: Config, profile: Option<&str>) -> Result<()> {
+    let existing: Option<Table> = fs::read_to_string(config_file_location())
+        .ok()
+        .and_then(|content| toml::from_str(&content).ok());
+    let mut table = match profile {
+        Some(_) => existing.clone().unwrap_or_default(),
+        None => Table::try_from(&config)?,
+    };
+    let mut profiles = existing
+        .and_then(|mut existing| existing.remove(PROFILES_KEY))
+        .and_then(|profiles| profiles.as_table().cloned())
+        .unwrap_or_default();
+    if let Some(name) = profile {
+        profiles.insert(name.to_owned(), Value::try_from(&config)?);
+    }
+    if !profiles.is_empty() {
+        table.insert(PROFILES_KEY.to_owned(), Value::Table(profiles));
+    }
+    let contents = toml::to_string_pretty(&table)?;
     fs::write(config_file_location(), contents).map_err(|e| e.into())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn attribute(key: &str, value: &str) -> WorkAttribute {
+        WorkAttribute {
+            key: key.to_owned(),
+            name: key.trim_matches('_').to_owned(),
+            work_attribute_id: 1,
+            value: value.to_owned(),
+        }
+    }
+
+    #[test]
+    fn written_configuration_loads_back() {
+        let path = std::env::temp_dir().join(format!("jt-config-{}.toml", std::process::id()));
+        use_config_file(path.clone());
+        let config = Config {
+            reviewer: Some(String::from("manager")),
+            static_tasks: vec![StaticTask {
+                key: String::from("PROJ-9"),
+                description: String::from("Standup"),
+                attributes: vec![attribute("_Account_", "MEETINGS")],
+                comment: None,
+                percent: Some(5.0),
+                default_time_spent_minutes: Some(15),
+                visibility: None,
+                billable: Some(false),
+                disabled: false,
+                group: None,
+            }],
+            static_attributes: vec![attribute("_Account_", "INTERNAL")],
+            dynamic_attributes: vec![DynamicAttribute {
+                attribute: attribute("_Component_", "/components/0/name"),
+                fallback: Some(String::from("None")),
+            }],
+            prompted_attributes: vec![PromptedAttribute {
+                key: String::from("_Billing_"),
+                name: String::from("Billing"),
+                work_attribute_id: 2,
+                options: vec![String::from("Client"), String::from("Internal")],
+            }],
+            ..Config::new(
+                "https://jira.example.com".parse().unwrap(),
+                String::from("jdoe"),
+                UserIdentifier::Key,
+                AuthKind::Bearer,
+            )
+        };
+        write_config(config, None).unwrap();
+        let loaded = load_config(None);
+        fs::remove_file(&path).unwrap();
+
+        let loaded = loaded.unwrap();
+        assert_eq!(loaded.worker, "jdoe");
+        assert_eq!(loaded.reviewer.as_deref(), Some("manager"));
+        let task = &loaded.static_tasks[0];
+        assert_eq!(
+            (task.key.as_str(), task.description.as_str()),
+            ("PROJ-9", "Standup")
+        );
+        assert_eq!(task.attributes[0].value, "MEETINGS");
+        assert_eq!(
+            (task.percent, task.default_time_spent_minutes),
+            (Some(5.0), Some(15))
+        );
+        assert_eq!(task.billable, Some(false));
+        assert_eq!(loaded.static_attributes[0].value, "INTERNAL");
+        assert_eq!(loaded.dynamic_attributes[0].attribute.key, "_Component_");
+        assert_eq!(
+            loaded.dynamic_attributes[0].fallback.as_deref(),
+            Some("None")
+        );
+        assert_eq!(
+            loaded.prompted_attributes[0].options,
+            ["Client", "Internal"]
+        );
+    }
+
+    #[test]
+    fn finds_attributes_wherever_they_are_configured() {
+        let mut config = Config::new(
+            "https://jira.example.com".parse().unwrap(),
+            String::from("jdoe"),
+            UserIdentifier::Key,
+            AuthKind::Bearer,
+        );
+        config.project_attributes.insert(
+            String::from("PROJ"),
+            vec![WorkAttribute {
+                work_attribute_id: 7,
+                ..attribute("_Account_", "CLIENT")
+            }],
+        );
+        config.prompted_attributes.push(PromptedAttribute {
+            key: String::from("_Billing_"),
+            name: String::from("Billing"),
+            work_attribute_id: 2,
+            options: Vec::new(),
+        });
+        assert_eq!(
+            config.configured_attribute("_Account_"),
+            Some(("Account", 7))
+        );
+        assert_eq!(
+            config.configured_attribute("_Billing_"),
+            Some(("Billing", 2))
+        );
+        assert_eq!(config.configured_attribute("_Component_"), None);
+    }
+}
@@ -0,0 +1,108 @@
+use std::sync::OnceLock;
+
+use anyhow::{Context, Result};
+use chrono::NaiveDate;
+
+use crate::{
+    api_stats::ApiStats,
+    auth::{self, AuthKind},
+    client::Credentials,
+    config::{self, Config},
+    error::JtError,
+    oauth::CodeFlow,
+    report::OutputFormat,
+    state,
+    week::{Timezone, WeekSpec},
+};
+
+///Options shared by every command, parsed once from the global flags
+pub struct RunContext {
+    pub profile: Option<String>,
+    pub verbose: bool,
+    ///Week given with `--week` or `--next`, if either was
+    pub week: Option<WeekSpec>,
+    pub output: OutputFormat,
+    ///Do not change anything in JIRA or Tempo
+    pub dry_run: bool,
+    ///Ring the terminal bell and show a desktop notification when waiting for input
+    pub notify: bool,
+    ///Fetch tasks from JIRA even if they were cached recently
+    pub refresh: bool,
+    ///Leave out dynamic attributes that cannot be resolved rather than failing or prompting
+    pub skip_unresolved: bool,
+    ///Calls made to JIRA and Tempo by every client over the run
+    pub api_stats: ApiStats,
+    ///Timezone the configuration gives once it is loaded
+    pub zone: OnceLock<Timezone>,
+}
+
+impl RunContext {
+    pub fn profile(&self) -> Option<&str> {
+        self.profile.as_deref()
+    }
+
+    ///The week to work on, the current week unless another was given
+    pub fn week_or_current(&self) -> WeekSpec {
+        self.week.unwrap_or(WeekSpec::Offset(0))
+    }
+
+    ///The Monday of the week to work on
+    pub fn monday(&self) -> NaiveDate {
+        self.week_or_current().monday(self.today())
+    }
+
+    ///Timezone to work out dates in, the system's until the configuration says otherwise
+    pub fn timezone(&self) -> Timezone {
+        self.zone.get().copied().unwrap_or_default()
+    }
+
+    ///Today's date in the run's timezone
+    pub fn today(&self) -> NaiveDate {
+        self.timezone().today()
+    }
+
+    ///Load the configuration, storing state and working out dates as it says from then on
+    pub fn config(&self) -> Result<Config> {
+        let config =
+            config::load_config(self.profile()).map_err(|e| JtError::Config(format!("{e:#}")))?;
+        state::use_storage(config.storage)?;
+        let timezone = config.timezone()?;
+        if self.zone.get_or_init(|| timezone) != &timezone {
+            log::warn!("Timezone already chosen for this run, ignoring {timezone}");
+        }
+        Ok(config)
+    }
+
+    ///Credentials for JIRA, signing in through the browser first if OAuth is configured and there
+    ///is no usable refresh token
+    pub async fn credentials(&self, config: &Config) -> Result<Credentials> {
+        match config.auth {
+            AuthKind::Oauth => self
+                .oauth_token(config, false)
+                .await
+                .map(Credentials::Token),
+            kind => {
+                auth::resolve_credentials(self.profile(), kind, config.token_command.as_deref())
+            }
+        }
+    }
+
+    ///An access token from the configured OAuth client, signing in through the browser if asked to
+    ///or if there is no usable refresh token
+    pub async fn oauth_token(&self, config: &Config, sign_in: bool) -> Result<String> {
+        let oauth = config.oauth.as_ref().context(
+            "`auth = \"oauth\"` needs an [oauth] table giving the client to sign in with",
+        )?;
+        let flow = CodeFlow {
+            credential: &auth::oauth_credential(self.profile()),
+            config: oauth,
+            base: &config.base_url()?,
+            http_options: &config.http_options(),
+        };
+        if sign_in {
+            flow.authorize().await
+        } else {
+            flow.access_token().await
+        }
+    }
+}
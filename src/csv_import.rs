@@ -0,0 +1,113 @@
+use std::{fs::File, io::Read, path::Path};
+
+use anyhow::{bail, Context, Result};
+use chrono::NaiveDate;
+
+use crate::{
+    import,
+    plan::{Plan, PlanEntry},
+};
+
+///A worklog read from a row of a CSV file
+pub struct Row {
+    ///Line of the file the row is on, for reporting problems
+    pub line: u64,
+    pub day: NaiveDate,
+    pub task: String,
+    pub minutes: u64,
+    pub comment: Option<String>,
+}
+
+///Rows of date, issue key, minutes and an optional comment, skipping a header row if there is
+///one and failing with every invalid row if there are any
+pub fn read_rows(path: &Path) -> Result<Vec<Row>> {
+    let file = File::open(path).with_context(|| format!("Unable to read {}", path.display()))?;
+    parse_rows(file)
+}
+
+fn parse_rows(reader: impl Read) -> Result<Vec<Row>> {
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .trim(csv::Trim::All)
+        .from_reader(reader);
+    let mut rows = Vec::new();
+    let mut problems = Vec::new();
+    for (index, record) in reader.records().enumerate() {
+        let record = record?;
+        let line = record.position().map_or(index as u64 + 1, |p| p.line());
+        let field = |i: usize| record.get(i).unwrap_or_default();
+        if record.iter().all(str::is_empty) {
+            continue;
+        }
+        let day = field(0).parse::<NaiveDate>();
+        if index == 0 && day.is_err() {
+            continue;
+        }
+        let minutes = field(2).parse::<u64>();
+        match (day, field(1), minutes) {
+            (Err(_), _, _) => problems.push(format!(
+                "line {line}: \"{}\" is not a date (YYYY-MM-DD)",
+                field(0)
+            )),
+            (_, "", _) => problems.push(format!("line {line}: no issue key")),
+            (_, _, Err(_)) | (_, _, Ok(0)) => problems.push(format!(
+                "line {line}: \"{}\" is not a number of minutes",
+                field(2)
+            )),
+            (Ok(day), task, Ok(minutes)) => rows.push(Row {
+                line,
+                day,
+                task: task.to_owned(),
+                minutes,
+                comment: Some(field(3)).filter(|c| !c.is_empty()).map(String::from),
+            }),
+        }
+    }
+    if !problems.is_empty() {
+        bail!("Invalid rows:\n  {}", problems.join("\n  "));
+    }
+    Ok(rows)
+}
+
+///A plan logging each row on its date
+pub fn rows_plan(rows: &[Row]) -> Plan {
+    import::plan_from(rows.iter().map(|row| {
+        (
+            row.day,
+            PlanEntry {
+                task: row.task.clone(),
+                minutes: Some(row.minutes),
+                duration: None,
+                percent: None,
+                comment: row.comment.clone(),
+                tags: Vec::new(),
+            },
+        )
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn skips_header_and_reports_bad_rows_by_line() {
+        let rows = parse_rows(
+            "date,issue,minutes,comment\n2024-05-06,PROJ-1,90,Pairing\n2024-05-07,PROJ-2,30\n"
+                .as_bytes(),
+        )
+        .unwrap();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].comment.as_deref(), Some("Pairing"));
+        assert_eq!((rows[1].line, rows[1].minutes), (3, 30));
+
+        let Err(error) = parse_rows("2024-05-06,PROJ-1,90\n06/05/2024,PROJ-1,x\n".as_bytes())
+        else {
+            panic!("invalid date accepted");
+        };
+        assert!(error
+            .to_string()
+            .contains("line 2: \"06/05/2024\" is not a date"));
+    }
+}
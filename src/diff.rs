@@ -0,0 +1,68 @@
+use console::style;
+
+enum Change<'a> {
+    Same(&'a str),
+    Removed(&'a str),
+    Added(&'a str),
+}
+
+///Lines removed from and added to a text, by the longest common subsequence of lines
+fn line_changes<'a>(old: &'a str, new: &'a str) -> Vec<Change<'a>> {
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+    let mut common = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            common[i][j] = if old[i] == new[j] {
+                common[i + 1][j + 1] + 1
+            } else {
+                common[i + 1][j].max(common[i][j + 1])
+            };
+        }
+    }
+    let (mut i, mut j) = (0, 0);
+    let mut changes = Vec::new();
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            changes.push(Change::Same(old[i]));
+            i += 1;
+            j += 1;
+        } else if i < old.len() && (j == new.len() || common[i + 1][j] >= common[i][j + 1]) {
+            changes.push(Change::Removed(old[i]));
+            i += 1;
+        } else {
+            changes.push(Change::Added(new[j]));
+            j += 1;
+        }
+    }
+    changes
+}
+
+///Print the lines that differ between two versions of a text, with the line before each run of
+///changes for context
+pub fn print_diff(old: &str, new: &str) {
+    let changes = line_changes(old, new);
+    let mut previous: Option<&str> = None;
+    let mut in_change = false;
+    for change in &changes {
+        match change {
+            Change::Same(line) => {
+                previous = Some(line);
+                in_change = false;
+            }
+            Change::Removed(line) | Change::Added(line) => {
+                if !in_change {
+                    println!("{}", style("…").dim());
+                    if let Some(context) = previous {
+                        println!("{}", style(format!("  {context}")).dim());
+                    }
+                    in_change = true;
+                }
+                match change {
+                    Change::Removed(_) => println!("{}", style(format!("- {line}")).red()),
+                    _ => println!("{}", style(format!("+ {line}")).green()),
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,124 @@
+use std::{collections::BTreeMap, fs::File, io::Write, path::Path};
+
+use anyhow::{Context, Result};
+use chrono::NaiveDate;
+use clap::ValueEnum;
+use serde::Serialize;
+
+use crate::client::Worklog;
+
+#[derive(ValueEnum, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Csv,
+    Json,
+}
+
+impl ExportFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Json => "json",
+        }
+    }
+}
+
+///A worklog as written out, with attributes by key
+#[derive(Serialize)]
+struct ExportedWorklog<'a> {
+    tempo_worklog_id: u64,
+    jira_worklog_id: Option<u64>,
+    date: NaiveDate,
+    start_time: Option<String>,
+    issue: &'a str,
+    minutes: u64,
+    billable_minutes: Option<u64>,
+    comment: Option<&'a str>,
+    attributes: BTreeMap<&'a str, &'a str>,
+}
+
+///The same, with attributes as `key=value` pairs separated by semicolons as CSV has no maps
+#[derive(Serialize)]
+struct CsvWorklog<'a> {
+    tempo_worklog_id: u64,
+    jira_worklog_id: Option<u64>,
+    date: NaiveDate,
+    start_time: Option<String>,
+    issue: &'a str,
+    minutes: u64,
+    billable_minutes: Option<u64>,
+    comment: Option<&'a str>,
+    attributes: String,
+}
+
+impl<'a> ExportedWorklog<'a> {
+    fn new(worklog: &'a Worklog) -> Result<Self> {
+        Ok(ExportedWorklog {
+            tempo_worklog_id: worklog.tempo_worklog_id,
+            jira_worklog_id: worklog.jira_worklog_id,
+            date: worklog.day()?,
+            start_time: worklog
+                .start_time()
+                .filter(|time| *time != chrono::NaiveTime::MIN)
+                .map(|time| time.format("%H:%M").to_string()),
+            issue: &worklog.issue.key,
+            minutes: worklog.time_spent_seconds / 60,
+            billable_minutes: worklog.billable_seconds.map(|seconds| seconds / 60),
+            comment: worklog.comment.as_deref(),
+            attributes: worklog
+                .attributes
+                .iter()
+                .map(|(key, attribute)| (key.as_str(), attribute.value.as_str()))
+                .collect(),
+        })
+    }
+
+    fn into_csv(self) -> CsvWorklog<'a> {
+        CsvWorklog {
+            tempo_worklog_id: self.tempo_worklog_id,
+            jira_worklog_id: self.jira_worklog_id,
+            date: self.date,
+            start_time: self.start_time,
+            issue: self.issue,
+            minutes: self.minutes,
+            billable_minutes: self.billable_minutes,
+            comment: self.comment,
+            attributes: self
+                .attributes
+                .iter()
+                .map(|(key, value)| format!("{key}={value}"))
+                .collect::<Vec<_>>()
+                .join("; "),
+        }
+    }
+}
+
+///Write worklogs in the given format to a file, or to standard output if the path is `-`
+pub fn write_worklogs(worklogs: &[Worklog], format: ExportFormat, path: &Path) -> Result<()> {
+    let mut exported = worklogs
+        .iter()
+        .map(ExportedWorklog::new)
+        .collect::<Result<Vec<_>>>()?;
+    exported.sort_by(|a, b| (a.date, &a.start_time).cmp(&(b.date, &b.start_time)));
+    let out: Box<dyn Write> = if path == Path::new("-") {
+        Box::new(std::io::stdout())
+    } else {
+        Box::new(
+            File::create(path).with_context(|| format!("Unable to create {}", path.display()))?,
+        )
+    };
+    match format {
+        ExportFormat::Json => {
+            let mut out = out;
+            serde_json::to_writer_pretty(&mut out, &exported)?;
+            writeln!(out)?;
+        }
+        ExportFormat::Csv => {
+            let mut writer = csv::Writer::from_writer(out);
+            for worklog in exported {
+                writer.serialize(worklog.into_csv())?;
+            }
+            writer.flush()?;
+        }
+    }
+    Ok(())
+}
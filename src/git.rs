@@ -0,0 +1,136 @@
+use std::{
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use anyhow::{bail, Result};
+use chrono::{NaiveDate, TimeDelta};
+use serde::{Deserialize, Serialize};
+
+///Local git repositories whose commits suggest what was worked on
+#[derive(Serialize, Deserialize)]
+pub struct GitConfig {
+    pub repos: Vec<PathBuf>,
+    ///Author whose commits count, defaulting to each repository's configured `user.email`
+    pub author: Option<String>,
+}
+
+///Issue keys mentioned in the author's commits between two days inclusive, earliest first
+pub fn committed_issues(config: &GitConfig, from: NaiveDate, to: NaiveDate) -> Vec<String> {
+    let mut commits: Vec<(NaiveDate, String)> = Vec::new();
+    for repo in &config.repos {
+        match repo_commits(repo, config.author.as_deref(), from, to) {
+            Ok(found) => commits.extend(found),
+            Err(e) => log::warn!("Unable to read commits from {}: {e}", repo.display()),
+        }
+    }
+    commits.sort_by_key(|(day, _)| *day);
+    let mut keys: Vec<String> = Vec::new();
+    for key in commits.iter().flat_map(|(_, message)| issue_keys(message)) {
+        if !keys.contains(&key) {
+            keys.push(key);
+        }
+    }
+    keys
+}
+
+///The branch checked out in the current directory and the issue key its name contains, eg
+///`PROJ-123` for `feature/PROJ-123-foo`, or None outside a repository or on any other branch
+pub fn branch_issue() -> Option<(String, String)> {
+    let branch = git(
+        Path::new("."),
+        &["symbolic-ref", "--quiet", "--short", "HEAD"],
+    )
+    .ok()?;
+    let branch = branch.trim().to_owned();
+    let key = issue_keys(&branch).into_iter().next()?;
+    Some((branch, key))
+}
+
+fn git(repo: &Path, args: &[&str]) -> Result<String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(repo)
+        .args(args)
+        .output()?;
+    if !output.status.success() {
+        bail!("{}", String::from_utf8_lossy(&output.stderr).trim());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+///The day and message of each of the author's commits on any branch between two days inclusive
+fn repo_commits(
+    repo: &Path,
+    author: Option<&str>,
+    from: NaiveDate,
+    to: NaiveDate,
+) -> Result<Vec<(NaiveDate, String)>> {
+    let author = match author {
+        Some(author) => author.to_owned(),
+        None => git(repo, &["config", "user.email"])?.trim().to_owned(),
+    };
+    let log = git(
+        repo,
+        &[
+            "log",
+            "--all",
+            "--no-merges",
+            "--reverse",
+            &format!("--author={author}"),
+            &format!("--since={from}T00:00:00"),
+            &format!("--until={}T00:00:00", to + TimeDelta::days(1)),
+            "--date=short",
+            "--format=%ad%x1f%B%x1e",
+        ],
+    )?;
+    Ok(log
+        .split('\x1e')
+        .filter_map(|commit| {
+            let (day, message) = commit.trim().split_once('\x1f')?;
+            Some((day.parse().ok()?, message.to_owned()))
+        })
+        .collect())
+}
+
+///Everything in some text that looks like an issue key, eg `PROJ-123`, in order of appearance
+pub fn issue_keys(text: &str) -> Vec<String> {
+    let mut keys: Vec<String> = Vec::new();
+    for word in text.split(|c: char| !(c.is_ascii_alphanumeric() || c == '-' || c == '_')) {
+        let mut parts = word.split('-');
+        let mut project = parts.next().unwrap_or_default();
+        for number in parts {
+            let is_project = project.starts_with(|c: char| c.is_ascii_uppercase())
+                && project
+                    .chars()
+                    .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_');
+            if is_project && !number.is_empty() && number.chars().all(|c| c.is_ascii_digit()) {
+                let key = format!("{project}-{number}");
+                if !keys.contains(&key) {
+                    keys.push(key);
+                }
+            }
+            project = number;
+        }
+    }
+    keys
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_keys_in_messages_and_branch_names() {
+        assert_eq!(
+            issue_keys("PROJ-12: fix parser (see also OPS-3, PROJ-12)"),
+            vec!["PROJ-12", "OPS-3"]
+        );
+        assert_eq!(issue_keys("feature/PROJ-123-foo-bar"), vec!["PROJ-123"]);
+    }
+
+    #[test]
+    fn ignores_lowercase_and_partial_keys() {
+        assert!(issue_keys("proj-12 PROJ- -12 PROJ-12a").is_empty());
+    }
+}
@@ -0,0 +1,155 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, FixedOffset, NaiveDate, TimeDelta};
+use reqwest::{Client, Url};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    import::{self, Event},
+    oauth::DeviceFlow,
+    plan::Plan,
+    week::Timezone,
+};
+
+const CALENDAR_API: &str = "https://www.googleapis.com/calendar/v3/calendars";
+
+///An OAuth client registered in Google Cloud for "TVs and Limited Input devices", and the
+///calendar and task to log meetings from and against
+#[derive(Serialize, Deserialize)]
+pub struct GoogleCalendarConfig {
+    pub client_id: String,
+    pub client_secret: String,
+    #[serde(default = "primary_calendar")]
+    pub calendar: String,
+    ///Task meetings are logged against
+    pub task: String,
+}
+
+fn primary_calendar() -> String {
+    String::from("primary")
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct EventList {
+    #[serde(default)]
+    items: Vec<CalendarEvent>,
+    next_page_token: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CalendarEvent {
+    #[serde(default)]
+    summary: String,
+    status: Option<String>,
+    transparency: Option<String>,
+    event_type: Option<String>,
+    start: EventTime,
+    end: EventTime,
+    #[serde(default)]
+    attendees: Vec<Attendee>,
+}
+
+///Start or end of an event, which for all-day events is a date rather than a time
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct EventTime {
+    date_time: Option<DateTime<FixedOffset>>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Attendee {
+    #[serde(default, rename = "self")]
+    is_self: bool,
+    response_status: Option<String>,
+}
+
+impl CalendarEvent {
+    ///A timed event the user is busy for and has not declined, other than out of office and
+    ///focus time
+    fn into_meeting(self, timezone: Timezone) -> Option<Event> {
+        let declined = self.attendees.iter().any(|attendee| {
+            attendee.is_self && attendee.response_status.as_deref() == Some("declined")
+        });
+        let meeting = self.status.as_deref() != Some("cancelled")
+            && self.transparency.as_deref() != Some("transparent")
+            && matches!(self.event_type.as_deref(), None | Some("default"))
+            && !declined;
+        meeting.then_some(Event {
+            title: self.summary,
+            start: timezone.local_time(self.start.date_time?.to_utc()),
+            end: timezone.local_time(self.end.date_time?.to_utc()),
+        })
+    }
+}
+
+///A plan logging the meetings in the calendar between two days inclusive against the configured
+///task
+pub async fn import(
+    config: &GoogleCalendarConfig,
+    from: NaiveDate,
+    to: NaiveDate,
+    timezone: Timezone,
+) -> Result<Plan> {
+    let flow = DeviceFlow {
+        credential: "google-calendar",
+        device_code_url: "https://oauth2.googleapis.com/device/code",
+        token_url: "https://oauth2.googleapis.com/token",
+        client_id: &config.client_id,
+        client_secret: Some(&config.client_secret),
+        scope: "https://www.googleapis.com/auth/calendar.readonly",
+    };
+    let token = flow.access_token().await?;
+    let events = calendar_events(&token, &config.calendar, from, to, timezone).await?;
+    Ok(import::events_plan(events, from, to, |_| {
+        Some(config.task.clone())
+    }))
+}
+
+///Events from a day either side of the range, as their days depend on the calendar's timezone
+async fn calendar_events(
+    token: &str,
+    calendar: &str,
+    from: NaiveDate,
+    to: NaiveDate,
+    timezone: Timezone,
+) -> Result<Vec<Event>> {
+    let mut url = Url::parse(CALENDAR_API)?;
+    url.path_segments_mut()
+        .expect("API URL has a path")
+        .extend([calendar, "events"]);
+    let time_min = format!("{}T00:00:00Z", from - TimeDelta::days(1));
+    let time_max = format!("{}T00:00:00Z", to + TimeDelta::days(2));
+    let client = Client::new();
+    let mut events = Vec::new();
+    let mut page_token: Option<String> = None;
+    loop {
+        let mut query = vec![
+            ("timeMin", time_min.as_str()),
+            ("timeMax", time_max.as_str()),
+            ("singleEvents", "true"),
+            ("maxResults", "250"),
+        ];
+        query.extend(page_token.as_deref().map(|page| ("pageToken", page)));
+        let list: EventList = client
+            .get(url.clone())
+            .bearer_auth(token)
+            .query(&query)
+            .send()
+            .await?
+            .error_for_status()
+            .with_context(|| format!("Unable to read Google Calendar \"{calendar}\""))?
+            .json()
+            .await?;
+        events.extend(
+            list.items
+                .into_iter()
+                .filter_map(|event| event.into_meeting(timezone)),
+        );
+        page_token = list.next_page_token;
+        if page_token.is_none() {
+            return Ok(events);
+        }
+    }
+}
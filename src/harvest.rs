@@ -0,0 +1,103 @@
+use anyhow::{Context, Result};
+use chrono::{NaiveDate, TimeDelta};
+use reqwest::{Client, RequestBuilder};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use crate::{
+    import::{self, TrackedTime, TrackerMapping},
+    plan::Plan,
+};
+
+const HARVEST_API: &str = "https://api.harvestapp.com/v2";
+
+///The Harvest account to read time from and how its projects and tasks map to Jira tasks, Harvest
+///task names being looked up in `tags`
+#[derive(Serialize, Deserialize)]
+pub struct HarvestConfig {
+    pub account_id: String,
+    #[serde(flatten)]
+    pub mapping: TrackerMapping,
+}
+
+#[derive(Deserialize)]
+struct User {
+    id: u64,
+}
+
+#[derive(Deserialize)]
+struct TimeEntryPage {
+    time_entries: Vec<TimeEntry>,
+    next_page: Option<u32>,
+}
+
+#[derive(Deserialize)]
+struct TimeEntry {
+    spent_date: NaiveDate,
+    hours: f64,
+    notes: Option<String>,
+    is_running: bool,
+    project: Named,
+    task: Named,
+}
+
+#[derive(Deserialize)]
+struct Named {
+    name: String,
+}
+
+///A plan logging the user's time in Harvest between two days inclusive against the tasks it maps
+///to
+pub async fn import(config: &HarvestConfig, from: NaiveDate, to: NaiveDate) -> Result<Plan> {
+    let token = import::api_token("harvest-api-token", "Harvest personal access token")?;
+    let client = Client::new();
+    let request = |path: &str| {
+        client
+            .get(format!("{HARVEST_API}/{path}"))
+            .bearer_auth(&token)
+            .header("Harvest-Account-Id", &config.account_id)
+            .header("User-Agent", concat!("jt/", env!("CARGO_PKG_VERSION")))
+    };
+    let user: User = send(request("users/me")).await?;
+    let (user_id, from_date, to_date) = (user.id.to_string(), from.to_string(), to.to_string());
+    let mut tracked = Vec::new();
+    let mut page = 1;
+    loop {
+        let page_number = page.to_string();
+        let entries: TimeEntryPage = send(request("time_entries").query(&[
+            ("user_id", user_id.as_str()),
+            ("from", from_date.as_str()),
+            ("to", to_date.as_str()),
+            ("page", page_number.as_str()),
+        ]))
+        .await?;
+        tracked.extend(
+            entries
+                .time_entries
+                .into_iter()
+                .filter(|entry| !entry.is_running)
+                .map(|entry| TrackedTime {
+                    day: entry.spent_date,
+                    time_spent: TimeDelta::seconds((entry.hours * 3600.0).round() as i64),
+                    description: entry.notes.unwrap_or_default(),
+                    project: Some(entry.project.name),
+                    tags: vec![entry.task.name],
+                }),
+        );
+        match entries.next_page {
+            Some(next) => page = next,
+            None => break,
+        }
+    }
+    Ok(import::tracked_plan(tracked, &config.mapping, from, to))
+}
+
+async fn send<T: DeserializeOwned>(request: RequestBuilder) -> Result<T> {
+    request
+        .send()
+        .await?
+        .error_for_status()
+        .context("Unable to read from Harvest, check the account ID and access token")?
+        .json()
+        .await
+        .map_err(|e| e.into())
+}
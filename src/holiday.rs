@@ -0,0 +1,147 @@
+use std::{collections::BTreeSet, fs::File, io::BufReader, path::Path};
+
+use anyhow::{Context, Result};
+use chrono::{Datelike, NaiveDate};
+use ical::IcalParser;
+use serde::{Deserialize, Serialize};
+
+use crate::{config::Config, state};
+
+const PUBLIC_HOLIDAY_API: &str = "https://date.nager.at/api/v3/PublicHolidays";
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Holiday {
+    pub day: NaiveDate,
+    pub name: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PublicHoliday {
+    date: NaiveDate,
+    local_name: String,
+    ///False for holidays only observed in some regions of the country
+    global: bool,
+}
+
+///Holidays between `from` and `to` inclusive from the configured country and calendar file
+pub async fn load_holidays(
+    config: &Config,
+    from: NaiveDate,
+    to: NaiveDate,
+) -> Result<Vec<Holiday>> {
+    let mut holidays = Vec::new();
+    if let Some(country) = &config.holiday_country {
+        let years: BTreeSet<i32> = [from.year(), to.year()].into();
+        for year in years {
+            holidays.extend(public_holidays(country, year).await?);
+        }
+    }
+    if let Some(path) = &config.holiday_calendar {
+        holidays.extend(calendar_holidays(path)?);
+    }
+    holidays.retain(|holiday| holiday.day >= from && holiday.day <= to);
+    Ok(holidays)
+}
+
+async fn public_holidays(country: &str, year: i32) -> Result<Vec<Holiday>> {
+    let cache_name = format!("holidays-{}-{year}.json", country.to_uppercase());
+    if let Some(cached) = state::load_cache(&cache_name) {
+        return Ok(cached);
+    }
+    let holidays: Vec<Holiday> = reqwest::get(format!("{PUBLIC_HOLIDAY_API}/{year}/{country}"))
+        .await?
+        .error_for_status()
+        .with_context(|| format!("Unable to retrieve public holidays for \"{country}\""))?
+        .json::<Vec<PublicHoliday>>()
+        .await?
+        .into_iter()
+        .filter(|holiday| holiday.global)
+        .map(|holiday| Holiday {
+            day: holiday.date,
+            name: holiday.local_name,
+        })
+        .collect();
+    if let Err(e) = state::write_cache(&cache_name, &holidays) {
+        log::warn!("Unable to cache public holidays: {e}");
+    }
+    Ok(holidays)
+}
+
+///Read all-day events from an ICS file, each day of a multi-day event being a holiday and timed
+///events being left out
+fn calendar_holidays(path: &Path) -> Result<Vec<Holiday>> {
+    let file = File::open(path)
+        .with_context(|| format!("Unable to read holiday calendar {}", path.display()))?;
+    let mut holidays = Vec::new();
+    for calendar in IcalParser::new(BufReader::new(file)) {
+        let calendar =
+            calendar.with_context(|| format!("Invalid holiday calendar {}", path.display()))?;
+        for event in calendar.events {
+            let property = |name: &str| {
+                event
+                    .properties
+                    .iter()
+                    .find(|p| p.name == name)
+                    .and_then(|p| p.value.clone())
+            };
+            let Some(start) = property("DTSTART").as_deref().and_then(ics_date) else {
+                continue;
+            };
+            let end = property("DTEND")
+                .as_deref()
+                .and_then(ics_date)
+                .filter(|end| *end > start)
+                .unwrap_or(start.succ_opt().unwrap());
+            let name = property("SUMMARY").unwrap_or_else(|| String::from("Holiday"));
+            holidays.extend(
+                start
+                    .iter_days()
+                    .take_while(|day| *day < end)
+                    .map(|day| Holiday {
+                        day,
+                        name: name.clone(),
+                    }),
+            );
+        }
+    }
+    Ok(holidays)
+}
+
+///An ICS date value such as `20241225`, or None for a date-time such as `20241225T090000Z` so that
+///timed events are not taken for holidays
+fn ics_date(value: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(value, "%Y%m%d").ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_all_day_events_are_holidays() {
+        let path = std::env::temp_dir().join(format!("jt-holidays-{}.ics", std::process::id()));
+        std::fs::write(
+            &path,
+            "BEGIN:VCALENDAR\r\n\
+             BEGIN:VEVENT\r\nSUMMARY:Christmas\r\nDTSTART;VALUE=DATE:20241225\r\nDTEND;VALUE=DATE:20241227\r\nEND:VEVENT\r\n\
+             BEGIN:VEVENT\r\nSUMMARY:Party\r\nDTSTART:20241220T090000Z\r\nDTEND:20241220T100000Z\r\nEND:VEVENT\r\n\
+             END:VCALENDAR\r\n",
+        )
+        .unwrap();
+        let holidays = calendar_holidays(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let days: Vec<(String, String)> = holidays
+            .into_iter()
+            .map(|holiday| (holiday.day.to_string(), holiday.name))
+            .collect();
+        assert_eq!(
+            days,
+            [
+                (String::from("2024-12-25"), String::from("Christmas")),
+                (String::from("2024-12-26"), String::from("Christmas")),
+            ]
+        );
+    }
+}
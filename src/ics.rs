@@ -0,0 +1,113 @@
+use std::{fs::File, io::BufReader, path::Path};
+
+use anyhow::{Context, Result};
+use chrono::{NaiveDate, NaiveDateTime, TimeDelta};
+use ical::{parser::ical::component::IcalEvent, IcalParser};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    import::{self, Event, TitleRule, TitleRules},
+    plan::Plan,
+    week::Timezone,
+};
+
+///How events in an exported calendar map to tasks
+#[derive(Serialize, Deserialize)]
+pub struct IcsConfig {
+    #[serde(default)]
+    pub rules: Vec<TitleRule>,
+    ///Task for events matching none of the rules, which are otherwise left out
+    pub default_task: Option<String>,
+}
+
+///A plan logging the timed events in an ICS file between two days inclusive against the tasks
+///their titles map to
+pub fn import(
+    config: &IcsConfig,
+    path: &Path,
+    from: NaiveDate,
+    to: NaiveDate,
+    timezone: Timezone,
+) -> Result<Plan> {
+    let rules = TitleRules::new(&config.rules, config.default_task.as_deref())?;
+    let events = calendar_events(path, timezone)?;
+    Ok(import::events_plan(events, from, to, |event| {
+        rules.task_for(&event.title)
+    }))
+}
+
+fn calendar_events(path: &Path, timezone: Timezone) -> Result<Vec<Event>> {
+    let file =
+        File::open(path).with_context(|| format!("Unable to read calendar {}", path.display()))?;
+    let mut events = Vec::new();
+    for calendar in IcalParser::new(BufReader::new(file)) {
+        let calendar = calendar.with_context(|| format!("Invalid calendar {}", path.display()))?;
+        events.extend(
+            calendar
+                .events
+                .iter()
+                .filter_map(|event| timed_event(event, timezone)),
+        );
+    }
+    Ok(events)
+}
+
+///An event with a start and end time that the user is busy for, leaving out all-day events
+fn timed_event(event: &IcalEvent, timezone: Timezone) -> Option<Event> {
+    let property = |name: &str| {
+        event
+            .properties
+            .iter()
+            .find(|p| p.name == name)
+            .and_then(|p| p.value.as_deref())
+    };
+    if property("STATUS") == Some("CANCELLED") || property("TRANSP") == Some("TRANSPARENT") {
+        return None;
+    }
+    let start = ics_time(property("DTSTART")?, timezone)?;
+    let end = match property("DTEND") {
+        Some(end) => ics_time(end, timezone)?,
+        None => start + ics_duration(property("DURATION")?)?,
+    };
+    Some(Event {
+        title: property("SUMMARY").unwrap_or_default().to_owned(),
+        start,
+        end,
+    })
+}
+
+///A date-time such as `20240506T090000Z` in UTC, read on the clock in `timezone`, or
+///`20240506T090000` already on the local clock, or None for a date alone
+fn ics_time(value: &str, timezone: Timezone) -> Option<NaiveDateTime> {
+    match value.strip_suffix('Z') {
+        Some(utc) => NaiveDateTime::parse_from_str(utc, "%Y%m%dT%H%M%S")
+            .ok()
+            .map(|time| timezone.local_time(time.and_utc())),
+        None => NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S").ok(),
+    }
+}
+
+///A duration such as `PT1H30M` or `P1DT2H`
+fn ics_duration(value: &str) -> Option<TimeDelta> {
+    let value = value.strip_prefix('P')?;
+    let mut duration = TimeDelta::zero();
+    let mut number = String::new();
+    for c in value.chars() {
+        match c {
+            'T' => {}
+            '0'..='9' => number.push(c),
+            unit => {
+                let n: i64 = std::mem::take(&mut number).parse().ok()?;
+                duration += match unit {
+                    'W' => TimeDelta::weeks(n),
+                    'D' => TimeDelta::days(n),
+                    'H' => TimeDelta::hours(n),
+                    'M' => TimeDelta::minutes(n),
+                    'S' => TimeDelta::seconds(n),
+                    _ => return None,
+                };
+            }
+        }
+    }
+    Some(duration)
+}
@@ -0,0 +1,348 @@
+use std::collections::{BTreeMap, HashMap};
+
+use anyhow::{Context, Result};
+use chrono::{NaiveDate, NaiveDateTime, TimeDelta};
+use console::style;
+use dialoguer::Password;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    auth,
+    clockify::ClockifyConfig,
+    duration::format_duration,
+    git,
+    google::GoogleCalendarConfig,
+    harvest::HarvestConfig,
+    ics::IcsConfig,
+    outlook::OutlookConfig,
+    plan::{DaySpec, Plan, PlanDay, PlanEntry},
+    toggl::TogglConfig,
+};
+
+///Where `import` reads time tracked elsewhere from, each source configured in its own table
+#[derive(Serialize, Deserialize, Default)]
+pub struct ImportConfig {
+    pub google_calendar: Option<GoogleCalendarConfig>,
+    pub outlook: Option<OutlookConfig>,
+    pub ics: Option<IcsConfig>,
+    pub toggl: Option<TogglConfig>,
+    pub harvest: Option<HarvestConfig>,
+    pub clockify: Option<ClockifyConfig>,
+}
+
+///A source's configuration, which is needed to know what to import and where to log it
+pub fn configured<'a, T>(source: &'a Option<T>, name: &str) -> Result<&'a T> {
+    source.as_ref().with_context(|| {
+        format!("Add an [import.{name}] table to the configuration to import from it")
+    })
+}
+
+///A timed calendar event, with its start and end as on the clock in the configured timezone
+pub struct Event {
+    pub title: String,
+    pub start: NaiveDateTime,
+    pub end: NaiveDateTime,
+}
+
+///Chooses the task for events whose titles match a regular expression, eg `pattern = "(?i)standup"`
+#[derive(Serialize, Deserialize)]
+pub struct TitleRule {
+    pub pattern: String,
+    ///Task to log matching events against, in which `$1` and so on stand for groups captured by
+    ///the pattern, eg `pattern = "^([A-Z]+-\\d+)"` with `task = "$1"`
+    pub task: String,
+}
+
+///Compiled title rules, tried in order with the default task used for events matching none
+pub struct TitleRules<'a> {
+    rules: Vec<(Regex, &'a str)>,
+    default_task: Option<&'a str>,
+}
+
+impl<'a> TitleRules<'a> {
+    pub fn new(rules: &'a [TitleRule], default_task: Option<&'a str>) -> Result<Self> {
+        let rules = rules
+            .iter()
+            .map(|rule| {
+                Regex::new(&rule.pattern)
+                    .with_context(|| format!("Invalid title pattern \"{}\"", rule.pattern))
+                    .map(|regex| (regex, rule.task.as_str()))
+            })
+            .collect::<Result<_>>()?;
+        Ok(TitleRules {
+            rules,
+            default_task,
+        })
+    }
+
+    ///The task for an event with the given title, or None if it should be left out
+    pub fn task_for(&self, title: &str) -> Option<String> {
+        for (regex, task) in &self.rules {
+            if let Some(captures) = regex.captures(title) {
+                let mut expanded = String::new();
+                captures.expand(task, &mut expanded);
+                return Some(expanded);
+            }
+        }
+        self.default_task.map(str::to_owned)
+    }
+}
+
+///A plan logging each day's time in events between two days inclusive against the task each
+///event is for, leaving out events for no task and counting overlapping time on a task once
+pub fn events_plan(
+    events: Vec<Event>,
+    from: NaiveDate,
+    to: NaiveDate,
+    task_for: impl Fn(&Event) -> Option<String>,
+) -> Plan {
+    let mut by_task: BTreeMap<String, Vec<Event>> = BTreeMap::new();
+    for event in events {
+        let day = event.start.date();
+        if event.end <= event.start || day < from || day > to {
+            continue;
+        }
+        match task_for(&event) {
+            Some(task) => by_task.entry(task).or_default().push(event),
+            None => log::info!("No task for \"{}\" on {day}, leaving it out", event.title),
+        }
+    }
+    let mut entries = Vec::new();
+    for (task, mut events) in by_task {
+        events.sort_by_key(|event| event.start);
+        let mut days: BTreeMap<NaiveDate, (i64, Vec<String>)> = BTreeMap::new();
+        let mut covered_until: Option<NaiveDateTime> = None;
+        for event in events {
+            let start = covered_until.map_or(event.start, |until| until.max(event.start));
+            let minutes = (event.end - start).num_minutes().max(0);
+            covered_until = Some(covered_until.map_or(event.end, |until| until.max(event.end)));
+            let (total, titles) = days.entry(event.start.date()).or_default();
+            *total += minutes;
+            if !titles.contains(&event.title) {
+                titles.push(event.title);
+            }
+        }
+        entries.extend(
+            days.into_iter()
+                .filter(|(_, (minutes, _))| *minutes > 0)
+                .map(|(day, (minutes, titles))| {
+                    (
+                        day,
+                        PlanEntry {
+                            task: task.clone(),
+                            minutes: Some(minutes as u64),
+                            duration: None,
+                            percent: None,
+                            comment: Some(titles.join(", ")),
+                            tags: Vec::new(),
+                        },
+                    )
+                }),
+        );
+    }
+    plan_from(entries)
+}
+
+///An entry from a time tracker
+pub struct TrackedTime {
+    pub day: NaiveDate,
+    pub time_spent: TimeDelta,
+    pub description: String,
+    pub project: Option<String>,
+    pub tags: Vec<String>,
+}
+
+///Which task time tracked against each of a tracker's projects and tags is for, eg
+///`projects = { Website = "WEB-1" }`
+#[derive(Serialize, Deserialize, Default)]
+pub struct TrackerMapping {
+    #[serde(default)]
+    pub projects: HashMap<String, String>,
+    ///Tasks by tag, taking precedence over the entry's project
+    #[serde(default)]
+    pub tags: HashMap<String, String>,
+    ///Task for entries whose tags, project and description say nothing, which are otherwise left
+    ///out
+    pub default_task: Option<String>,
+}
+
+impl TrackerMapping {
+    ///The task for tracked time from its tags, then its project, then an issue key in its
+    ///description, then the default task
+    fn task_for(&self, tracked: &TrackedTime) -> Option<String> {
+        tracked
+            .tags
+            .iter()
+            .find_map(|tag| self.tags.get(tag))
+            .or_else(|| {
+                tracked
+                    .project
+                    .as_ref()
+                    .and_then(|project| self.projects.get(project))
+            })
+            .cloned()
+            .or_else(|| git::issue_keys(&tracked.description).into_iter().next())
+            .or_else(|| self.default_task.clone())
+    }
+}
+
+///A plan logging the time tracked each day between two days inclusive against the task it maps
+///to, with one entry per task per day commented with the descriptions of the time in it
+pub fn tracked_plan(
+    tracked: Vec<TrackedTime>,
+    mapping: &TrackerMapping,
+    from: NaiveDate,
+    to: NaiveDate,
+) -> Plan {
+    let mut totals: BTreeMap<(NaiveDate, String), (TimeDelta, Vec<String>)> = BTreeMap::new();
+    for tracked in tracked {
+        if tracked.day < from || tracked.day > to || tracked.time_spent <= TimeDelta::zero() {
+            continue;
+        }
+        let Some(task) = mapping.task_for(&tracked) else {
+            println!(
+                "{}",
+                style(format!(
+                    "Leaving out {} of \"{}\" on {}, which maps to no task",
+                    format_duration(tracked.time_spent),
+                    tracked.description,
+                    tracked.day
+                ))
+                .yellow()
+            );
+            continue;
+        };
+        let (total, descriptions) = totals.entry((tracked.day, task)).or_default();
+        *total += tracked.time_spent;
+        if !tracked.description.is_empty() && !descriptions.contains(&tracked.description) {
+            descriptions.push(tracked.description);
+        }
+    }
+    plan_from(
+        totals
+            .into_iter()
+            .map(|((day, task), (time_spent, descriptions))| {
+                let minutes = (time_spent.num_seconds() as f64 / 60.0).round() as u64;
+                (
+                    day,
+                    PlanEntry {
+                        task,
+                        minutes: Some(minutes),
+                        duration: None,
+                        percent: None,
+                        comment: (!descriptions.is_empty()).then(|| descriptions.join("; ")),
+                        tags: Vec::new(),
+                    },
+                )
+            }),
+    )
+}
+
+///An API token for a time tracker from the OS keyring, asking for one and storing it if there
+///is none
+pub fn api_token(credential: &str, prompt: &str) -> Result<String> {
+    if let Some(token) = auth::load_credential(credential) {
+        return Ok(token);
+    }
+    let token = Password::new().with_prompt(prompt).interact().unwrap();
+    auth::store_credential(credential, &token)?;
+    Ok(token)
+}
+
+///A plan of entries on specific days
+pub fn plan_from(entries: impl IntoIterator<Item = (NaiveDate, PlanEntry)>) -> Plan {
+    let mut days: BTreeMap<NaiveDate, Vec<PlanEntry>> = BTreeMap::new();
+    for (day, entry) in entries {
+        days.entry(day).or_default().push(entry);
+    }
+    Plan {
+        days: days
+            .into_iter()
+            .map(|(day, entries)| PlanDay {
+                day: DaySpec::Date(day),
+                entries,
+            })
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(title: &str, start: &str, end: &str) -> Event {
+        Event {
+            title: title.to_owned(),
+            start: start.parse().unwrap(),
+            end: end.parse().unwrap(),
+        }
+    }
+
+    #[test]
+    fn overlapping_events_count_once() {
+        let events = vec![
+            event("Planning", "2024-05-06T10:00:00", "2024-05-06T11:00:00"),
+            event("Standup", "2024-05-06T09:30:00", "2024-05-06T10:15:00"),
+            event("Retro", "2024-05-07T14:00:00", "2024-05-07T15:00:00"),
+        ];
+        let from = NaiveDate::from_ymd_opt(2024, 5, 6).unwrap();
+        let to = from + chrono::TimeDelta::days(6);
+        let plan = events_plan(events, from, to, |_| Some(String::from("MEET-1")));
+        let minutes: Vec<Option<u64>> =
+            plan.days.iter().map(|day| day.entries[0].minutes).collect();
+        assert_eq!(minutes, vec![Some(90), Some(60)]);
+        assert_eq!(
+            plan.days[0].entries[0].comment.as_deref(),
+            Some("Standup, Planning")
+        );
+    }
+
+    #[test]
+    fn title_rules_fill_in_captured_keys() {
+        let rules = [
+            TitleRule {
+                pattern: String::from("(?i)standup"),
+                task: String::from("TEAM-1"),
+            },
+            TitleRule {
+                pattern: String::from(r"^([A-Z]+-\d+)"),
+                task: String::from("$1"),
+            },
+        ];
+        let rules = TitleRules::new(&rules, None).unwrap();
+        assert_eq!(rules.task_for("Daily Standup").as_deref(), Some("TEAM-1"));
+        assert_eq!(rules.task_for("PROJ-42 design").as_deref(), Some("PROJ-42"));
+        assert_eq!(rules.task_for("Lunch"), None);
+    }
+
+    #[test]
+    fn tracked_time_maps_by_tag_then_project_then_key() {
+        let mapping = TrackerMapping {
+            projects: HashMap::from([(String::from("Website"), String::from("WEB-1"))]),
+            tags: HashMap::from([(String::from("support"), String::from("SUP-2"))]),
+            default_task: None,
+        };
+        let tracked = |description: &str, project: Option<&str>, tag: Option<&str>| TrackedTime {
+            day: NaiveDate::from_ymd_opt(2024, 5, 6).unwrap(),
+            time_spent: TimeDelta::minutes(30),
+            description: description.to_owned(),
+            project: project.map(String::from),
+            tags: tag.into_iter().map(String::from).collect(),
+        };
+        let task = |tracked| mapping.task_for(&tracked);
+        assert_eq!(
+            task(tracked("", Some("Website"), Some("support"))).as_deref(),
+            Some("SUP-2")
+        );
+        assert_eq!(
+            task(tracked("PROJ-4", Some("Website"), None)).as_deref(),
+            Some("WEB-1")
+        );
+        assert_eq!(
+            task(tracked("PROJ-4 fix", None, None)).as_deref(),
+            Some("PROJ-4")
+        );
+        assert_eq!(task(tracked("email", None, None)), None);
+    }
+}
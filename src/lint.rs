@@ -0,0 +1,156 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Display,
+};
+
+use chrono::NaiveDate;
+
+use crate::{client::Worklog, config::Config, report::project_of};
+
+const PLACEHOLDER_COMMENTS: [&str; 8] = ["x", ".", "-", "?", "n/a", "na", "todo", "wip"];
+
+pub enum ProblemKind {
+    ZeroDuration,
+    Duplicate { of: u64 },
+    MissingAttribute(String),
+    PlaceholderComment(String),
+    TooManyIssues { max: usize },
+}
+
+pub struct Problem {
+    pub worklog_id: u64,
+    pub day: NaiveDate,
+    pub issue: String,
+    pub kind: ProblemKind,
+}
+
+impl Problem {
+    fn fix(&self) -> String {
+        match &self.kind {
+            ProblemKind::ZeroDuration => String::from("delete the worklog or set a duration"),
+            ProblemKind::Duplicate { .. } => {
+                String::from("merge the entries or delete one of them")
+            }
+            ProblemKind::MissingAttribute(key) => format!("set a value for attribute {key}"),
+            ProblemKind::PlaceholderComment(_) => {
+                String::from("replace the comment with a description of the work")
+            }
+            ProblemKind::TooManyIssues { max } => {
+                format!("merge the day's work into at most {max} issues")
+            }
+        }
+    }
+}
+
+impl Display for Problem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let description = match &self.kind {
+            ProblemKind::ZeroDuration => String::from("zero minutes logged"),
+            ProblemKind::Duplicate { of } => format!("duplicate of worklog {of}"),
+            ProblemKind::MissingAttribute(key) => format!("missing attribute {key}"),
+            ProblemKind::PlaceholderComment(comment) => {
+                format!("placeholder comment \"{comment}\"")
+            }
+            ProblemKind::TooManyIssues { max } => {
+                format!("more than {max} issues logged on the day")
+            }
+        };
+        write!(
+            f,
+            "{} {} (worklog {}): {} - {}",
+            self.day.format("%a %-d %b"),
+            self.issue,
+            self.worklog_id,
+            description,
+            self.fix()
+        )
+    }
+}
+
+///Check a set of existing worklogs for common problems
+pub fn lint(worklogs: &[Worklog], config: &Config) -> Vec<Problem> {
+    let static_attributes: HashMap<&str, Vec<&str>> = config
+        .static_tasks
+        .iter()
+        .map(|task| {
+            let keys = task.attributes.iter().map(|a| a.key.as_str()).collect();
+            (task.key.as_str(), keys)
+        })
+        .collect();
+    let global_attributes: Vec<&str> = config
+        .static_attributes
+        .iter()
+        .chain(config.dynamic_attributes.iter().map(|d| &d.attribute))
+        .map(|a| a.key.as_str())
+        .collect();
+
+    let mut problems = Vec::new();
+    let mut seen = HashMap::new();
+    let mut issues_per_day: HashMap<NaiveDate, HashSet<&str>> = HashMap::new();
+    for worklog in worklogs {
+        let Ok(day) = worklog.day() else {
+            log::warn!(
+                "Unable to parse start of worklog {}",
+                worklog.tempo_worklog_id
+            );
+            continue;
+        };
+        let mut report = |kind| {
+            problems.push(Problem {
+                worklog_id: worklog.tempo_worklog_id,
+                day,
+                issue: worklog.issue.key.clone(),
+                kind,
+            })
+        };
+
+        if worklog.time_spent_seconds == 0 {
+            report(ProblemKind::ZeroDuration);
+        }
+
+        if let Some(of) = seen.insert((day, worklog.issue.key.as_str()), worklog.tempo_worklog_id) {
+            report(ProblemKind::Duplicate { of });
+        }
+
+        if let Some(max) = config.max_issues_per_day {
+            let issues = issues_per_day.entry(day).or_default();
+            if issues.insert(worklog.issue.key.as_str()) && issues.len() > max {
+                report(ProblemKind::TooManyIssues { max });
+            }
+        }
+
+        let mut expected = static_attributes
+            .get(worklog.issue.key.as_str())
+            .unwrap_or(&global_attributes)
+            .clone();
+        if let Some(project) = config
+            .project_attributes
+            .get(project_of(&worklog.issue.key))
+        {
+            for attribute in project {
+                if !expected.contains(&attribute.key.as_str()) {
+                    expected.push(&attribute.key);
+                }
+            }
+        }
+        let present: HashSet<&str> = worklog
+            .attributes
+            .iter()
+            .filter(|(_, attr)| !attr.value.is_empty())
+            .map(|(key, _)| key.as_str())
+            .collect();
+        for key in expected.iter().filter(|key| !present.contains(*key)) {
+            report(ProblemKind::MissingAttribute(key.to_string()));
+        }
+
+        if let Some(comment) = &worklog.comment {
+            let trimmed = comment.trim();
+            if !trimmed.is_empty()
+                && PLACEHOLDER_COMMENTS.contains(&trimmed.to_lowercase().as_str())
+            {
+                report(ProblemKind::PlaceholderComment(trimmed.to_owned()));
+            }
+        }
+    }
+    problems
+}
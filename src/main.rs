@@ -1,115 +1,921 @@
 use anyhow::{anyhow, bail, Context, Result};
-use chrono::{Datelike, NaiveDate, TimeDelta};
-use clap::{Parser, Subcommand};
-use client::{Issue, JtClient};
-use config::{Config, StaticTask, WorkAttribute};
+use api_stats::ApiStats;
+use auth::AuthKind;
+use chrono::{DateTime, Datelike, NaiveDate, NaiveTime, TimeDelta, Utc, Weekday};
+use clap::{ArgAction, CommandFactory, Parser, Subcommand};
+use client::{
+    ApprovalState, AuthFailure, Issue, JtClient, NewWorklog, Priority, Review, TempoWorkAttribute,
+    Worklog,
+};
+use config::{
+    Absence, Config, DynamicAttribute, Instance, PromptedAttribute, StaticTask, StaticTaskRepair,
+};
 use console::style;
-use dialoguer::{Confirm, Input, Select};
+use context::RunContext;
+use dialoguer::{Confirm, FuzzySelect, Input, MultiSelect, Password, Select};
+use duration::{format_duration, parse_duration, split_evenly, STANDARD_DAY};
+use error::JtError;
+use export::ExportFormat;
+use futures_util::{future::try_join, stream, StreamExt, TryStreamExt};
 use indicatif::{ProgressBar, ProgressStyle};
+use jt_core::{
+    api_stats, balance, client, duration, endpoint, error, locale,
+    options::{
+        HttpOptions, RemainingEstimate, TempoVersion, UserIdentifier, Visibility, WorkAttribute,
+    },
+    plan, week,
+    work::{
+        self, allocate, even_tasks, planned_entries, split_entry, split_tags, tasks_named,
+        Plannable,
+    },
+};
+use locale::NumberFormat;
+use log::LevelFilter;
+use notify::Notifier;
 use rand::{seq::SliceRandom, thread_rng};
+use report::{project_of, OutputFormat, WeekGrid};
 use reqwest::Url;
-use std::{env, fmt::Display};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    fmt::Display,
+    path::{Path, PathBuf},
+    process::ExitCode,
+    sync::OnceLock,
+};
+use week::{week_days, MonthSpec, WeekSpec};
 
-mod client;
+mod auth;
+mod clockify;
 mod config;
+mod context;
+mod csv_import;
+mod diff;
+mod export;
+mod git;
+mod google;
+mod harvest;
+mod holiday;
+mod ics;
+mod import;
+mod lint;
+mod manual;
+mod notify;
+mod oauth;
+mod outlook;
+mod receipt;
+mod report;
+mod service_desk;
+mod state;
+mod tag;
+mod template;
+mod toggl;
+mod tui;
+
+const TEMPO_VERSION_CACHE: &str = "tempo-api-versions.json";
+const ATTRIBUTE_MEMORY: &str = "attribute-memory.json";
+const TASK_CACHE: &str = "tasks.json";
+const PRIORITY_CACHE: &str = "priorities.json";
+///How long an instance's priorities are reused before being fetched again
+const PRIORITY_CACHE_TTL: TimeDelta = TimeDelta::days(1);
+///How long the results of a task search are reused for before searching again
+const TASK_CACHE_TTL: TimeDelta = TimeDelta::minutes(10);
+///Issues due within this many days are flagged in the task picker
+const URGENT_WITHIN_DAYS: i64 = 7;
+///Issues at or above this position in the instance's priority order, most urgent first (Jira's
+///"High" by default), are flagged in the task picker
+const URGENT_PRIORITY_RANK: usize = 1;
+///Issues without any activity for longer than this many days are flagged as stale in the task picker
+const STALE_AFTER_DAYS: i64 = 60;
+///Most issues offered when searching for a replacement static task
+const STATIC_TASK_SEARCH_LIMIT: u32 = 20;
+
+///Time spent on a task on a given day, as selected by the user or read from a plan
+type Entry<'a> = work::Entry<'a, Task>;
+
+///Position of each priority in the instance's order, most urgent first, by priority ID
+static PRIORITY_RANKS: OnceLock<HashMap<String, usize>> = OnceLock::new();
+
+///Priorities of an instance, kept for reuse by later runs
+#[derive(Serialize, Deserialize)]
+struct CachedPriorities {
+    fetched: DateTime<Utc>,
+    priorities: Vec<Priority>,
+}
+
+///Results of a task search, kept for reuse by later runs
+#[derive(Serialize, Deserialize)]
+struct CachedTasks {
+    fetched: DateTime<Utc>,
+    total: usize,
+    issues: Vec<Issue>,
+}
 
-const DEFAULT_DAILY_TARGET: TimeDelta = TimeDelta::hours(8);
+struct UploadResult {
+    day: NaiveDate,
+    key: String,
+    time_spent: TimeDelta,
+    attributes: Vec<WorkAttribute>,
+    ///The worklog as created by Tempo, absent on dry runs
+    worklog: Option<Worklog>,
+}
 
 enum Task {
     Static(StaticTask),
     FromQuery(Issue),
+    Remote(RemoteIssue),
 }
 
-impl Task {
+///An issue on another instance, logged against a task on the main one
+struct RemoteIssue {
+    ///Name of the instance the issue is on
+    instance: String,
+    issue: Issue,
+    ///Issue on the main instance time is logged against
+    task: String,
+}
+
+impl Plannable for Task {
     fn key(&self) -> String {
         match self {
             Task::Static(s) => s.key.clone(),
             Task::FromQuery(f) => f.key.clone(),
+            Task::Remote(r) => r.issue.key.clone(),
+        }
+    }
+
+    fn group(&self) -> Option<&str> {
+        match self {
+            Task::Static(s) => s.group.as_deref(),
+            Task::FromQuery(_) | Task::Remote(_) => None,
+        }
+    }
+
+    fn is_static(&self) -> bool {
+        matches!(self, Task::Static(_))
+    }
+
+    fn percent(&self) -> Option<f64> {
+        match self {
+            Task::Static(s) => s.percent,
+            Task::FromQuery(_) | Task::Remote(_) => None,
+        }
+    }
+
+    fn billable(&self) -> Option<bool> {
+        match self {
+            Task::Static(s) => s.billable,
+            Task::FromQuery(_) | Task::Remote(_) => None,
+        }
+    }
+
+    fn default_comment(&self) -> Option<String> {
+        match self {
+            Task::Static(s) => s.comment.clone(),
+            Task::FromQuery(_) | Task::Remote(_) => None,
         }
     }
 }
 
+impl Task {
+    ///Issue on the main instance that worklogs for the task are created on
+    fn worklog_key(&self) -> String {
+        match self {
+            Task::Remote(r) => r.task.clone(),
+            _ => self.key(),
+        }
+    }
+
+    ///The comment for a worklog, naming the issue it was really spent on if that is on another
+    ///instance
+    fn worklog_comment(&self, comment: Option<String>) -> Option<String> {
+        let Task::Remote(remote) = self else {
+            return comment;
+        };
+        let key = &remote.issue.key;
+        Some(match comment {
+            Some(comment) => format!("{key}: {comment}"),
+            None => format!("{key} {}", remote.issue.summary().unwrap_or_default()),
+        })
+    }
+
+    ///Time offered when the task is selected, if it has its own default
+    fn default_time_spent(&self) -> Option<TimeDelta> {
+        match self {
+            Task::Static(s) => s
+                .default_time_spent_minutes
+                .map(|minutes| TimeDelta::minutes(minutes as i64)),
+            Task::FromQuery(_) | Task::Remote(_) => None,
+        }
+    }
+
+    ///Whether the task is an issue that has gone without activity for a long time by `today`
+    fn is_stale(&self, today: NaiveDate) -> bool {
+        match self {
+            Task::Static(_) => false,
+            Task::FromQuery(issue) | Task::Remote(RemoteIssue { issue, .. }) => {
+                issue_age(issue, today).is_some_and(|days| days > STALE_AFTER_DAYS)
+            }
+        }
+    }
+
+    ///How the task is offered for selection, noting if its issue is urgent or stale by `today`
+    fn label(&self, today: NaiveDate) -> String {
+        let Task::FromQuery(issue) = self else {
+            return self.to_string();
+        };
+        match urgency_badge(issue, today).or_else(|| stale_badge(issue, today)) {
+            Some(badge) => format!("{self} {badge}"),
+            None => self.to_string(),
+        }
+    }
+
+    fn project(&self) -> String {
+        project_of(&self.worklog_key()).to_owned()
+    }
+
+    ///Who can see worklogs against the task, from the task itself or its project
+    fn visibility(&self, config: &Config) -> Option<Visibility> {
+        let own = match self {
+            Task::Static(s) => s.visibility.clone(),
+            Task::FromQuery(_) | Task::Remote(_) => None,
+        };
+        own.or_else(|| config.worklog_visibility.get(&self.project()).cloned())
+    }
+}
+
 impl Display for Task {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            Task::Static(s) => write!(f, "{} - {}", s.key, s.description),
+            Task::Static(s) => match &s.group {
+                Some(group) => write!(
+                    f,
+                    "{} {} - {}",
+                    style(format!("{group} ›")).cyan(),
+                    s.key,
+                    s.description
+                ),
+                None => write!(f, "{} - {}", s.key, s.description),
+            },
             Task::FromQuery(q) => write!(f, "{}", q),
+            Task::Remote(r) => write!(
+                f,
+                "{} {}",
+                style(format!("{} ›", r.instance)).magenta(),
+                r.issue
+            ),
+        }
+    }
+}
+
+///A short note on how soon an issue is due, or on its priority if it is urgent
+fn urgency_badge(issue: &Issue, today: NaiveDate) -> Option<String> {
+    if let Some(due) = issue.due_date() {
+        let days = (due - today).num_days();
+        let badge = match days {
+            ..=-1 => style(String::from("⚠ overdue")).red(),
+            0 => style(String::from("⚠ due today")).red(),
+            1 => style(String::from("⚠ due tomorrow")).yellow(),
+            2..=URGENT_WITHIN_DAYS => style(format!("due in {days} days")).yellow(),
+            _ => return None,
+        };
+        return Some(badge.to_string());
+    }
+    match (issue.priority(), priority_rank(issue)) {
+        (Some(name), Some(rank)) if rank <= URGENT_PRIORITY_RANK => {
+            Some(style(format!("▲ {name}")).red().to_string())
         }
+        _ => None,
     }
 }
 
+///A note on how long an issue has gone without activity, if it is stale
+fn stale_badge(issue: &Issue, today: NaiveDate) -> Option<String> {
+    let days = issue_age(issue, today)?;
+    (days > STALE_AFTER_DAYS).then(|| style(format!("stale, {days} days")).dim().to_string())
+}
+
+///Days since an issue last changed
+fn issue_age(issue: &Issue, today: NaiveDate) -> Option<i64> {
+    Some((today - issue.updated()?).num_days())
+}
+
 #[derive(Parser)]
 #[command(version, about)]
 struct Args {
     #[command(subcommand)]
     command: Commands,
+    #[arg(long = "config", global = true, env = "JT_CONFIG", value_name = "PATH")]
+    ///Read the configuration from this file rather than the one in the platform's configuration directory
+    config_file: Option<PathBuf>,
+    #[arg(long, global = true)]
+    ///Use the named profile from the configuration file
+    profile: Option<String>,
+    #[arg(short, long, global = true, action = ArgAction::Count)]
+    ///Print more detailed output, repeat (-vv) to also log each HTTP request and response or (-vvv) their bodies too
+    verbose: u8,
+    #[arg(long, global = true, allow_hyphen_values = true)]
+    ///Week to work on, relative to the current week (eg -1 for last week) or as an ISO week (eg 2024-W18)
+    week: Option<WeekSpec>,
+    #[arg(long, global = true, conflicts_with = "week")]
+    ///Work on next week rather than the current week, the same as --week 1
+    next: bool,
+    #[arg(long, global = true, value_enum, default_value_t = OutputFormat::Table, alias = "preview")]
+    ///How to show results: weeks of work as a table or as blocks, or anything as JSON for scripts
+    output: OutputFormat,
+    #[arg(long, global = true)]
+    ///Do not actually log work or submit timesheets, instead listing every worklog that would be created
+    dry_run: bool,
+    #[arg(long, global = true)]
+    ///Ring the terminal bell and show a desktop notification when finished or waiting for input
+    notify: bool,
+    #[arg(long, global = true)]
+    ///Fetch tasks from JIRA rather than using those cached in the last few minutes
+    refresh: bool,
+    #[arg(long, global = true)]
+    ///Leave out dynamic attributes that cannot be read from an issue and have no fallback, rather than failing or prompting
+    skip_unresolved: bool,
 }
 
 #[derive(Subcommand)]
 enum Commands {
     ///Fill a timesheet
-    Fill {
-        #[arg(long)]
-        ///Do not actually log work
-        dry_run: bool,
+    #[command(after_long_help = manual::FILL_EXAMPLES)]
+    Fill(FillArgs),
+    ///Log time against a single task
+    #[command(after_long_help = manual::LOG_EXAMPLES)]
+    Log(LogArgs),
+    ///Generate a configuration file
+    #[command(after_long_help = manual::INIT_EXAMPLES)]
+    Init,
+    ///Select a week's tasks interactively and write them to a plan file for later use with `fill --plan`
+    #[command(after_long_help = manual::PLAN_EXAMPLES)]
+    Plan(PlanArgs),
+    ///Check a week's worklogs for common problems
+    #[command(after_long_help = manual::LINT_EXAMPLES)]
+    Lint,
+    ///List the tasks offered when filling a timesheet
+    #[command(after_long_help = manual::ISSUES_EXAMPLES)]
+    Issues(IssuesArgs),
+    ///Show the details of a single issue
+    #[command(after_long_help = manual::VIEW_EXAMPLES)]
+    View {
+        ///Key of the issue to show
+        key: String,
+    },
+    ///Show a week's worklogs as a table of time per issue per day, or a month's totals per issue
+    #[command(after_long_help = manual::REPORT_EXAMPLES)]
+    Report(ReportArgs),
+    ///Write your worklogs over a range of days to a CSV or JSON file
+    #[command(after_long_help = manual::EXPORT_EXAMPLES)]
+    Export(ExportArgs),
+    ///Edit a week as a grid of tasks by days in a full-screen editor, then log it all at once
+    #[command(after_long_help = manual::TUI_EXAMPLES)]
+    Tui(TuiArgs),
+    ///Compare each project's share of a week with its configured commitment
+    #[command(after_long_help = manual::BALANCE_EXAMPLES)]
+    Balance(BalanceArgs),
+    ///Copy a previous week's worklogs into another week
+    #[command(after_long_help = manual::COPY_WEEK_EXAMPLES)]
+    CopyWeek(CopyWeekArgs),
+    ///Log a week's time from a calendar or another time tracker, after reviewing it
+    #[command(after_long_help = manual::IMPORT_EXAMPLES)]
+    Import {
+        #[command(subcommand)]
+        command: ImportCommands,
+    },
+    ///Show the approval state of a week's timesheet and receipts of past submissions
+    #[command(after_long_help = manual::STATUS_EXAMPLES)]
+    Status,
+    ///Change the configuration without editing it by hand
+    #[command(after_long_help = manual::CONFIG_EXAMPLES)]
+    Config {
+        #[command(subcommand)]
+        command: ConfigCommands,
+    },
+    ///Manage the JIRA token stored in the OS keyring
+    #[command(after_long_help = manual::AUTH_EXAMPLES)]
+    Auth {
+        #[command(subcommand)]
+        command: AuthCommands,
+    },
+    ///Approve a team member's submitted timesheet
+    #[command(after_long_help = manual::APPROVE_EXAMPLES)]
+    Approve(ReviewArgs),
+    ///Reject a team member's submitted timesheet so they can correct it
+    #[command(after_long_help = manual::REJECT_EXAMPLES)]
+    Reject(ReviewArgs),
+    ///Print a script completing jt's commands and flags in the given shell
+    #[command(after_long_help = manual::COMPLETIONS_EXAMPLES)]
+    Completions { shell: clap_complete::Shell },
+    ///Write man pages for jt and each of its commands
+    #[command(hide = true)]
+    Man {
+        #[arg(long, default_value = ".")]
+        ///Directory to write the pages to
+        dir: PathBuf,
+    },
+}
+
+#[derive(clap::Args)]
+struct FillArgs {
+    #[arg(long, conflicts_with_all = ["submit", "from", "plan"])]
+    ///Fill just this day (YYYY-MM-DD) rather than a whole week
+    date: Option<NaiveDate>,
+    #[arg(long, conflicts_with = "submit")]
+    ///Fill the days from this date (YYYY-MM-DD) rather than a whole week
+    from: Option<NaiveDate>,
+    #[arg(long, requires = "from")]
+    ///Last day to fill when using --from, defaulting to today
+    to: Option<NaiveDate>,
+    #[arg(long)]
+    ///Submit timesheet for approval after adding work
+    submit: bool,
+    #[arg(long)]
+    ///Select task at random rather than prompting
+    random: bool,
+    #[arg(long, requires = "submit")]
+    ///Check the week's worklogs for problems before submitting, aborting if any are found
+    lint: bool,
+    #[arg(long)]
+    ///Prompt for attribute values again rather than reusing those remembered for each issue
+    reprompt_attributes: bool,
+    #[arg(long, conflicts_with_all = ["random", "reprompt_attributes"])]
+    ///Fill the timesheet from a plan file rather than prompting
+    plan: Option<PathBuf>,
+    #[arg(long)]
+    ///Do not check logging permissions before starting
+    skip_permission_check: bool,
+    #[arg(long)]
+    ///Do not check that the issues of static tasks still exist before starting
+    skip_static_task_check: bool,
+    #[arg(long, conflicts_with_all = ["random", "plan"])]
+    ///Prompt for a comment on each worklog
+    comments: bool,
+    #[arg(long, conflicts_with_all = ["random", "plan"])]
+    ///Pick each day's tasks all at once and split the day between them
+    multi_select: bool,
+    #[arg(long)]
+    ///Leave issues without recent activity out of the task picker
+    hide_stale: bool,
+    #[arg(long)]
+    ///Offer the tasks found by this named filter from the configuration
+    filter: Option<String>,
+    #[arg(long, conflicts_with_all = ["random", "plan", "comments", "multi_select"])]
+    ///Split each working day evenly between the static tasks without any prompts
+    even: bool,
+    #[arg(long, value_name = "auto|leave|DURATION")]
+    ///What to do to each issue's remaining estimate, overriding remaining_estimate in the configuration
+    remaining_estimate: Option<RemainingEstimate>,
+    #[arg(long, conflicts_with_all = ["random", "plan", "even"])]
+    ///Prompt for how much of each worklog is billable, rather than leaving it to the task or Tempo
+    prompt_billable: bool,
+    #[arg(long, requires = "even", value_delimiter = ',')]
+    ///Issues or static task groups to split the day between with --even, eg ABC-1,ABC-2
+    tasks: Vec<String>,
+}
+
+#[derive(clap::Args)]
+struct LogArgs {
+    ///Key of the issue or static task to log time against, defaulting to the issue named by the
+    ///current git branch
+    key: Option<String>,
+    ///Time spent on the task, eg 90m, 1h30m or 1.5h
+    duration: Option<String>,
+    #[arg(long)]
+    ///Day to log time against, defaults to today
+    date: Option<NaiveDate>,
+    #[arg(long)]
+    ///Comment to attach to the worklog, any #tags in which are treated as tags
+    comment: Option<String>,
+    #[arg(long = "tag")]
+    ///Tag the entry, as mapped in the configuration; may be repeated
+    tags: Vec<String>,
+    #[arg(long, value_name = "auto|leave|DURATION")]
+    ///What to do to the issue's remaining estimate, overriding remaining_estimate in the configuration
+    remaining_estimate: Option<RemainingEstimate>,
+    #[arg(long)]
+    ///How much of the time spent is billable, eg 0 or 45m, all of it unless the task says otherwise
+    billable: Option<String>,
+    #[arg(long, value_name = "HH:MM")]
+    ///Time of day the work started, overriding workday_start in the configuration
+    start: Option<NaiveTime>,
+}
+
+#[derive(clap::Args)]
+struct CopyWeekArgs {
+    #[arg(long, default_value = "-1", allow_hyphen_values = true)]
+    ///Week to copy, relative to the current week (eg -1 for last week) or as an ISO week (eg 2024-W18)
+    from: WeekSpec,
+    #[arg(long, allow_hyphen_values = true)]
+    ///Week to copy the worklogs into, in the same form as --from, defaulting to --week or the
+    ///current week
+    to: Option<WeekSpec>,
+}
+
+#[derive(clap::Args)]
+struct IssuesArgs {
+    #[arg(long)]
+    ///Leave out issues without recent activity
+    hide_stale: bool,
+    #[arg(long)]
+    ///List the tasks found by this named filter from the configuration
+    filter: Option<String>,
+}
+
+#[derive(clap::Args)]
+struct ReportArgs {
+    #[arg(long, allow_hyphen_values = true, num_args = 0..=1, default_missing_value = "0")]
+    ///Total each issue over a calendar month rather than showing a week, either relative to the
+    ///current month (eg -1 for last month) or as eg 2024-05, defaulting to the current month
+    month: Option<MonthSpec>,
+    #[arg(long, requires = "month")]
+    ///Also write the month's totals to this CSV file
+    csv: Option<PathBuf>,
+}
+
+#[derive(clap::Args)]
+struct ExportArgs {
+    #[arg(long)]
+    ///First day to export (YYYY-MM-DD)
+    from: NaiveDate,
+    #[arg(long)]
+    ///Last day to export, defaulting to today
+    to: Option<NaiveDate>,
+    #[arg(long, value_enum, default_value_t = ExportFormat::Csv)]
+    ///Whether to write a CSV file with a row per worklog or a JSON array
+    format: ExportFormat,
+    #[arg(long)]
+    ///File to write to, or - for standard output, defaulting to eg worklogs-2024-05-01-2024-05-31.csv
+    file: Option<PathBuf>,
+}
+
+#[derive(clap::Args)]
+struct BalanceArgs {
+    #[arg(long)]
+    ///Check a plan file rather than the worklogs already logged for the week
+    plan: Option<PathBuf>,
+    #[arg(long, requires = "plan")]
+    ///Rewrite the plan so that each project's share of the week matches its commitment, keeping
+    ///each day's total
+    auto: bool,
+}
+
+#[derive(clap::Args)]
+struct TuiArgs {
+    #[arg(long)]
+    ///Start from a plan file rather than an empty grid
+    plan: Option<PathBuf>,
+    #[arg(long)]
+    ///Use a named JQL filter from the configuration instead of the default query
+    filter: Option<String>,
+    #[arg(long)]
+    ///Leave issues without recent activity out of the tasks that can be added
+    hide_stale: bool,
+}
+
+#[derive(clap::Args)]
+struct ReviewArgs {
+    ///Worker whose timesheet to review, chosen from the configured team if not given
+    worker: Option<String>,
+    #[arg(long)]
+    ///Comment to leave with the decision, prompted for when rejecting
+    comment: Option<String>,
+}
+
+#[derive(clap::Args)]
+#[command(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
+struct PlanArgs {
+    #[command(subcommand)]
+    command: Option<PlanCommands>,
+    #[arg(required = true)]
+    ///File to write the plan to
+    file: Option<PathBuf>,
+    #[arg(long)]
+    ///Existing plan to use as a starting point
+    from: Option<PathBuf>,
+    #[arg(long)]
+    ///Prompt for a comment on each entry
+    comments: bool,
+    #[arg(long)]
+    ///Pick each day's tasks all at once and split the day between them
+    multi_select: bool,
+    #[arg(long)]
+    ///Leave issues without recent activity out of the task picker
+    hide_stale: bool,
+    #[arg(long)]
+    ///Offer the tasks found by this named filter from the configuration
+    filter: Option<String>,
+}
+
+#[derive(Subcommand)]
+enum PlanCommands {
+    ///Write a plan from the issues a JQL search finds, spread evenly across the given days
+    Generate(GenerateArgs),
+}
+
+#[derive(clap::Args)]
+struct GenerateArgs {
+    ///File to write the plan to
+    file: PathBuf,
+    #[arg(long)]
+    ///JQL selecting the issues to plan, in which {from} stands for the day before the week
+    jql: String,
+    #[arg(long)]
+    ///Time planned against each issue
+    minutes_per_issue: u64,
+    #[arg(long, default_value = "mon..fri")]
+    ///Days to spread the issues over, as a range (eg mon..fri) or a list (eg mon,wed,fri)
+    days: plan::Weekdays,
+}
+
+#[derive(Subcommand)]
+enum ConfigCommands {
+    ///Check the configuration against the JIRA instance, reporting every problem found
+    Validate,
+    ///Open the configuration file in $VISUAL or $EDITOR, then show and check the changes
+    Edit,
+    ///Print the configuration in effect, with defaults filled in and secrets redacted
+    Show,
+    ///Add a static task, checking its issue exists
+    AddTask {
         #[arg(long)]
-        ///Fill timesheet for next week rather than current week
-        next: bool,
+        ///Group to add the task to, where static tasks are declared in groups
+        group: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum ImportCommands {
+    ///Log meetings from Google Calendar against the task configured for them
+    Google,
+    ///Log accepted meetings from an Outlook or Microsoft 365 calendar against the task configured
+    ///for them
+    Outlook,
+    ///Log events from an exported calendar file against the tasks their titles map to
+    Ics {
+        ///ICS file to read events from
+        file: PathBuf,
+    },
+    ///Log time tracked in Toggl Track against the tasks its projects and tags map to
+    Toggl,
+    ///Log time tracked in Harvest against the tasks its projects and tasks map to
+    Harvest,
+    ///Log time tracked in Clockify against the tasks its projects and tags map to
+    Clockify,
+    ///Log worklogs from a CSV file of date, issue key, minutes and an optional comment, regardless
+    ///of week
+    Csv {
+        ///CSV file to read rows from, with or without a header row
+        file: PathBuf,
+    },
+}
+
+#[derive(Subcommand)]
+enum AuthCommands {
+    ///Store a JIRA token in the OS keyring, or sign in through the browser if OAuth is configured
+    Login {
         #[arg(long)]
-        ///Submit timesheet for approval after adding work
-        submit: bool,
+        ///Store a username and password for basic authentication instead, as used when the
+        ///configuration has `auth = "basic"`
+        basic: bool,
+        #[arg(long, conflicts_with = "basic")]
+        ///Store the token for one of the other instances whose issues are offered as tasks
+        instance: Option<String>,
+    },
+    ///Remove the JIRA token, or username and password, from the OS keyring
+    Logout {
         #[arg(long)]
-        ///Select task at random rather than prompting
-        random: bool,
+        ///Remove the token for one of the other instances instead
+        instance: Option<String>,
     },
-    ///Generate a configuration file
-    Init,
 }
 
 #[tokio::main(flavor = "current_thread")]
-async fn main() -> Result<()> {
-    env_logger::init();
+async fn main() -> ExitCode {
     let args = Args::parse();
-    let token = env::var("JIRA_TOKEN")?;
+    init_logging(args.verbose);
+    if let Some(path) = args.config_file {
+        config::use_config_file(path);
+    }
+    let ctx = RunContext {
+        profile: args.profile,
+        verbose: args.verbose > 0,
+        week: args.week.or(args.next.then_some(WeekSpec::Offset(1))),
+        output: args.output,
+        dry_run: args.dry_run,
+        notify: args.notify,
+        refresh: args.refresh,
+        skip_unresolved: args.skip_unresolved,
+        api_stats: ApiStats::default(),
+        zone: OnceLock::new(),
+    };
+
+    let result = match args.command {
+        Commands::Fill(fill_args) => fill(&ctx, fill_args).await,
+        Commands::Plan(plan_args) => plan(&ctx, plan_args).await,
+        Commands::Log(log_args) => log(&ctx, log_args).await,
+        Commands::Init => init(&ctx).await,
+        Commands::Lint => lint(&ctx).await,
+        Commands::Issues(issues_args) => issues(&ctx, issues_args).await,
+        Commands::View { key } => view(&ctx, &key).await,
+        Commands::Report(report_args) => report(&ctx, report_args).await,
+        Commands::Export(export_args) => export(&ctx, export_args).await,
+        Commands::Tui(tui_args) => tui(&ctx, tui_args).await,
+        Commands::Balance(balance_args) => balance(&ctx, balance_args).await,
+        Commands::CopyWeek(copy_args) => copy_week(&ctx, copy_args).await,
+        Commands::Import { command } => import(&ctx, command).await,
+        Commands::Status => status(&ctx).await,
+        Commands::Config { command } => configure(&ctx, command).await,
+        Commands::Auth { command } => auth(command, &ctx).await,
+        Commands::Approve(review_args) => review(&ctx, review_args, Review::Approve).await,
+        Commands::Reject(review_args) => review(&ctx, review_args, Review::Reject).await,
+        Commands::Completions { shell } => {
+            manual::write_completions(Args::command(), shell);
+            Ok(())
+        }
+        Commands::Man { dir } => manual::write_man_pages(Args::command(), &dir),
+    };
+    if ctx.verbose {
+        print_api_summary(&ctx.api_stats.summary());
+    }
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("Error: {e:?}");
+            exit_code(&e)
+        }
+    }
+}
+
+///Print the calls made during the run, if there were any
+fn print_api_summary(summary: &api_stats::Summary) {
+    if summary.endpoints.is_empty() {
+        return;
+    }
+    eprintln!("{}", style("API calls").bold());
+    let width = summary.endpoints.keys().map(String::len).max().unwrap_or(0);
+    for (endpoint, counts) in &summary.endpoints {
+        let skipped = match counts.skipped {
+            0 => String::new(),
+            skipped => style(format!(" ({skipped} skipped by dry run)"))
+                .dim()
+                .to_string(),
+        };
+        eprintln!("  {endpoint:<width$} {:>4}{skipped}", counts.calls);
+    }
+    eprintln!(
+        "{}",
+        style(format!(
+            "{} calls, {:.1?} spent in requests",
+            summary.calls(),
+            summary.network_time
+        ))
+        .dim()
+    );
+}
+
+///Exit status telling scripts what kind of failure stopped the run, following sysexits.h
+fn exit_code(e: &anyhow::Error) -> ExitCode {
+    let code = match e.chain().find_map(|cause| cause.downcast_ref::<JtError>()) {
+        Some(JtError::Validation(_)) => 65,
+        Some(JtError::Http(e)) if e.is_decode() => 76,
+        Some(JtError::Http(_)) => 69,
+        Some(JtError::Io { .. }) => 74,
+        Some(JtError::Api { .. }) => 76,
+        Some(JtError::Auth(_)) => 77,
+        Some(JtError::Config(_)) => 78,
+        _ => 1,
+    };
+    ExitCode::from(code)
+}
 
-    match args.command {
-        Commands::Fill {
-            dry_run,
-            next,
-            submit,
-            random,
-        } => fill(token, dry_run, next, submit, random).await,
-        Commands::Init => init(token).await,
+///Log as RUST_LOG asks, and from `-vv` each HTTP request and response as well
+fn init_logging(verbose: u8) {
+    let mut logger = env_logger::Builder::from_default_env();
+    match verbose {
+        0 | 1 => {}
+        2 => {
+            logger.filter_module("jt_core::trace", LevelFilter::Debug);
+        }
+        _ => {
+            logger.filter_module("jt_core::trace", LevelFilter::Trace);
+        }
+    }
+    logger.init();
+}
+
+async fn auth(command: AuthCommands, ctx: &RunContext) -> Result<()> {
+    match command {
+        AuthCommands::Login {
+            instance: Some(instance),
+            ..
+        } => {
+            let token = Password::new()
+                .with_prompt(format!("Personal access token for {instance}"))
+                .interact()
+                .unwrap();
+            auth::store_instance_token(&instance, &token)?;
+            println!("{}", style("Token stored in keyring").green());
+        }
+        AuthCommands::Login { basic, .. } => {
+            let config = config::load_config(ctx.profile()).ok();
+            let kind = match (basic, &config) {
+                (true, _) => AuthKind::Basic,
+                (false, Some(config)) => config.auth,
+                (false, None) => AuthKind::Bearer,
+            };
+            if let (AuthKind::Oauth, Some(config)) = (kind, &config) {
+                ctx.oauth_token(config, true).await?;
+                println!(
+                    "{}",
+                    style("Signed in, refresh token stored in keyring").green()
+                );
+            } else if kind == AuthKind::Basic {
+                let username: String = Input::new()
+                    .with_prompt("JIRA username")
+                    .interact_text()
+                    .unwrap();
+                let password = Password::new()
+                    .with_prompt("JIRA password or API token")
+                    .interact()
+                    .unwrap();
+                auth::store_username(&username, ctx.profile())?;
+                auth::store_token(&password, ctx.profile())?;
+                println!(
+                    "{}",
+                    style("Username and password stored in keyring").green()
+                );
+            } else {
+                let token = Password::new()
+                    .with_prompt("JIRA personal access token")
+                    .interact()
+                    .unwrap();
+                auth::store_token(&token, ctx.profile())?;
+                println!("{}", style("Token stored in keyring").green());
+            }
+        }
+        AuthCommands::Logout {
+            instance: Some(instance),
+        } => {
+            auth::delete_instance_token(&instance)?;
+            println!("{}", style("Token removed from keyring").green());
+        }
+        AuthCommands::Logout { instance: None } => {
+            auth::delete_token(ctx.profile())?;
+            println!("{}", style("Credentials removed from keyring").green());
+        }
     }
+    Ok(())
 }
 
-async fn init(token: String) -> Result<()> {
+async fn init(ctx: &RunContext) -> Result<()> {
+    let auth = auth::detect_kind(ctx.profile());
+    let credentials = auth::resolve_credentials(ctx.profile(), auth, None)?;
     let endpoint: Url = Input::new()
-        .with_prompt("JIRA instance URL (eg \"https://jira.yourcompany.com\")")
+        .with_prompt("JIRA instance URL, including any context path (eg \"https://jira.yourcompany.com\" or \"https://tools.yourcompany.com/jira\")")
+        .validate_with(|input: &Url| endpoint::base_url(input, None).map(|_| ()).map_err(|e| e.to_string()))
         .interact()
         .unwrap();
-    let client = JtClient::new(&token, endpoint.clone(), true);
+    let endpoint = endpoint::base_url(&endpoint, None)?;
+    let client = JtClient::new(
+        &credentials,
+        endpoint.clone(),
+        true,
+        UserIdentifier::Key,
+        &HttpOptions::default(),
+    )?
+    .with_stats(ctx.api_stats.clone());
     let spinner = ProgressBar::new_spinner().with_message("Validating instance URL");
     spinner.enable_steady_tick(std::time::Duration::from_millis(100));
-    client.health_check().await?;
+    let server_info = client.health_check().await.with_context(|| {
+        format!("No Jira instance found at {endpoint}, check the URL includes any context path (eg /jira)")
+    });
     spinner.finish_and_clear();
+    let server_info = server_info?;
     println!("{}", style("Instance URL validated").green());
 
-    let username: String = Input::new()
-        .with_prompt("Your JIRA username (eg \"jsmith\")")
-        .interact()
-        .unwrap();
-    let spinner = ProgressBar::new_spinner().with_message("Retrieving user key");
-    spinner.enable_steady_tick(std::time::Duration::from_millis(100));
-    let user_key = client.get_user_key(&username).await?;
-    spinner.finish_and_clear();
-    println!("{}", style("User key retrieved").green());
+    let user_identifier = if server_info.is_cloud() {
+        println!("Jira Cloud detected, users will be identified by account ID");
+        UserIdentifier::AccountId
+    } else {
+        UserIdentifier::Key
+    };
 
-    let daily_time_target: u64 = Input::new()
-        .with_prompt("Your daily target for time spent on tasks (in minutes, default is equivalent to 8 hours)")
-        .default(480)
-        .interact()
+    let user_key = resolve_user(&client, user_identifier, "Your").await?;
+
+    let number_format = NumberFormat::resolve(None);
+    let daily_time_target: String = Input::new()
+        .with_prompt("Your daily target for time spent on tasks (eg 8h, 7h30m or minutes)")
+        .default(String::from("8h"))
+        .validate_with(|input: &String| {
+            parse_duration(input, STANDARD_DAY, number_format)
+                .map(|_| ())
+                .map_err(|e| e.to_string())
+        })
+        .interact_text()
         .unwrap();
+    let daily_time_target = parse_duration(&daily_time_target, STANDARD_DAY, number_format)?;
 
     let specify_reviewer = Confirm::new()
         .with_prompt("Specify reviewer? (enables submission)")
@@ -117,31 +923,34 @@ async fn init(token: String) -> Result<()> {
         .interact()
         .unwrap();
     let reviewer = if specify_reviewer {
-        let reviewer_username: String = Input::new()
-            .with_prompt("Your reviewer's JIRA username (eg \"jsmith\")")
-            .interact()
-            .unwrap();
-        let spinner = ProgressBar::new_spinner().with_message("Retrieving reviewer key");
-        spinner.enable_steady_tick(std::time::Duration::from_millis(100));
-        let reviewer_key = client.get_user_key(&reviewer_username).await?;
-        spinner.finish_and_clear();
-        println!("{}", style("Reviewer key retrieved").green());
+        let reviewer_key = resolve_user(&client, user_identifier, "Your reviewer's").await?;
         Some(reviewer_key)
     } else {
         None
     };
 
+    let attributes = choose_attributes(&client).await?;
+
+    let mut static_tasks: Vec<StaticTask> = Vec::new();
+    while Confirm::new()
+        .with_prompt("Add a static task, eg for meetings or time off?")
+        .default(false)
+        .interact()
+        .unwrap()
+    {
+        static_tasks.push(prompt_static_task(&client, number_format).await?);
+    }
+
     let config = Config {
-        api_endpoint: endpoint,
-        worker: user_key,
         reviewer,
-        daily_target_time_spent_minutes: Some(daily_time_target),
-        default_time_spent_minutes: None,
-        static_tasks: Vec::new(),
-        static_attributes: Vec::new(),
-        dynamic_attributes: Vec::new(),
+        daily_target_time_spent_minutes: Some(daily_time_target.num_minutes() as u64),
+        static_tasks,
+        static_attributes: attributes.fixed,
+        dynamic_attributes: attributes.dynamic,
+        prompted_attributes: attributes.prompted,
+        ..Config::new(endpoint, user_key, user_identifier, auth)
     };
-    config::write_config(config)?;
+    config::write_config(config, ctx.profile())?;
     println!(
         "\n{}\n",
         style(format!(
@@ -155,178 +964,3268 @@ async fn init(token: String) -> Result<()> {
     Ok(())
 }
 
-async fn fill(
-    token: String,
-    dry_run: bool,
-    next: bool,
-    auto_submit: bool,
-    random: bool,
-) -> Result<()> {
-    let config = config::load_config()?;
-    let client = JtClient::new(&token, config.api_endpoint.clone(), dry_run);
+///Work attributes chosen during `init`, by how their values are found
+#[derive(Default)]
+struct ChosenAttributes {
+    fixed: Vec<WorkAttribute>,
+    dynamic: Vec<DynamicAttribute>,
+    prompted: Vec<PromptedAttribute>,
+}
 
-    let now = chrono::Local::now();
-    let week = if next {
-        (now + TimeDelta::weeks(1)).iso_week()
-    } else {
-        now.iso_week()
+///Offer the work attributes defined in Tempo and ask how to fill in each one chosen
+async fn choose_attributes(client: &JtClient) -> Result<ChosenAttributes> {
+    let mut chosen = ChosenAttributes::default();
+    let configure = Confirm::new()
+        .with_prompt("Configure Tempo work attributes?")
+        .default(false)
+        .interact()
+        .unwrap();
+    if !configure {
+        return Ok(chosen);
+    }
+    for attribute in select_work_attributes(client, "Attributes to set on worklogs").await? {
+        let how = Select::new()
+            .with_prompt(format!("How should {} be set?", attribute.name))
+            .items(&[
+                "The same value on every worklog",
+                "Read from a field of each issue",
+                "Asked for the first time each issue is logged against",
+            ])
+            .default(0)
+            .interact()
+            .unwrap();
+        match how {
+            0 => {
+                let value = prompt_attribute_value(&attribute);
+                chosen.fixed.push(work_attribute(&attribute, value));
+            }
+            1 => {
+                let pointer: String = Input::new()
+                    .with_prompt("Pointer to the value within the issue's fields (eg \"/customfield_12345/value\")")
+                    .interact_text()
+                    .unwrap();
+                chosen.dynamic.push(DynamicAttribute {
+                    attribute: work_attribute(&attribute, pointer),
+                    fallback: None,
+                });
+            }
+            _ => chosen.prompted.push(PromptedAttribute {
+                key: attribute.key.clone(),
+                name: attribute.name.clone(),
+                work_attribute_id: attribute.id,
+                options: attribute.options(),
+            }),
+        }
+    }
+    Ok(chosen)
+}
+
+///Attributes with a fixed value for each of the work attributes chosen, for a static task
+async fn choose_task_attributes(client: &JtClient) -> Result<Vec<WorkAttribute>> {
+    let selected =
+        select_work_attributes(client, "Attributes to set on the task's worklogs").await?;
+    Ok(selected
+        .iter()
+        .map(|attribute| work_attribute(attribute, prompt_attribute_value(attribute)))
+        .collect())
+}
+
+///Let the user pick from the work attributes defined in Tempo, with required ones preselected,
+///choosing none if they cannot be retrieved
+async fn select_work_attributes(
+    client: &JtClient,
+    prompt: &str,
+) -> Result<Vec<TempoWorkAttribute>> {
+    let spinner = ProgressBar::new_spinner().with_message("Retrieving work attributes");
+    spinner.enable_steady_tick(std::time::Duration::from_millis(100));
+    let available = client.get_work_attributes().await;
+    spinner.finish_and_clear();
+    let available = match available {
+        Ok(available) if !available.is_empty() => available,
+        Ok(_) => {
+            println!("{}", style("No work attributes defined in Tempo").yellow());
+            return Ok(Vec::new());
+        }
+        Err(e) => {
+            println!(
+                "{}",
+                style(format!("Unable to retrieve work attributes, skipping: {e}")).yellow()
+            );
+            return Ok(Vec::new());
+        }
     };
-    let first_day =
-        NaiveDate::from_isoywd_opt(now.year(), week.week(), chrono::Weekday::Mon).unwrap();
-    let done_tasks_from = first_day - TimeDelta::days(1);
+    let defaults: Vec<bool> = available
+        .iter()
+        .map(|attribute| attribute.required)
+        .collect();
+    let selected = MultiSelect::new()
+        .with_prompt(format!("{prompt} (space to select, enter to confirm)"))
+        .items(&available)
+        .defaults(&defaults)
+        .interact()
+        .unwrap();
+    Ok(available
+        .into_iter()
+        .enumerate()
+        .filter(|(i, _)| selected.contains(i))
+        .map(|(_, attribute)| attribute)
+        .collect())
+}
 
-    let issues = get_tasks(&client, done_tasks_from).await?;
-    let mut tasks: Vec<Task> = issues.into_iter().map(Task::FromQuery).collect();
-    tasks.extend(config.static_tasks.into_iter().map(Task::Static));
-
-    let target_per_day = config
-        .daily_target_time_spent_minutes
-        .map(|minutes| TimeDelta::minutes(minutes as i64))
-        .unwrap_or(DEFAULT_DAILY_TARGET);
-    let mut work = Vec::new();
-    for day in first_day.iter_days().take(5) {
-        let today = select_days_tasks(
-            day,
-            &tasks,
-            target_per_day,
-            config
-                .default_time_spent_minutes
-                .map(|minutes| TimeDelta::minutes(minutes as i64)),
-            random,
-        )?;
-        let today = today
-            .into_iter()
-            .map(|(task, duration)| (day, task, duration));
-        work.extend(today);
+///Ask for a single value of a work attribute, from its options if it has any
+fn prompt_attribute_value(attribute: &TempoWorkAttribute) -> String {
+    let options = attribute.options();
+    if options.is_empty() {
+        return Input::new()
+            .with_prompt(format!("Value for {}", attribute.name))
+            .interact_text()
+            .unwrap();
     }
+    let option = Select::new()
+        .with_prompt(format!("Value for {}", attribute.name))
+        .items(&options)
+        .default(0)
+        .interact()
+        .unwrap();
+    options[option].clone()
+}
 
-    upload_worklogs(
-        &client,
-        config.dynamic_attributes,
-        config.static_attributes,
-        &config.worker,
-        work,
-    )
-    .await?;
+fn work_attribute(attribute: &TempoWorkAttribute, value: String) -> WorkAttribute {
+    WorkAttribute {
+        key: attribute.key.clone(),
+        name: attribute.name.clone(),
+        work_attribute_id: attribute.id,
+        value,
+    }
+}
 
-    if auto_submit {
-        submit(&client, config.reviewer, &config.worker, first_day).await?;
+async fn configure(ctx: &RunContext, command: ConfigCommands) -> Result<()> {
+    match command {
+        ConfigCommands::Validate => {
+            let problems = validate_config(ctx).await?;
+            if !problems.is_empty() {
+                bail!("{} problems found in configuration", problems.len());
+            }
+        }
+        ConfigCommands::Edit => edit_config(ctx).await?,
+        ConfigCommands::Show => show_config(ctx)?,
+        ConfigCommands::AddTask { group } => {
+            let config = ctx.config()?;
+            let client = connect(ctx, &config, true).await?;
+            let task = prompt_static_task(&client, config.number_format()).await?;
+            if config.static_tasks.iter().any(|t| t.key == task.key) {
+                bail!("{} is already a static task", task.key);
+            }
+            config::add_static_task(ctx.profile(), &task, group.as_deref())?;
+            println!(
+                "{}",
+                style(format!("Static task {} added", task.key)).green()
+            );
+        }
     }
+    Ok(())
+}
 
+fn show_config(ctx: &RunContext) -> Result<()> {
+    let config = ctx.config()?;
+    let table = config::effective_table(ctx.profile(), &config)?;
+    if ctx.output == OutputFormat::Json {
+        println!("{}", serde_json::to_string_pretty(&table)?);
+        return Ok(());
+    }
+    let profile = ctx
+        .profile()
+        .map(|name| format!(" for profile \"{name}\""))
+        .unwrap_or_default();
+    println!(
+        "{}",
+        style(format!(
+            "# Configuration{profile} from {}",
+            config::config_file_location().display()
+        ))
+        .dim()
+    );
+    let overridden = config::env_override_vars();
+    if !overridden.is_empty() {
+        println!(
+            "{}",
+            style(format!("# Overridden by {}", overridden.join(", "))).dim()
+        );
+    }
+    println!("{}", toml::to_string_pretty(&table)?);
+    let token = match auth::token_source(ctx.profile(), config.token_command.as_deref()) {
+        Some(source) => format!("from {source}"),
+        None => String::from("not found"),
+    };
+    let numbers = config.number_format();
+    println!(
+        "{}",
+        style(format!(
+            "# JIRA token: {token}\n# Daily target: {}\n# Decimal separator: '{}'",
+            format_duration(config.daily_target(ctx.today())),
+            numbers.decimal
+        ))
+        .dim()
+    );
     Ok(())
 }
 
-fn select_days_tasks(
-    day: NaiveDate,
-    tasks: &[Task],
-    target_per_day: TimeDelta,
-    default_time_spent: Option<TimeDelta>,
-    random: bool,
-) -> Result<Vec<(&Task, TimeDelta)>> {
-    let mut today = Vec::new();
+///Let the user edit the configuration file until it loads, then show what changed and validate it
+async fn edit_config(ctx: &RunContext) -> Result<()> {
+    let location = config::config_file_location();
+    let before = std::fs::read_to_string(&location)
+        .with_context(|| format!("Unable to read {}, run jt init first", location.display()))?;
+    loop {
+        open_editor(&location)?;
+        let after = std::fs::read_to_string(&location)?;
+        if after == before {
+            println!("No changes made");
+            return Ok(());
+        }
+        diff::print_diff(&before, &after);
+        match config::load_config(ctx.profile()) {
+            Ok(_) => break,
+            Err(e) => {
+                println!(
+                    "{}",
+                    style(format!("The configuration no longer loads: {e:#}")).red()
+                );
+                let again = Confirm::new()
+                    .with_prompt("Edit it again?")
+                    .default(true)
+                    .interact()
+                    .unwrap();
+                if !again {
+                    bail!("Configuration left unloadable");
+                }
+            }
+        }
+    }
+    let problems = validate_config(ctx).await?;
+    if !problems.is_empty() {
+        bail!("{} problems found in configuration", problems.len());
+    }
+    Ok(())
+}
+
+///Open a file in the editor named by $VISUAL or $EDITOR, waiting for it to close
+fn open_editor(path: &Path) -> Result<()> {
+    let editor = std::env::var("VISUAL")
+        .or_else(|_| std::env::var("EDITOR"))
+        .unwrap_or_else(|_| String::from(if cfg!(windows) { "notepad" } else { "vi" }));
+    let mut words = shell_words::split(&editor)?;
+    if words.is_empty() {
+        bail!("No editor set, set $EDITOR");
+    }
+    let program = words.remove(0);
+    let status = std::process::Command::new(&program)
+        .args(words)
+        .arg(path)
+        .status()
+        .with_context(|| format!("Unable to run editor \"{editor}\""))?;
+    if !status.success() {
+        bail!("Editor exited with {status}");
+    }
+    Ok(())
+}
+
+///Check everything in the configuration that would otherwise only fail partway through a run,
+///printing each problem found
+async fn validate_config(ctx: &RunContext) -> Result<Vec<String>> {
+    let config = ctx.config()?;
+    let mut problems: Vec<String> = config
+        .dynamic_attributes
+        .iter()
+        .filter_map(|dynamic| {
+            let problem = dynamic.value_problem()?;
+            Some(format!(
+                "Dynamic attribute {} has an invalid value: {problem}",
+                dynamic.attribute.name
+            ))
+        })
+        .collect();
+
+    let spinner =
+        ProgressBar::new_spinner().with_message(style("Checking configuration").bold().to_string());
+    spinner.enable_steady_tick(std::time::Duration::from_millis(100));
+    let client = JtClient::new(
+        &ctx.credentials(&config).await?,
+        config.base_url()?,
+        true,
+        config.user_identifier,
+        &config.http_options(),
+    )?
+    .with_stats(ctx.api_stats.clone());
+    if let Err(e) = client.health_check().await {
+        problems.push(format!(
+            "No Jira instance reachable at {}: {e}",
+            config.base_url()?
+        ));
+    } else {
+        match connect(ctx, &config, true).await {
+            Ok(client) => problems.extend(check_users_and_tasks(&client, &config).await),
+            Err(e) => problems.push(format!("Unable to connect to Jira: {e:#}")),
+        }
+    }
+    spinner.finish_and_clear();
+
+    if problems.is_empty() {
+        println!("{}", style("No problems found").green());
+    }
+    for problem in &problems {
+        println!("{} {}", style("✗").red().bold(), problem);
+    }
+    Ok(problems)
+}
+
+///Problems with the users and static tasks the configuration names, noting any that cannot be
+///checked rather than stopping there
+async fn check_users_and_tasks(client: &JtClient, config: &Config) -> Vec<String> {
+    let mut problems = Vec::new();
+    let users = std::iter::once(("Worker", &config.worker))
+        .chain(
+            config
+                .reviewer
+                .iter()
+                .map(|reviewer| ("Reviewer", reviewer)),
+        )
+        .chain(config.team.iter().map(|member| ("Team member", member)));
+    for (role, id) in users {
+        match client.user_exists(id).await {
+            Ok(true) => {}
+            Ok(false) => problems.push(format!("{role} {id} does not exist")),
+            Err(e) => problems.push(format!("Unable to check {role} {id}: {e}")),
+        }
+    }
+    for task in &config.static_tasks {
+        match client.find_issue(&task.key).await {
+            Ok(None) => problems.push(format!("Static task {} does not exist", task.key)),
+            Ok(Some(issue)) if issue.key != task.key => problems.push(format!(
+                "Static task {} has moved to {}",
+                task.key, issue.key
+            )),
+            Ok(Some(_)) => {}
+            Err(e) => problems.push(format!("Unable to check static task {}: {e}", task.key)),
+        }
+    }
+    problems
+}
+
+///Ask for the details of a static task, checking its issue exists
+async fn prompt_static_task(client: &JtClient, number_format: NumberFormat) -> Result<StaticTask> {
+    let issue = loop {
+        let key: String = Input::new()
+            .with_prompt("Issue key (eg \"ADMIN-12\")")
+            .interact_text()
+            .unwrap();
+        let spinner = ProgressBar::new_spinner().with_message("Checking issue");
+        spinner.enable_steady_tick(std::time::Duration::from_millis(100));
+        let issue = client.find_issue(key.trim()).await;
+        spinner.finish_and_clear();
+        match issue? {
+            Some(issue) => break issue,
+            None => println!("{}", style(format!("No issue {key} found")).red()),
+        }
+    };
+    let description: String = Input::new()
+        .with_prompt("Description")
+        .default(issue.summary().unwrap_or_default().to_owned())
+        .interact_text()
+        .unwrap();
+    let default_time_spent: String = Input::new()
+        .with_prompt("Default time spent when selected (optional, eg 30m)")
+        .allow_empty(true)
+        .validate_with(|input: &String| {
+            if input.is_empty() {
+                return Ok(());
+            }
+            parse_duration(input, STANDARD_DAY, number_format)
+                .map(|_| ())
+                .map_err(|e| e.to_string())
+        })
+        .interact_text()
+        .unwrap();
+    let default_time_spent_minutes = match default_time_spent.as_str() {
+        "" => None,
+        input => Some(parse_duration(input, STANDARD_DAY, number_format)?.num_minutes() as u64),
+    };
+    let attributes = if Confirm::new()
+        .with_prompt("Set work attributes on this task's worklogs?")
+        .default(false)
+        .interact()
+        .unwrap()
+    {
+        choose_task_attributes(client).await?
+    } else {
+        Vec::new()
+    };
+    Ok(StaticTask {
+        key: issue.key,
+        description,
+        attributes,
+        comment: None,
+        percent: None,
+        default_time_spent_minutes,
+        visibility: None,
+        billable: None,
+        disabled: false,
+        group: None,
+    })
+}
+
+async fn connect(ctx: &RunContext, config: &Config, dry_run: bool) -> Result<JtClient> {
+    if config.danger_accept_invalid_certs {
+        eprintln!(
+            "{}",
+            style("WARNING: certificate verification is disabled, anyone between you and Jira can read your token and worklogs")
+                .red()
+                .bold()
+        );
+    }
+    let mut client = JtClient::new(
+        &ctx.credentials(config).await?,
+        config.base_url()?,
+        dry_run,
+        config.user_identifier,
+        &config.http_options(),
+    )?
+    .with_stats(ctx.api_stats.clone());
+    if let Some(performance) = &config.performance {
+        let mut performance = performance.clone();
+        performance.fields.extend(
+            config
+                .dynamic_attributes
+                .iter()
+                .flat_map(DynamicAttribute::fields),
+        );
+        client = client.with_performance(performance);
+    }
+    match client.check_credentials().await {
+        Err(e @ JtError::Auth(AuthFailure::Rejected)) => {
+            return Err(e).context(auth::renewal_instructions(
+                config.auth,
+                config.token_command.is_some(),
+                client.base(),
+            ))
+        }
+        result => result?,
+    }
+    let version = match config.tempo_api_version {
+        Some(version) => version,
+        None => tempo_version(&client, &config.api_endpoint).await?,
+    };
+    Ok(client.with_tempo_version(version))
+}
+
+///Detect the Tempo API version exposed by an instance, caching the result per endpoint
+async fn tempo_version(client: &JtClient, endpoint: &Url) -> Result<TempoVersion> {
+    let mut cached: HashMap<String, TempoVersion> =
+        state::load_cache(TEMPO_VERSION_CACHE).unwrap_or_default();
+    if let Some(version) = cached.get(endpoint.as_str()) {
+        return Ok(*version);
+    }
+    let version = client.detect_tempo_version().await?;
+    cached.insert(endpoint.to_string(), version);
+    if let Err(e) = state::write_cache(TEMPO_VERSION_CACHE, &cached) {
+        log::warn!("Unable to cache Tempo API version: {e}");
+    }
+    Ok(version)
+}
+
+async fn resolve_user(
+    client: &JtClient,
+    user_identifier: UserIdentifier,
+    whose: &str,
+) -> Result<String> {
+    match user_identifier {
+        UserIdentifier::Key => {
+            let username: String = Input::new()
+                .with_prompt(format!("{whose} JIRA username (eg \"jsmith\")"))
+                .interact()
+                .unwrap();
+            let spinner = ProgressBar::new_spinner().with_message("Retrieving user key");
+            spinner.enable_steady_tick(std::time::Duration::from_millis(100));
+            let key = client.get_user_key(&username).await?;
+            spinner.finish_and_clear();
+            println!("{}", style("User key retrieved").green());
+            Ok(key)
+        }
+        UserIdentifier::AccountId => {
+            let query: String = Input::new()
+                .with_prompt(format!(
+                    "{whose} JIRA email address or display name (eg \"jsmith@yourcompany.com\")"
+                ))
+                .interact()
+                .unwrap();
+            let spinner = ProgressBar::new_spinner().with_message("Searching for user");
+            spinner.enable_steady_tick(std::time::Duration::from_millis(100));
+            let mut users = client.find_users(&query).await?;
+            spinner.finish_and_clear();
+            let user = match users.len() {
+                0 => bail!("No users found matching \"{query}\""),
+                1 => users.remove(0),
+                _ => {
+                    let select = Select::new()
+                        .with_prompt("Multiple users found, select one")
+                        .items(&users)
+                        .default(0)
+                        .interact()
+                        .unwrap();
+                    users.remove(select)
+                }
+            };
+            println!("{}", style("Account ID retrieved").green());
+            Ok(user.account_id)
+        }
+    }
+}
+
+async fn fill(ctx: &RunContext, args: FillArgs) -> Result<()> {
+    let mut config = ctx.config()?;
+    if let Some(filter) = &args.filter {
+        config.use_filter(filter)?;
+    }
+    if let Some(remaining_estimate) = args.remaining_estimate {
+        config.remaining_estimate = remaining_estimate;
+    }
+    let notifier = Notifier::new(ctx.notify || config.notify);
+    let client = connect(ctx, &config, ctx.dry_run).await?;
+    if ctx.week.is_some() && (args.date.is_some() || args.from.is_some()) {
+        bail!("--week and --next cannot be combined with --date or --from");
+    }
+
+    let (first_day, days) = match (args.date, args.from) {
+        (Some(date), _) => (date.week(Weekday::Mon).first_day(), vec![date]),
+        (None, Some(from)) => {
+            let to = args.to.unwrap_or_else(|| ctx.today());
+            if to < from {
+                bail!("--to {to} is before --from {from}");
+            }
+            let days: Vec<NaiveDate> = from
+                .iter_days()
+                .take_while(|day| *day <= to)
+                .filter(|day| config.working_days.contains(&day.weekday()))
+                .collect();
+            if days.is_empty() {
+                bail!("No working days between {from} and {to}");
+            }
+            (from.week(Weekday::Mon).first_day(), days)
+        }
+        (None, None) => {
+            let first_day = ctx.monday();
+            (first_day, week_days(first_day, &config.working_days))
+        }
+    };
+    if !args.skip_static_task_check {
+        check_static_tasks(&client, &mut config, ctx.profile()).await?;
+    }
+    let mut tasks = load_tasks(&client, &config, days[0], ctx.refresh).await?;
+    if args.hide_stale {
+        tasks.retain(|task| !task.is_stale(ctx.today()));
+    }
+
+    let plan = args.plan.as_deref().map(plan::load_plan).transpose()?;
+    if let Some(plan) = &plan {
+        fetch_planned_issues(&client, plan, &mut tasks).await?;
+    }
+
+    if plan.is_none() {
+        fetch_absence_issues(&client, &config, &days, &mut tasks).await?;
+        fetch_template_issues(&client, &config, &mut tasks).await?;
+    }
+    let names = args.tasks.iter().map(String::as_str);
+    fetch_missing_issues(&client, names, &mut tasks).await?;
+    if plan.is_none() {
+        surface_git_issues(&client, config.git.as_ref(), &days, &mut tasks).await?;
+    }
+    let interactive = plan.is_none() && !args.even;
+
+    if !args.skip_permission_check {
+        check_permissions(&client, &config, &tasks).await?;
+    }
+
+    let complete = complete_days(&client, &config, &days).await?;
+    for day in days.iter().filter(|day| complete.contains(day)) {
+        println!(
+            "{} already complete",
+            style(day.format("%A, %-d %B")).bold()
+        );
+    }
+    let work = if let Some(plan) = &plan {
+        let mut work = plan_work(plan, &tasks, first_day, &config)?;
+        work.retain(|entry| days.contains(&entry.day) && !complete.contains(&entry.day));
+        work
+    } else {
+        let holidays = holidays_on(&config, &days).await?;
+        let even_tasks = if args.even {
+            Some(even_tasks(&tasks, &args.tasks)?)
+        } else {
+            notifier.attention("Ready to select this week's tasks");
+            None
+        };
+        let mut work = Vec::new();
+        for day in days.iter().copied() {
+            if complete.contains(&day) {
+                continue;
+            }
+            if let Some(absence) = config.absence_on(day) {
+                work.extend(absence_entries(day, absence, &config, &tasks));
+                continue;
+            }
+            if skip_holiday(day, &holidays, args.random || args.even) {
+                continue;
+            }
+            let today = match &even_tasks {
+                Some(even_tasks) => even_entries(day, even_tasks, config.daily_target(day)),
+                None => select_days_tasks(
+                    day,
+                    &tasks,
+                    &config,
+                    args.random,
+                    args.comments,
+                    args.multi_select,
+                )?,
+            };
+            work.extend(today);
+        }
+        work
+    };
+    let mut work = round_work(work, &config);
+    let issue_attributes = loop {
+        let issue_attributes = prepare_work(
+            ctx,
+            &client,
+            &config,
+            &work,
+            &days,
+            args.reprompt_attributes,
+            interactive,
+        )
+        .await?;
+        if !interactive {
+            break issue_attributes;
+        }
+        notifier.attention("Ready to confirm this week's work");
+        let Some(day) = confirm_work(&work, &days)? else {
+            break issue_attributes;
+        };
+        work.retain(|entry| entry.day != day);
+        work.extend(select_days_tasks(
+            day,
+            &tasks,
+            &config,
+            args.random,
+            args.comments,
+            args.multi_select,
+        )?);
+        work.sort_by_key(|entry| entry.day);
+        work = round_work(work, &config);
+    };
+    notifier.reset();
+    if args.prompt_billable {
+        prompt_billable(&mut work, &config)?;
+    }
+
+    let results = upload_worklogs(&client, &config, &issue_attributes.values, work).await?;
+    print_results(ctx, &client, &results);
+    print_fallbacks(&issue_attributes.fallbacks);
+
+    if args.submit {
+        if args.lint && !lint_week(&client, &config, first_day).await?.is_empty() {
+            bail!("Problems found in timesheet, not submitting")
+        }
+        submit(&client, config.reviewer, &config.worker, first_day).await?;
+    }
+
+    notifier.attention("Finished filling timesheet");
+    Ok(())
+}
+
+///Days that already have at least their target logged, so that re-running `fill` does not log
+///them twice
+async fn complete_days(
+    client: &JtClient,
+    config: &Config,
+    days: &[NaiveDate],
+) -> Result<HashSet<NaiveDate>> {
+    let (Some(first), Some(last)) = (days.first(), days.last()) else {
+        return Ok(HashSet::new());
+    };
+    let spinner = ProgressBar::new_spinner()
+        .with_message(style("Checking existing worklogs").bold().to_string());
+    spinner.enable_steady_tick(std::time::Duration::from_millis(100));
+    let worklogs = client.get_worklogs(&config.worker, *first, *last).await?;
+    spinner.finish_and_clear();
+    let mut logged: HashMap<NaiveDate, TimeDelta> = HashMap::new();
+    for worklog in &worklogs {
+        *logged.entry(worklog.day()?).or_default() +=
+            TimeDelta::seconds(worklog.time_spent_seconds as i64);
+    }
+    Ok(days
+        .iter()
+        .copied()
+        .filter(|day| {
+            let target = config.daily_target(*day);
+            target > TimeDelta::zero() && logged.get(day).is_some_and(|time| *time >= target)
+        })
+        .collect())
+}
+
+///Ask whether to log the work as shown, returning a day to select again if the user wants to edit
+///one, and bailing if they cancel
+fn confirm_work(work: &[Entry], days: &[NaiveDate]) -> Result<Option<NaiveDate>> {
+    let total: TimeDelta = work.iter().map(|entry| entry.time_spent).sum();
+    let choice = Select::new()
+        .with_prompt(format!(
+            "Log {} entries, {} in total?",
+            work.len(),
+            format_duration(total)
+        ))
+        .items(&["Log it", "Edit a day", "Cancel"])
+        .default(0)
+        .interact()
+        .unwrap();
+    match choice {
+        0 => Ok(None),
+        1 => {
+            let names: Vec<String> = days
+                .iter()
+                .map(|day| day.format("%A, %-d %B").to_string())
+                .collect();
+            let day = Select::new()
+                .with_prompt("Day to select again")
+                .items(&names)
+                .default(0)
+                .interact()
+                .unwrap();
+            Ok(Some(days[day]))
+        }
+        _ => bail!("Cancelled, no work logged"),
+    }
+}
+
+///Resolve the attributes to log the work with, then show it and check it can be logged
+async fn prepare_work(
+    ctx: &RunContext,
+    client: &JtClient,
+    config: &Config,
+    work: &[Entry<'_>],
+    days: &[NaiveDate],
+    reprompt_attributes: bool,
+    interactive: bool,
+) -> Result<IssueAttributes> {
+    let mut issue_attributes = resolve_dynamic_attributes(
+        work,
+        &config.dynamic_attributes,
+        interactive,
+        ctx.skip_unresolved,
+    )?;
+    issue_attributes.merge(
+        service_desk::request_attributes(client, &config.service_desk, &queried_issues(work))
+            .await?,
+    );
+    let prompted_attributes = prompt_attributes(
+        work,
+        &config.prompted_attributes,
+        reprompt_attributes,
+        interactive,
+    )?;
+    issue_attributes.merge(prompted_attributes);
+
+    print_preview(work, days, ctx.output);
+    if ctx.output != OutputFormat::Json {
+        print_tag_totals(work, config);
+    }
+    if let Some(max) = config.max_issues_per_day {
+        if !check_issues_per_day(work, max, interactive) {
+            bail!("Not logging work, too many issues on some days");
+        }
+    }
+    Ok(issue_attributes)
+}
+
+///Let the user lay out the week in the full-screen editor, then log the whole grid
+async fn tui(ctx: &RunContext, args: TuiArgs) -> Result<()> {
+    let mut config = ctx.config()?;
+    if let Some(filter) = &args.filter {
+        config.use_filter(filter)?;
+    }
+    let client = connect(ctx, &config, ctx.dry_run).await?;
+    let first_day = ctx.monday();
+    let days = week_days(first_day, &config.working_days);
+    let mut tasks = load_tasks(&client, &config, first_day, ctx.refresh).await?;
+    if args.hide_stale {
+        tasks.retain(|task| !task.is_stale(ctx.today()));
+    }
+    let plan = args.plan.as_deref().map(plan::load_plan).transpose()?;
+    if let Some(plan) = &plan {
+        fetch_planned_issues(&client, plan, &mut tasks).await?;
+    }
+
+    let choices = tasks
+        .iter()
+        .map(|task| tui::Choice {
+            key: task.key(),
+            label: console::strip_ansi_codes(&task.label(ctx.today())).into_owned(),
+        })
+        .collect();
+    let step = TimeDelta::minutes(config.rounding_minutes.unwrap_or(15) as i64);
+    let targets = days.iter().map(|day| config.daily_target(*day)).collect();
+    let mut editor = tui::WeekEditor::new(days.clone(), targets, step, choices);
+    if let Some(plan) = &plan {
+        for entry in plan_work(plan, &tasks, first_day, &config)? {
+            editor.add(&entry.task.key(), entry.day, entry.time_spent);
+        }
+    }
+    let Some(grid) = editor.run()? else {
+        println!("{}", style("Nothing logged").yellow());
+        return Ok(());
+    };
+
+    let work: Vec<Entry> = grid
+        .into_iter()
+        .filter_map(|(key, day, time_spent)| {
+            let task = tasks.iter().find(|task| task.key() == key)?;
+            Some(Entry::new(day, task, time_spent))
+        })
+        .collect();
+    if work.is_empty() {
+        println!("{}", style("Nothing to log").yellow());
+        return Ok(());
+    }
+    let work = round_work(work, &config);
+    let issue_attributes = prepare_work(ctx, &client, &config, &work, &days, false, true).await?;
+    let results = upload_worklogs(&client, &config, &issue_attributes.values, work).await?;
+    print_results(ctx, &client, &results);
+    print_fallbacks(&issue_attributes.fallbacks);
+    Ok(())
+}
+
+///Read a week's time from elsewhere and log it once reviewed
+async fn import(ctx: &RunContext, command: ImportCommands) -> Result<()> {
+    let config = ctx.config()?;
+    let first_day = ctx.monday();
+    let last_day = first_day + TimeDelta::days(6);
+    let sources = &config.import;
+    let mut rows = None;
+    let plan = match command {
+        ImportCommands::Google => {
+            let google = import::configured(&sources.google_calendar, "google_calendar")?;
+            google::import(google, first_day, last_day, ctx.timezone()).await?
+        }
+        ImportCommands::Outlook => {
+            let outlook = import::configured(&sources.outlook, "outlook")?;
+            outlook::import(outlook, first_day, last_day, ctx.timezone()).await?
+        }
+        ImportCommands::Ics { file } => {
+            let ics = import::configured(&sources.ics, "ics")?;
+            ics::import(ics, &file, first_day, last_day, ctx.timezone())?
+        }
+        ImportCommands::Toggl => {
+            let toggl = import::configured(&sources.toggl, "toggl")?;
+            toggl::import(toggl, first_day, last_day, ctx.timezone()).await?
+        }
+        ImportCommands::Harvest => {
+            let harvest = import::configured(&sources.harvest, "harvest")?;
+            harvest::import(harvest, first_day, last_day).await?
+        }
+        ImportCommands::Clockify => {
+            let clockify = import::configured(&sources.clockify, "clockify")?;
+            clockify::import(clockify, first_day, last_day, ctx.timezone()).await?
+        }
+        ImportCommands::Csv { file } => {
+            let read = csv_import::read_rows(&file)?;
+            csv_import::rows_plan(rows.insert(read))
+        }
+    };
+    let client = connect(ctx, &config, ctx.dry_run).await?;
+    if let Some(rows) = &rows {
+        check_row_tasks(&client, &config, rows).await?;
+    }
+    log_imported(ctx, &client, &config, &plan, first_day).await
+}
+
+///Check every row is for a static task or an issue that exists, listing the lines of any that are
+///not
+async fn check_row_tasks(
+    client: &JtClient,
+    config: &Config,
+    rows: &[csv_import::Row],
+) -> Result<()> {
+    let static_tasks: Vec<Task> = config
+        .static_tasks
+        .iter()
+        .cloned()
+        .map(Task::Static)
+        .collect();
+    let mut keys: Vec<&str> = rows
+        .iter()
+        .map(|row| row.task.as_str())
+        .filter(|key| tasks_named(&static_tasks, key).is_empty())
+        .collect();
+    keys.sort();
+    keys.dedup();
+    let found: Vec<bool> = stream::iter(&keys)
+        .map(|key| async move { Ok::<_, anyhow::Error>(client.find_issue(key).await?.is_some()) })
+        .buffered(client.concurrency())
+        .try_collect()
+        .await?;
+    let unknown: Vec<&str> = keys
+        .iter()
+        .zip(found)
+        .filter(|(_, found)| !found)
+        .map(|(key, _)| *key)
+        .collect();
+    let problems: Vec<String> = rows
+        .iter()
+        .filter(|row| unknown.contains(&row.task.as_str()))
+        .map(|row| format!("line {}: no issue or static task {}", row.line, row.task))
+        .collect();
+    if !problems.is_empty() {
+        bail!("Unknown tasks:\n  {}", problems.join("\n  "));
+    }
+    Ok(())
+}
+
+///Log the work in an imported plan, showing it and asking for confirmation first
+async fn log_imported(
+    ctx: &RunContext,
+    client: &JtClient,
+    config: &Config,
+    plan: &plan::Plan,
+    first_day: NaiveDate,
+) -> Result<()> {
+    if plan.days.is_empty() {
+        println!("{}", style("Nothing to import").yellow());
+        return Ok(());
+    }
+    let mut tasks: Vec<Task> = config
+        .static_tasks
+        .iter()
+        .cloned()
+        .map(Task::Static)
+        .collect();
+    fetch_planned_issues(client, plan, &mut tasks).await?;
+    let work = round_work(plan_work(plan, &tasks, first_day, config)?, config);
+    let mut days: Vec<NaiveDate> = work.iter().map(|entry| entry.day).collect();
+    days.dedup();
+    let issue_attributes = prepare_work(ctx, client, config, &work, &days, false, true).await?;
+    let confirmed = Confirm::new()
+        .with_prompt("Log this work?")
+        .default(true)
+        .interact()
+        .unwrap();
+    if !confirmed {
+        bail!("Cancelled, no work logged");
+    }
+    let results = upload_worklogs(client, config, &issue_attributes.values, work).await?;
+    print_results(ctx, client, &results);
+    print_fallbacks(&issue_attributes.fallbacks);
+    Ok(())
+}
+
+///Walk through the interactive selection for a week, writing the result to a plan file
+async fn plan(ctx: &RunContext, args: PlanArgs) -> Result<()> {
+    let file = match (args.command, &args.file) {
+        (Some(PlanCommands::Generate(generate_args)), _) => {
+            return generate_plan(ctx, generate_args).await
+        }
+        (None, Some(file)) => file,
+        (None, None) => unreachable!("clap requires a plan file"),
+    };
+    let mut config = ctx.config()?;
+    if let Some(filter) = &args.filter {
+        config.use_filter(filter)?;
+    }
+    let client = connect(ctx, &config, true).await?;
+
+    let first_day = ctx.monday();
+    let mut tasks = load_tasks(&client, &config, first_day, ctx.refresh).await?;
+    if args.hide_stale {
+        tasks.retain(|task| !task.is_stale(ctx.today()));
+    }
+
+    let starting_point = args.from.as_deref().map(plan::load_plan).transpose()?;
+    if let Some(starting_point) = &starting_point {
+        fetch_planned_issues(&client, starting_point, &mut tasks).await?;
+    }
+    fetch_template_issues(&client, &config, &mut tasks).await?;
+
+    let mut previous = match &starting_point {
+        Some(starting_point) => plan_work(starting_point, &tasks, first_day, &config)?,
+        None => Vec::new(),
+    };
+
+    let holidays = holidays_on(&config, &week_days(first_day, &config.working_days)).await?;
+    let mut plan = plan::Plan::default();
+    for day in week_days(first_day, &config.working_days) {
+        if config.absence_on(day).is_some() || skip_holiday(day, &holidays, false) {
+            continue;
+        }
+        let (planned, rest): (Vec<Entry>, Vec<Entry>) =
+            previous.into_iter().partition(|entry| entry.day == day);
+        previous = rest;
+        let keep = !planned.is_empty() && {
+            println!("{}", style(day.format("%A, %-d %B")).bold());
+            for entry in &planned {
+                println!("  {} {}", format_duration(entry.time_spent), entry.task);
+            }
+            Confirm::new()
+                .with_prompt("Keep this day as planned?")
+                .default(true)
+                .interact()
+                .unwrap()
+        };
+        let today = if keep {
+            planned
+        } else {
+            select_days_tasks(
+                day,
+                &tasks,
+                &config,
+                false,
+                args.comments,
+                args.multi_select,
+            )?
+        };
+        plan.days.push(plan::PlanDay {
+            day: plan::DaySpec::Weekday(day.weekday()),
+            entries: today.into_iter().map(plan_entry).collect(),
+        });
+    }
+
+    plan::write_plan(&plan, file)?;
+    println!(
+        "{}",
+        style(format!("Plan written to {}", file.display()))
+            .green()
+            .bold()
+    );
+    Ok(())
+}
+
+///An entry as written to a plan file
+fn plan_entry(entry: Entry) -> plan::PlanEntry {
+    plan::PlanEntry {
+        task: entry.task.key(),
+        minutes: None,
+        duration: Some(format_duration(entry.time_spent)),
+        percent: None,
+        comment: entry.comment,
+        tags: entry.tags,
+    }
+}
+
+///Write a plan giving each issue a JQL search finds the same time, dealing the issues out across
+///the days in turn
+async fn generate_plan(ctx: &RunContext, args: GenerateArgs) -> Result<()> {
+    let config = ctx.config()?;
+    let client = connect(ctx, &config, true).await?;
+    let done_tasks_from = ctx.monday() - TimeDelta::days(1);
+    let issues = get_tasks(&client, done_tasks_from, Some(&args.jql), ctx.refresh).await?;
+    if issues.is_empty() {
+        bail!("No issues found by \"{}\"", args.jql);
+    }
+
+    let mut plan = plan::Plan {
+        days: args
+            .days
+            .0
+            .iter()
+            .map(|weekday| plan::PlanDay {
+                day: plan::DaySpec::Weekday(*weekday),
+                entries: Vec::new(),
+            })
+            .collect(),
+    };
+    let day_count = plan.days.len();
+    for (i, issue) in issues.into_iter().enumerate() {
+        plan.days[i % day_count].entries.push(plan::PlanEntry {
+            task: issue.key,
+            minutes: Some(args.minutes_per_issue),
+            duration: None,
+            percent: None,
+            comment: None,
+            tags: Vec::new(),
+        });
+    }
+    plan.days.retain(|day| !day.entries.is_empty());
+
+    plan::write_plan(&plan, &args.file)?;
+    println!(
+        "{}",
+        style(format!("Plan written to {}", args.file.display()))
+            .green()
+            .bold()
+    );
+    Ok(())
+}
+
+///Log time against a single issue or static task outside of the weekly flow
+async fn log(ctx: &RunContext, args: LogArgs) -> Result<()> {
+    let mut config = ctx.config()?;
+    if let Some(remaining_estimate) = args.remaining_estimate {
+        config.remaining_estimate = remaining_estimate;
+    }
+    let (key, duration) = match (args.key, args.duration) {
+        (Some(key), Some(duration)) => (key, duration),
+        (Some(duration), None) => match git::branch_issue() {
+            Some((branch, key)) => {
+                println!("Logging against {key} from branch {branch}");
+                (key, duration)
+            }
+            None => bail!("No task given and the current git branch does not name an issue"),
+        },
+        _ => bail!("Expected the time spent"),
+    };
+    let client = connect(ctx, &config, ctx.dry_run).await?;
+
+    let task = match config.static_tasks.iter().find(|t| t.key == key) {
+        Some(task) => Task::Static(task.clone()),
+        None => Task::FromQuery(
+            client
+                .get_issue(&key)
+                .await
+                .with_context(|| format!("Unable to retrieve task {key}"))?,
+        ),
+    };
+    let day = args.date.unwrap_or_else(|| ctx.today());
+    let time_spent = config.round(parse_duration(
+        &duration,
+        config.daily_target(day),
+        config.number_format(),
+    )?);
+    if time_spent <= TimeDelta::zero() && config.rounding_minutes.is_some() {
+        bail!("{duration} rounds down to nothing");
+    }
+    let mut entry = Entry::new(day, &task, time_spent);
+    if let Some(comment) = &args.comment {
+        (entry.comment, entry.tags) = split_tags(comment);
+    }
+    entry
+        .tags
+        .extend(args.tags.iter().map(|tag| tag.to_lowercase()));
+    entry.start_time = args.start;
+    if let Some(billable) = &args.billable {
+        let billable = parse_duration(billable, config.daily_target(day), config.number_format())?;
+        if billable > time_spent {
+            bail!(
+                "Billable time cannot exceed the {} spent",
+                format_duration(time_spent)
+            );
+        }
+        entry.billable = Some(billable);
+    }
+    let work = vec![entry];
+
+    let mut issue_attributes =
+        resolve_dynamic_attributes(&work, &config.dynamic_attributes, true, ctx.skip_unresolved)?;
+    issue_attributes.merge(
+        service_desk::request_attributes(&client, &config.service_desk, &queried_issues(&work))
+            .await?,
+    );
+    issue_attributes.merge(prompt_attributes(
+        &work,
+        &config.prompted_attributes,
+        false,
+        true,
+    )?);
+    let results = upload_worklogs(&client, &config, &issue_attributes.values, work).await?;
+    print_results(ctx, &client, &results);
+    print_fallbacks(&issue_attributes.fallbacks);
+    Ok(())
+}
+
+///Re-create one week's worklogs, with the same tasks, durations, start times, comments and
+///attributes, in another week once the user has reviewed them, leaving out any the other week
+///already has
+async fn copy_week(ctx: &RunContext, args: CopyWeekArgs) -> Result<()> {
+    let config = ctx.config()?;
+    let client = connect(ctx, &config, ctx.dry_run).await?;
+    let source = args.from.monday(ctx.today());
+    let target = args.to.unwrap_or(ctx.week_or_current()).monday(ctx.today());
+    if source == target {
+        bail!("Cannot copy a week into itself");
+    }
+
+    let spinner =
+        ProgressBar::new_spinner().with_message(style("Retrieving worklogs").bold().to_string());
+    spinner.enable_steady_tick(std::time::Duration::from_millis(100));
+    let (worklogs, existing) = try_join(
+        client.get_worklogs(&config.worker, source, source + TimeDelta::days(6)),
+        client.get_worklogs(&config.worker, target, target + TimeDelta::days(6)),
+    )
+    .await?;
+    spinner.finish_and_clear();
+    let existing: HashSet<(NaiveDate, String, i64)> = existing
+        .into_iter()
+        .filter_map(|worklog| {
+            let day = worklog.day().ok()?;
+            Some((day, worklog.issue.key, worklog.time_spent_seconds as i64))
+        })
+        .collect();
+
+    let copied: Vec<(NaiveDate, Task, Worklog)> = worklogs
+        .into_iter()
+        .filter_map(|worklog| {
+            let Ok(day) = worklog.day() else {
+                log::warn!(
+                    "Unable to parse start of worklog {}, not copying it",
+                    worklog.tempo_worklog_id
+                );
+                return None;
+            };
+            let id = worklog.tempo_worklog_id;
+            let attributes = worklog
+                .attributes
+                .iter()
+                .filter_map(|(key, attr)| {
+                    let (name, work_attribute_id) = match (&attr.name, attr.work_attribute_id) {
+                        (Some(name), Some(work_attribute_id)) => (name.clone(), work_attribute_id),
+                        _ => match config.configured_attribute(key) {
+                            Some((name, work_attribute_id)) => (name.to_owned(), work_attribute_id),
+                            None => {
+                                eprintln!(
+                                    "{}",
+                                    style(format!(
+                                        "Attribute {key} of worklog {id} is not configured, copying the worklog without it"
+                                    ))
+                                    .yellow()
+                                );
+                                return None;
+                            }
+                        },
+                    };
+                    Some(WorkAttribute {
+                        key: key.clone(),
+                        name,
+                        work_attribute_id,
+                        value: attr.value.clone(),
+                    })
+                })
+                .collect();
+            let task = Task::Static(StaticTask {
+                key: worklog.issue.key.clone(),
+                description: String::new(),
+                attributes,
+                comment: None,
+                percent: None,
+                default_time_spent_minutes: None,
+                visibility: None,
+                billable: None,
+                disabled: false,
+                group: None,
+            });
+            Some((target + (day - source), task, worklog))
+        })
+        .collect();
+    if copied.is_empty() {
+        println!("No worklogs found in the week of {source}");
+        return Ok(());
+    }
+    let (duplicates, copies): (Vec<Entry>, Vec<Entry>) = copied
+        .iter()
+        .map(|(day, task, worklog)| Entry {
+            day: *day,
+            task,
+            time_spent: TimeDelta::seconds(worklog.time_spent_seconds as i64),
+            comment: worklog.comment.clone(),
+            tags: Vec::new(),
+            billable: worklog
+                .billable_seconds
+                .map(|seconds| TimeDelta::seconds(seconds as i64)),
+            start_time: worklog.start_time(),
+        })
+        .partition(|copy| {
+            existing.contains(&(copy.day, copy.task.key(), copy.time_spent.num_seconds()))
+        });
+    for duplicate in &duplicates {
+        eprintln!(
+            "{}",
+            style(format!(
+                "Skipping {} on {}, {} is already logged against it",
+                duplicate.task.key(),
+                duplicate.day,
+                format_duration(duplicate.time_spent)
+            ))
+            .yellow()
+        );
+    }
+    if copies.is_empty() {
+        println!("Every worklog is already in the week of {target}");
+        return Ok(());
+    }
+
+    let mut days = week_days(target, &config.working_days);
+    days.extend(copies.iter().map(|copy| copy.day));
+    days.sort();
+    days.dedup();
+    let entries = copies
+        .iter()
+        .map(|copy| (copy.day, copy.task.key(), copy.time_spent));
+    println!(
+        "{}",
+        report::render(&WeekGrid::new(days, entries), ctx.output)
+    );
+    if !ctx.dry_run {
+        let proceed = Confirm::new()
+            .with_prompt(format!(
+                "Copy {} worklogs into the week of {target}?",
+                copies.len()
+            ))
+            .default(true)
+            .interact()
+            .unwrap();
+        if !proceed {
+            return Ok(());
+        }
+    }
+
+    let results = upload_worklogs(&client, &config, &HashMap::new(), copies).await?;
+    print_results(ctx, &client, &results);
+    Ok(())
+}
+
+///Position of each of the instance's priorities in its order, empty if they cannot be retrieved
+///so that tasks are left unordered by priority
+async fn priority_ranks(client: &JtClient) -> HashMap<String, usize> {
+    let mut cached: HashMap<String, CachedPriorities> =
+        state::load_cache(PRIORITY_CACHE).unwrap_or_default();
+    let now = Utc::now();
+    cached.retain(|_, priorities| now - priorities.fetched < PRIORITY_CACHE_TTL);
+    let key = client.base().to_string();
+    let priorities = match cached.get(&key) {
+        Some(cached) => cached.priorities.clone(),
+        None => match client.get_priorities().await {
+            Ok(priorities) => {
+                cached.insert(
+                    key,
+                    CachedPriorities {
+                        fetched: now,
+                        priorities: priorities.clone(),
+                    },
+                );
+                if let Err(e) = state::write_cache(PRIORITY_CACHE, &cached) {
+                    log::warn!("Unable to cache priorities: {e}");
+                }
+                priorities
+            }
+            Err(e) => {
+                log::warn!("Unable to retrieve priorities, not ordering tasks by them: {e}");
+                Vec::new()
+            }
+        },
+    };
+    priorities
+        .into_iter()
+        .enumerate()
+        .map(|(rank, priority)| (priority.id, rank))
+        .collect()
+}
+
+///Urgency of an issue's priority, lower being more urgent, if the instance's order is known
+fn priority_rank(issue: &Issue) -> Option<usize> {
+    PRIORITY_RANKS.get()?.get(issue.priority_id()?).copied()
+}
+
+///Assigned issues for the week along with any static tasks
+async fn load_tasks(
+    client: &JtClient,
+    config: &Config,
+    first_day: NaiveDate,
+    refresh: bool,
+) -> Result<Vec<Task>> {
+    let done_tasks_from = first_day - TimeDelta::days(1);
+    let mut issues = get_tasks(client, done_tasks_from, config.jql.as_deref(), refresh).await?;
+    if PRIORITY_RANKS.get().is_none() {
+        let _ = PRIORITY_RANKS.set(priority_ranks(client).await);
+    }
+    issues.sort_by_key(|issue| {
+        let due = issue.due_date();
+        let rank = priority_rank(issue);
+        (due.is_none(), due, rank.is_none(), rank)
+    });
+    let mut tasks: Vec<Task> = issues.into_iter().map(Task::FromQuery).collect();
+    for instance in &config.instances {
+        match remote_tasks(instance, config, client.stats(), done_tasks_from, refresh).await {
+            Ok(remote) => tasks.extend(remote),
+            Err(e) => eprintln!(
+                "{}",
+                style(format!(
+                    "Unable to retrieve tasks from {}, skipping: {e}",
+                    instance.name
+                ))
+                .yellow()
+            ),
+        }
+    }
+    tasks.extend(config.static_tasks.iter().cloned().map(Task::Static));
+    Ok(tasks)
+}
+
+///Issues found on another instance, to be logged against its task on this one
+async fn remote_tasks(
+    instance: &Instance,
+    config: &Config,
+    stats: &ApiStats,
+    done_tasks_from: NaiveDate,
+    refresh: bool,
+) -> Result<Vec<Task>> {
+    let credentials = auth::instance_token(&instance.name, instance.token_command.as_deref())?;
+    let client = JtClient::new(
+        &credentials,
+        instance.base_url()?,
+        true,
+        UserIdentifier::Key,
+        &config.http_options(),
+    )?
+    .with_stats(stats.clone());
+    let issues = get_tasks(&client, done_tasks_from, instance.jql.as_deref(), refresh).await?;
+    Ok(issues
+        .into_iter()
+        .map(|issue| {
+            Task::Remote(RemoteIssue {
+                instance: instance.name.clone(),
+                issue,
+                task: instance.task.clone(),
+            })
+        })
+        .collect())
+}
+
+///List the static tasks and the issues found by the task search, as offered by `fill`
+async fn issues(ctx: &RunContext, args: IssuesArgs) -> Result<()> {
+    let mut config = ctx.config()?;
+    if let Some(filter) = &args.filter {
+        config.use_filter(filter)?;
+    }
+    let client = connect(ctx, &config, true).await?;
+    let mut tasks = load_tasks(&client, &config, ctx.monday(), ctx.refresh).await?;
+    if args.hide_stale {
+        tasks.retain(|task| !task.is_stale(ctx.today()));
+    }
+    if ctx.output != OutputFormat::Json {
+        let today = ctx.today();
+        for task in &tasks {
+            println!("{}", task.label(today));
+        }
+        return Ok(());
+    }
+    let tasks: Vec<serde_json::Value> = tasks
+        .iter()
+        .map(|task| match task {
+            Task::Static(s) => serde_json::json!({
+                "key": s.key,
+                "summary": s.description,
+                "static": true,
+                "group": s.group,
+            }),
+            Task::FromQuery(issue) => serde_json::json!({
+                "key": issue.key,
+                "summary": issue.summary(),
+                "static": false,
+                "due": issue.due_date(),
+                "priority": issue.priority(),
+                "updated": issue.updated(),
+                "stale": task.is_stale(ctx.today()),
+            }),
+            Task::Remote(remote) => serde_json::json!({
+                "key": remote.issue.key,
+                "summary": remote.issue.summary(),
+                "static": false,
+                "instance": remote.instance,
+                "logged_against": remote.task,
+                "updated": remote.issue.updated(),
+                "stale": task.is_stale(ctx.today()),
+            }),
+        })
+        .collect();
+    println!("{}", serde_json::to_string_pretty(&tasks)?);
+    Ok(())
+}
+
+///Show the details of an issue
+async fn view(ctx: &RunContext, key: &str) -> Result<()> {
+    let config = ctx.config()?;
+    let client = connect(ctx, &config, true).await?;
+    let issue = client
+        .find_issue(key)
+        .await?
+        .with_context(|| format!("No issue {key} found"))?;
+    let url = client.issue_url(&issue.key, None);
+    let status = issue.named_field("status", "name");
+    let assignee = issue.named_field("assignee", "displayName");
+    let priority = issue.priority();
+    if ctx.output == OutputFormat::Json {
+        let view = serde_json::json!({
+            "key": issue.key,
+            "summary": issue.summary(),
+            "status": status,
+            "assignee": assignee,
+            "priority": priority,
+            "due": issue.due_date(),
+            "updated": issue.updated(),
+            "url": url.as_str(),
+        });
+        println!("{}", serde_json::to_string_pretty(&view)?);
+        return Ok(());
+    }
+
+    println!(
+        "{} {}",
+        style(&issue.key).bold(),
+        issue.summary().unwrap_or_default()
+    );
+    let due = issue
+        .due_date()
+        .map(|due| match urgency_badge(&issue, ctx.today()) {
+            Some(badge) => format!("{due} {badge}"),
+            None => due.to_string(),
+        });
+    let updated = issue
+        .updated()
+        .map(|updated| match stale_badge(&issue, ctx.today()) {
+            Some(badge) => format!("{updated} {badge}"),
+            None => updated.to_string(),
+        });
+    let details = [
+        ("Status", status.map(str::to_owned)),
+        ("Assignee", assignee.map(str::to_owned)),
+        ("Priority", priority.map(str::to_owned)),
+        ("Due", due),
+        ("Updated", updated),
+    ];
+    for (label, value) in details {
+        if let Some(value) = value {
+            println!("  {:<10}{value}", style(label).dim());
+        }
+    }
+    println!("  {}", style(url).underlined());
+    Ok(())
+}
+
+///Show where the week's timesheet is in the approval process, followed by receipts of past
+///submissions
+async fn status(ctx: &RunContext) -> Result<()> {
+    let config = ctx.config()?;
+    let client = connect(ctx, &config, true).await?;
+    let monday = ctx.monday();
+    let approval = client.get_week_approval(&config.worker, monday).await?;
+    let reviewer = approval
+        .reviewer
+        .as_ref()
+        .and_then(|reviewer| reviewer.display_name.as_deref());
+    let receipts = receipt::receipts_for(&config.worker);
+    if ctx.output == OutputFormat::Json {
+        let status = serde_json::json!({
+            "week": monday,
+            "state": approval.state(),
+            "reviewer": reviewer,
+            "comment": approval.comment(),
+            "receipts": receipts,
+        });
+        println!("{}", serde_json::to_string_pretty(&status)?);
+        return Ok(());
+    }
+
+    let state = approval.state();
+    let state = match state {
+        ApprovalState::Open => style(state.to_string()).yellow(),
+        ApprovalState::Submitted => style(state.to_string()).cyan(),
+        ApprovalState::Approved => style(state.to_string()).green(),
+        ApprovalState::Rejected => style(state.to_string()).red(),
+    };
+    print!(
+        "Timesheet for the week of {} is {}",
+        monday.format("%-d %B %Y"),
+        state.bold()
+    );
+    match reviewer {
+        Some(reviewer) => println!(", reviewer {reviewer}"),
+        None => println!(),
+    }
+    if let Some(comment) = approval.comment() {
+        println!("  {}", style(comment).italic());
+    }
+    println!();
+
+    if receipts.is_empty() {
+        println!("No timesheet submissions recorded");
+        return Ok(());
+    }
+    println!(
+        "{}",
+        style(format!(
+            "{:<12} {:<18} {:<24} {}",
+            "Week", "Submitted", "Reviewer", "Status"
+        ))
+        .bold()
+    );
+    for receipt in receipts {
+        let status = if receipt.status.eq_ignore_ascii_case("open") {
+            style(format!("{} (not submitted?)", receipt.status)).yellow()
+        } else {
+            style(receipt.status).green()
+        };
+        println!(
+            "{:<12} {:<18} {:<24} {}",
+            receipt.week,
+            receipt.submitted_at.format("%Y-%m-%d %H:%M"),
+            receipt.reviewer,
+            status
+        );
+    }
+    Ok(())
+}
+
+///Approve or reject a team member's submitted timesheet for the week
+async fn review(ctx: &RunContext, args: ReviewArgs, review: Review) -> Result<()> {
+    let config = ctx.config()?;
+    let worker = match args.worker {
+        Some(worker) => worker,
+        None if config.team.is_empty() => {
+            bail!("No worker given, name one or configure your team")
+        }
+        None => {
+            let selected = Select::new()
+                .with_prompt("Whose timesheet?")
+                .items(&config.team)
+                .default(0)
+                .interact()
+                .unwrap();
+            config.team[selected].clone()
+        }
+    };
+    let client = connect(ctx, &config, ctx.dry_run).await?;
+    let monday = ctx.monday();
+    let week = monday.format("%-d %B %Y");
+
+    let current = client.get_week_approval(&worker, monday).await?;
+    if current.state() != ApprovalState::Submitted {
+        bail!(
+            "{worker}'s timesheet for the week of {week} is {}, not waiting for approval",
+            current.state()
+        );
+    }
+    let comment = match (args.comment, review) {
+        (Some(comment), _) => comment,
+        (None, Review::Reject) => Input::new()
+            .with_prompt("Reason for rejecting")
+            .allow_empty(true)
+            .interact_text()
+            .unwrap(),
+        (None, Review::Approve) => String::new(),
+    };
+
+    let spinner =
+        ProgressBar::new_spinner().with_message(style("Reviewing timesheet").bold().to_string());
+    spinner.enable_steady_tick(std::time::Duration::from_millis(100));
+    let approval = client
+        .review_timesheet(&worker, monday, review, comment)
+        .await?;
+    spinner.finish_and_clear();
+    let decision = match review {
+        Review::Approve => "approved",
+        Review::Reject => "rejected",
+    };
+    let message = match approval {
+        Some(approval) => format!(
+            "{worker}'s timesheet for the week of {week} {decision}, now {}",
+            approval.state()
+        ),
+        None => format!("{worker}'s timesheet for the week of {week} {decision}"),
+    };
+    println!("{}", style(message).green().bold());
+    Ok(())
+}
+
+///Show the worklogs already in Tempo for the week, with totals per issue and per day
+async fn report(ctx: &RunContext, args: ReportArgs) -> Result<()> {
+    let config = ctx.config()?;
+    let client = connect(ctx, &config, true).await?;
+    if let Some(month) = args.month {
+        if ctx.week.is_some() {
+            bail!("--month cannot be combined with --week or --next");
+        }
+        return report_month(ctx, &client, &config, month, args.csv.as_deref()).await;
+    }
+    let first_day = ctx.monday();
+    let spinner =
+        ProgressBar::new_spinner().with_message(style("Retrieving worklogs").bold().to_string());
+    spinner.enable_steady_tick(std::time::Duration::from_millis(100));
+    let worklogs = client
+        .get_worklogs(&config.worker, first_day, first_day + TimeDelta::days(6))
+        .await?;
+    spinner.finish_and_clear();
+    let entries = worklogs
+        .into_iter()
+        .map(|worklog| {
+            let time_spent = TimeDelta::seconds(worklog.time_spent_seconds as i64);
+            Ok((worklog.day()?, worklog.issue.key, time_spent))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    if entries.is_empty() && ctx.output != OutputFormat::Json {
+        println!("No worklogs found in the week of {first_day}");
+        return Ok(());
+    }
+    let mut days = week_days(first_day, &config.working_days);
+    days.extend(entries.iter().map(|(day, _, _)| *day));
+    days.sort();
+    days.dedup();
+    let grid = WeekGrid::new(days, entries);
+    println!("{}", report::render(&grid, ctx.output));
+    Ok(())
+}
+
+///Download worklogs between two days and write them to a file
+async fn export(ctx: &RunContext, args: ExportArgs) -> Result<()> {
+    let to = args.to.unwrap_or_else(|| ctx.today());
+    if to < args.from {
+        bail!("--to {to} is before --from {}", args.from);
+    }
+    let config = ctx.config()?;
+    let client = connect(ctx, &config, true).await?;
+    let spinner =
+        ProgressBar::new_spinner().with_message(style("Retrieving worklogs").bold().to_string());
+    spinner.enable_steady_tick(std::time::Duration::from_millis(100));
+    let worklogs = client.get_worklogs(&config.worker, args.from, to).await?;
+    spinner.finish_and_clear();
+    let file = args.file.unwrap_or_else(|| {
+        PathBuf::from(format!(
+            "worklogs-{}-{to}.{}",
+            args.from,
+            args.format.extension()
+        ))
+    });
+    export::write_worklogs(&worklogs, args.format, &file)?;
+    if file != Path::new("-") {
+        println!(
+            "{}",
+            style(format!(
+                "{} worklogs written to {}",
+                worklogs.len(),
+                file.display()
+            ))
+            .green()
+            .bold()
+        );
+    }
+    Ok(())
+}
+
+///Show the time logged against each issue over a month, totalled by project, optionally writing
+///it to a CSV file
+async fn report_month(
+    ctx: &RunContext,
+    client: &JtClient,
+    config: &Config,
+    month: MonthSpec,
+    csv: Option<&Path>,
+) -> Result<()> {
+    let first_day = month.first_day(ctx.today());
+    let spinner =
+        ProgressBar::new_spinner().with_message(style("Retrieving worklogs").bold().to_string());
+    spinner.enable_steady_tick(std::time::Duration::from_millis(100));
+    let worklogs = client
+        .get_worklogs(&config.worker, first_day, month.last_day(ctx.today()))
+        .await?;
+    spinner.finish_and_clear();
+    let entries = worklogs.into_iter().map(|worklog| {
+        let time_spent = TimeDelta::seconds(worklog.time_spent_seconds as i64);
+        (worklog.issue.key, time_spent)
+    });
+    let summary = report::MonthSummary::new(first_day, entries);
+    println!("{}", report::render_month(&summary, ctx.output));
+    if let Some(csv) = csv {
+        summary.write_csv(csv)?;
+        if ctx.output != OutputFormat::Json {
+            println!(
+                "{}",
+                style(format!("Written to {}", csv.display()))
+                    .green()
+                    .bold()
+            );
+        }
+    }
+    Ok(())
+}
+
+///Compare each project's share of the week, logged or planned, with its commitment, optionally
+///rescaling the plan to match
+async fn balance(ctx: &RunContext, args: BalanceArgs) -> Result<()> {
+    let config = ctx.config()?;
+    if config.commitments.is_empty() {
+        bail!("No project commitments configured to balance against");
+    }
+    let client = connect(ctx, &config, true).await?;
+    let first_day = ctx.monday();
+
+    let Some(file) = &args.plan else {
+        let worklogs = client
+            .get_worklogs(&config.worker, first_day, first_day + TimeDelta::days(6))
+            .await?;
+        let mut totals: HashMap<String, TimeDelta> = HashMap::new();
+        for worklog in &worklogs {
+            *totals
+                .entry(project_of(&worklog.issue.key).to_owned())
+                .or_default() += TimeDelta::seconds(worklog.time_spent_seconds as i64);
+        }
+        print_balance(&balance::drift(&totals, &config.commitments));
+        return Ok(());
+    };
+
+    let mut plan = plan::load_plan(file)?;
+    let mut tasks: Vec<Task> = config
+        .static_tasks
+        .iter()
+        .cloned()
+        .map(Task::Static)
+        .collect();
+    fetch_planned_issues(&client, &plan, &mut tasks).await?;
+    let work = plan_work(&plan, &tasks, first_day, &config)?;
+    let mut totals: HashMap<String, TimeDelta> = HashMap::new();
+    for entry in &work {
+        *totals.entry(entry.task.project()).or_default() += entry.time_spent;
+    }
+    let drifts = balance::drift(&totals, &config.commitments);
+    print_balance(&drifts);
+    if !args.auto {
+        return Ok(());
+    }
+
+    rebalance_plan(&mut plan, &tasks, first_day, &config)?;
+    plan::write_plan(&plan, file)?;
+    println!(
+        "{}",
+        style(format!(
+            "Plan {} rescaled to match commitments",
+            file.display()
+        ))
+        .green()
+        .bold()
+    );
+    Ok(())
+}
+
+///Rewrite the plan so that each project gets its committed share of the week without changing how
+///much is planned each day. Entries keep their form, a share of the day staying a share of the day,
+///and those filling the rest of a day are rescaled together so that they still do
+fn rebalance_plan(
+    plan: &mut plan::Plan,
+    tasks: &[Task],
+    first_day: NaiveDate,
+    config: &Config,
+) -> Result<()> {
+    let number_format = config.number_format();
+    let mut planned = Vec::new();
+    for planned_day in &plan.days {
+        let day = planned_day.day.resolve(first_day);
+        let target = config.daily_target(day);
+        let times = plan::day_time_spent(
+            &planned_day.entries,
+            target,
+            TimeDelta::zero(),
+            number_format,
+        )?;
+        let mut rest = balance::Planned {
+            day,
+            projects: Vec::new(),
+            time: TimeDelta::zero(),
+        };
+        let mut rest_count = 0;
+        for (entry, time_spent) in planned_day.entries.iter().zip(times) {
+            let named = tasks_named(tasks, &entry.task);
+            let projects = named
+                .iter()
+                .map(|task| (task.project(), 1.0 / named.len() as f64));
+            if entry.time_spent(target, number_format)?.is_some() {
+                planned.push(balance::Planned {
+                    day,
+                    projects: projects.collect(),
+                    time: time_spent,
+                });
+            } else {
+                rest.projects.extend(projects);
+                rest.time += time_spent;
+                rest_count += 1;
+            }
+        }
+        if rest_count > 0 {
+            for (_, share) in &mut rest.projects {
+                *share /= rest_count as f64;
+            }
+            planned.push(rest);
+        }
+    }
+
+    let mut times = balance::rebalance(&planned, &config.commitments)?.into_iter();
+    for planned_day in &mut plan.days {
+        let target = config.daily_target(planned_day.day.resolve(first_day));
+        let mut entries = Vec::new();
+        let mut fills_rest = false;
+        for mut entry in std::mem::take(&mut planned_day.entries) {
+            if entry.time_spent(target, number_format)?.is_none() {
+                fills_rest = true;
+                entries.push(entry);
+                continue;
+            }
+            let time_spent = times.next().unwrap_or_default();
+            if time_spent <= TimeDelta::zero() {
+                continue;
+            }
+            if entry.minutes.is_some() {
+                entry.minutes = Some(time_spent.num_minutes() as u64);
+            } else if entry.duration.is_some() {
+                entry.duration = Some(format_duration(time_spent));
+            } else if target > TimeDelta::zero() {
+                let percent = time_spent.num_seconds() as f64 / target.num_seconds() as f64;
+                entry.percent = Some((percent * 10000.0).round() / 100.0);
+            }
+            entries.push(entry);
+        }
+        if fills_rest {
+            //The entries filling the rest of the day were rescaled together, after the others
+            times.next();
+        }
+        planned_day.entries = entries;
+    }
+    Ok(())
+}
+
+fn print_balance(drifts: &[balance::Drift]) {
+    println!(
+        "{}",
+        style(format!(
+            "{:<12} {:>9} {:>9} {:>9}  {}",
+            "Project", "Committed", "Target", "Actual", "Suggestion"
+        ))
+        .bold()
+    );
+    for drift in drifts {
+        if drift.on_target() {
+            println!("{}", style(drift).green());
+        } else {
+            println!("{}", style(drift).yellow());
+        }
+    }
+}
+
+async fn lint(ctx: &RunContext) -> Result<()> {
+    let config = ctx.config()?;
+    let client = connect(ctx, &config, false).await?;
+    let problems = lint_week(&client, &config, ctx.monday()).await?;
+    if problems.is_empty() {
+        Ok(())
+    } else {
+        bail!("{} problem(s) found", problems.len())
+    }
+}
+
+async fn lint_week(
+    client: &JtClient,
+    config: &Config,
+    first_day: NaiveDate,
+) -> Result<Vec<lint::Problem>> {
+    let spinner =
+        ProgressBar::new_spinner().with_message(style("Checking worklogs").bold().to_string());
+    spinner.enable_steady_tick(std::time::Duration::from_millis(100));
+    let worklogs = client
+        .get_worklogs(&config.worker, first_day, first_day + TimeDelta::days(6))
+        .await?;
+    spinner.finish_and_clear();
+    let problems = lint::lint(&worklogs, config);
+    if problems.is_empty() {
+        println!("{}", style("No problems found").green());
+    }
+    for problem in &problems {
+        println!("{} {}", style("✗").red().bold(), problem);
+    }
+    Ok(problems)
+}
+
+///Verify up front that work can be logged against every project in the task list, so that
+///permission problems surface before any time is spent selecting tasks
+async fn check_permissions(client: &JtClient, config: &Config, tasks: &[Task]) -> Result<()> {
+    let spinner =
+        ProgressBar::new_spinner().with_message(style("Checking permissions").bold().to_string());
+    spinner.enable_steady_tick(std::time::Duration::from_millis(100));
+
+    let mut problems = Vec::new();
+    let (configuration, myself) = try_join(client.get_configuration(), client.get_myself()).await?;
+    if !configuration.time_tracking_enabled {
+        problems.push(String::from(
+            "time tracking is disabled on this JIRA instance",
+        ));
+    }
+    let me = match config.user_identifier {
+        UserIdentifier::Key => myself.key,
+        UserIdentifier::AccountId => myself.account_id,
+    };
+    let for_others = me.as_deref() != Some(config.worker.as_str());
+
+    let mut projects: Vec<String> = tasks.iter().map(Task::project).collect();
+    projects.sort();
+    projects.dedup();
+    let project_permissions: Vec<_> = stream::iter(&projects)
+        .map(|project| client.get_my_permissions(project))
+        .buffered(client.concurrency())
+        .try_collect()
+        .await?;
+    for (project, permissions) in projects.iter().zip(project_permissions) {
+        let can_log = permissions
+            .get("WORK_ON_ISSUES")
+            .is_some_and(|p| p.have_permission);
+        if !can_log {
+            problems.push(format!(
+                "missing \"Work On Issues\" permission (log work for self) in project {project}"
+            ));
+        }
+        if for_others {
+            let log_for_others = permissions.iter().find(|(key, p)| {
+                let needle = "log work for others";
+                key.to_lowercase().replace(['_', '-'], " ").contains(needle)
+                    || p.name.to_lowercase().contains(needle)
+            });
+            match log_for_others {
+                Some((_, p)) if !p.have_permission => problems.push(format!(
+                    "missing \"Log Work for Others\" permission in project {project}, required as worker {} is not the authenticated user",
+                    config.worker
+                )),
+                Some(_) => (),
+                None => log::warn!(
+                    "Unable to verify \"Log Work for Others\" permission in project {project}"
+                ),
+            }
+        }
+    }
+    spinner.finish_and_clear();
+
+    if problems.is_empty() {
+        return Ok(());
+    }
+    for problem in &problems {
+        println!("{} {}", style("✗").red().bold(), problem);
+    }
+    bail!("Insufficient permissions to log work, see above for details")
+}
+
+///Check that the issue of each static task still exists under the same key, offering to repair the
+///configuration of any that do not rather than failing part way through uploading
+async fn check_static_tasks(
+    client: &JtClient,
+    config: &mut Config,
+    profile: Option<&str>,
+) -> Result<()> {
+    let spinner =
+        ProgressBar::new_spinner().with_message(style("Checking static tasks").bold().to_string());
+    spinner.enable_steady_tick(std::time::Duration::from_millis(100));
+    let mut keys: Vec<&str> = config.static_tasks.iter().map(|t| t.key.as_str()).collect();
+    keys.sort();
+    keys.dedup();
+    let found: Vec<_> = stream::iter(&keys)
+        .map(|key| client.find_issue(key))
+        .buffered(client.concurrency())
+        .try_collect()
+        .await?;
+    let problems: BTreeMap<String, Option<String>> = keys
+        .into_iter()
+        .zip(found)
+        .filter(|(key, issue)| issue.as_ref().is_none_or(|issue| issue.key != *key))
+        .map(|(key, issue)| (key.to_owned(), issue.map(|issue| issue.key)))
+        .collect();
+    spinner.finish_and_clear();
+
+    for (key, moved_to) in problems {
+        match choose_static_task_repair(client, &key, moved_to).await? {
+            Some(repair) => {
+                config::repair_static_task(profile, &key, &repair)?;
+                match repair {
+                    StaticTaskRepair::Rekey(new_key) => config
+                        .static_tasks
+                        .iter_mut()
+                        .filter(|task| task.key == key)
+                        .for_each(|task| task.key = new_key.clone()),
+                    StaticTaskRepair::Disable => config.static_tasks.retain(|task| task.key != key),
+                }
+                println!("{}", style("Configuration updated").green());
+            }
+            None => config.static_tasks.retain(|task| task.key != key),
+        }
+    }
+    Ok(())
+}
+
+///Ask how to repair a static task whose issue is missing or moved, None meaning leave it out of
+///this run without changing the configuration
+async fn choose_static_task_repair(
+    client: &JtClient,
+    key: &str,
+    moved_to: Option<String>,
+) -> Result<Option<StaticTaskRepair>> {
+    let problem = match &moved_to {
+        Some(new_key) => format!("Static task {key} has moved to {new_key}"),
+        None => format!("Static task {key} no longer exists"),
+    };
+    println!("{} {}", style("!").yellow().bold(), problem);
+    let mut options = Vec::new();
+    if let Some(new_key) = &moved_to {
+        options.push(format!("Use {new_key} in the configuration"));
+    }
+    options.extend([
+        String::from("Search for a replacement issue"),
+        String::from("Disable the task in the configuration"),
+        String::from("Leave the task out this time"),
+    ]);
+    loop {
+        let mut select = Select::new()
+            .with_prompt(format!("How should {key} be repaired?"))
+            .items(&options)
+            .default(0)
+            .interact()
+            .unwrap();
+        if let Some(new_key) = &moved_to {
+            if select == 0 {
+                return Ok(Some(StaticTaskRepair::Rekey(new_key.clone())));
+            }
+            select -= 1;
+        }
+        match select {
+            0 => {
+                let query: String = Input::new()
+                    .with_prompt("Search issues for")
+                    .interact_text()
+                    .unwrap();
+                let issues = client
+                    .search_issues(&query, STATIC_TASK_SEARCH_LIMIT)
+                    .await?;
+                if issues.is_empty() {
+                    println!("{}", style("No matching issues found").yellow());
+                    continue;
+                }
+                let mut items: Vec<String> = issues.iter().map(Issue::to_string).collect();
+                items.push(String::from("None of these"));
+                let chosen = Select::new()
+                    .with_prompt("Select replacement")
+                    .items(&items)
+                    .default(0)
+                    .interact()
+                    .unwrap();
+                if let Some(issue) = issues.get(chosen) {
+                    return Ok(Some(StaticTaskRepair::Rekey(issue.key.clone())));
+                }
+            }
+            1 => return Ok(Some(StaticTaskRepair::Disable)),
+            _ => return Ok(None),
+        }
+    }
+}
+
+///Retrieve any issues referenced by a plan that are not already in the task list
+async fn fetch_planned_issues(
+    client: &JtClient,
+    plan: &plan::Plan,
+    tasks: &mut Vec<Task>,
+) -> Result<()> {
+    let names = plan
+        .days
+        .iter()
+        .flat_map(|day| day.entries.iter().map(|entry| entry.task.as_str()));
+    fetch_missing_issues(client, names, tasks).await
+}
+
+///Add the absence tasks for any absences on the days being filled to the tasks
+async fn fetch_absence_issues(
+    client: &JtClient,
+    config: &Config,
+    days: &[NaiveDate],
+    tasks: &mut Vec<Task>,
+) -> Result<()> {
+    let names = days
+        .iter()
+        .filter_map(|day| config.absence_on(*day))
+        .filter_map(|absence| config.absence_tasks.get(&absence.kind))
+        .map(String::as_str);
+    fetch_missing_issues(client, names, tasks).await
+}
+
+///Retrieve any issues referenced by the weekly template that are not already in the task list
+async fn fetch_template_issues(
+    client: &JtClient,
+    config: &Config,
+    tasks: &mut Vec<Task>,
+) -> Result<()> {
+    let names = config
+        .template
+        .values()
+        .flatten()
+        .map(|entry| entry.task.as_str());
+    fetch_missing_issues(client, names, tasks).await
+}
+
+///Move the issue named by the current git branch, then those mentioned in commits on the days
+///being filled, to the top of the tasks, retrieving any not already among them and ignoring
+///anything that only looks like an issue key
+async fn surface_git_issues(
+    client: &JtClient,
+    git: Option<&git::GitConfig>,
+    days: &[NaiveDate],
+    tasks: &mut Vec<Task>,
+) -> Result<()> {
+    let mut keys: Vec<String> = Vec::new();
+    if let Some((branch, key)) = git::branch_issue() {
+        log::info!("On branch {branch} for {key}");
+        keys.push(key);
+    }
+    if let (Some(git), Some(from), Some(to)) = (git, days.first(), days.last()) {
+        let committed = git::committed_issues(git, *from, *to);
+        log::info!("Found commits mentioning {}", committed.join(", "));
+        for key in committed {
+            if !keys.contains(&key) {
+                keys.push(key);
+            }
+        }
+    }
+    for key in &keys {
+        if tasks_named(tasks, key).is_empty() {
+            if let Some(issue) = client.find_issue(key).await? {
+                tasks.push(Task::FromQuery(issue));
+            }
+        }
+    }
+    tasks.sort_by_key(|task| {
+        keys.iter()
+            .position(|key| *key == task.key())
+            .unwrap_or(keys.len())
+    });
+    Ok(())
+}
+
+///Retrieve any issues named that are not already among the tasks
+async fn fetch_missing_issues<'a>(
+    client: &JtClient,
+    names: impl Iterator<Item = &'a str>,
+    tasks: &mut Vec<Task>,
+) -> Result<()> {
+    let mut missing: Vec<&str> = names
+        .filter(|name| tasks_named(tasks, name).is_empty())
+        .collect();
+    missing.sort();
+    missing.dedup();
+    let started = std::time::Instant::now();
+    let issues = client.get_issues(&missing).await?;
+    log::info!(
+        "Retrieved {} other issues in {:?}",
+        issues.len(),
+        started.elapsed()
+    );
+    tasks.extend(issues.into_iter().map(Task::FromQuery));
+    Ok(())
+}
+
+///Entries for a day away from work, logging the full target against the absence's task if it
+///has one and otherwise leaving the day empty
+fn absence_entries<'a>(
+    day: NaiveDate,
+    absence: &Absence,
+    config: &Config,
+    tasks: &'a [Task],
+) -> Vec<Entry<'a>> {
+    let day_name = day.format("%A, %-d %B");
+    let task = config
+        .absence_tasks
+        .get(&absence.kind)
+        .and_then(|key| tasks_named(tasks, key).into_iter().next());
+    match task {
+        Some(task) => {
+            println!(
+                "{}",
+                style(format!(
+                    "Logging {day_name} ({}) against {}",
+                    absence.kind,
+                    task.key()
+                ))
+                .dim()
+            );
+            vec![Entry::new(day, task, config.daily_target(day))]
+        }
+        None => {
+            println!(
+                "{}",
+                style(format!("Skipping {day_name} ({})", absence.kind)).dim()
+            );
+            Vec::new()
+        }
+    }
+}
+
+///Entries splitting a day evenly between tasks, printing the day's share for each
+fn even_entries<'a>(
+    day: NaiveDate,
+    tasks: &[&'a Task],
+    target_per_day: TimeDelta,
+) -> Vec<Entry<'a>> {
+    let today = work::even_entries(day, tasks, target_per_day);
+    println!("{}", style(day.format("%A, %-d %B")).bold());
+    for entry in &today {
+        println!("  {} {}", format_duration(entry.time_spent), entry.task);
+    }
+    today
+}
+
+fn plan_work<'a>(
+    plan: &plan::Plan,
+    tasks: &'a [Task],
+    first_day: NaiveDate,
+    config: &Config,
+) -> Result<Vec<Entry<'a>>> {
+    Ok(work::plan_work(
+        plan,
+        tasks,
+        first_day,
+        |day| config.daily_target(day),
+        config.number_format(),
+    )?)
+}
+
+async fn holidays_on(config: &Config, days: &[NaiveDate]) -> Result<Vec<holiday::Holiday>> {
+    match (days.first(), days.last()) {
+        (Some(first), Some(last)) => holiday::load_holidays(config, *first, *last).await,
+        _ => Ok(Vec::new()),
+    }
+}
+
+///Whether to leave a day empty because it is a holiday, asking first unless choosing at random
+fn skip_holiday(day: NaiveDate, holidays: &[holiday::Holiday], random: bool) -> bool {
+    let Some(holiday) = holidays.iter().find(|holiday| holiday.day == day) else {
+        return false;
+    };
+    let day = day.format("%A, %-d %B");
+    if random {
+        println!(
+            "{}",
+            style(format!("Skipping {day}, {}", holiday.name)).dim()
+        );
+        return true;
+    }
+    Confirm::new()
+        .with_prompt(format!("{day} is a holiday ({}), skip?", holiday.name))
+        .default(true)
+        .interact()
+        .unwrap()
+}
+
+fn select_days_tasks<'a>(
+    day: NaiveDate,
+    tasks: &'a [Task],
+    config: &Config,
+    random: bool,
+    prompt_comment: bool,
+    multi_select: bool,
+) -> Result<Vec<Entry<'a>>> {
+    let target_per_day = config.daily_target(day);
+    let default_time_spent = config.default_time_spent();
+    let number_format = config.number_format();
+    let current_date = config.timezone()?.today();
     println!("{}", style(day.format("%A, %-d %B")).bold());
+    let (mut today, mut unallocated) = allocate(day, tasks, target_per_day);
+    for entry in &today {
+        println!(
+            "Allocating {} to {}",
+            format_duration(entry.time_spent),
+            entry.task.key()
+        );
+    }
+    if let Some(template) = config.template.get(&day.weekday()) {
+        let allocated = today.iter().map(|entry| entry.time_spent).sum();
+        let templated = planned_entries(
+            day,
+            template,
+            tasks,
+            target_per_day,
+            allocated,
+            number_format,
+        )
+        .context("Invalid weekly template")?;
+        if random {
+            for entry in &templated {
+                println!(
+                    "Using template, {} on {}",
+                    format_duration(entry.time_spent),
+                    entry.task.key()
+                );
+            }
+            today.extend(templated);
+        } else {
+            today.extend(review_template(templated, target_per_day, number_format)?);
+        }
+    }
+    if multi_select && !random {
+        let remaining = target_per_day - today.iter().map(|entry| entry.time_spent).sum();
+        let split = select_split_tasks(
+            day,
+            current_date,
+            &unallocated,
+            remaining,
+            target_per_day,
+            number_format,
+        )?;
+        unallocated.retain(|task| !split.iter().any(|entry| entry.task.key() == task.key()));
+        for mut entry in split {
+            if prompt_comment {
+                prompt_entry_comment(&mut entry);
+            }
+            today.push(entry);
+        }
+    }
     while today
         .iter()
-        .map(|(_, duration)| duration)
+        .map(|entry| entry.time_spent)
         .sum::<TimeDelta>()
         < target_per_day
     {
+        if unallocated.is_empty() {
+            bail!("Allocations do not cover the daily target and there are no other tasks");
+        }
         let (selected, time_spent) = if random {
-            let time_spent = default_time_spent.ok_or(anyhow!(""))?;
-            let selected = tasks.choose(&mut thread_rng()).unwrap();
+            let selected = *unallocated.choose(&mut thread_rng()).unwrap();
+            let time_spent = selected
+                .default_time_spent()
+                .or(default_time_spent)
+                .ok_or(anyhow!(""))?;
             println!(
                 "selected {} at random, assigning default time spent",
                 selected.key()
             );
             (selected, time_spent)
         } else {
-            let select = Select::new()
-                .with_prompt("Select task")
-                .items(tasks)
+            let labels: Vec<String> = unallocated
+                .iter()
+                .map(|task| task.label(current_date))
+                .collect();
+            let select = FuzzySelect::new()
+                .with_prompt("Select task (type to filter)")
+                .items(&labels)
                 .default(0)
                 .interact()
                 .unwrap();
-            let selected = tasks.get(select).unwrap();
+            let selected = unallocated[select];
             let time_spent = if let Some(time) = default_time_spent {
                 println!("Using default time spent");
                 time
             } else {
-                let input: u64 = Input::new()
-                    .with_prompt("How many minutes did you spend on this task?")
-                    .interact()
-                    .unwrap();
-                TimeDelta::minutes(input as i64)
+                let mut input = Input::new()
+                    .with_prompt("How long did you spend on this task? (eg 90m, 1h30m, 1.5h)")
+                    .validate_with(|input: &String| {
+                        parse_duration(input, target_per_day, number_format)
+                            .map(|_| ())
+                            .map_err(|e| e.to_string())
+                    });
+                if let Some(time) = selected.default_time_spent() {
+                    input = input.default(format_duration(time));
+                }
+                let input: String = input.interact_text().unwrap();
+                parse_duration(&input, target_per_day, number_format)?
             };
             (selected, time_spent)
         };
-        today.push((selected, time_spent));
+        let mut entry = Entry::new(day, selected, time_spent);
+        if prompt_comment {
+            prompt_entry_comment(&mut entry);
+        }
+        today.push(entry);
     }
     Ok(today)
 }
 
-async fn get_tasks(client: &JtClient, done_tasks_from: NaiveDate) -> Result<Vec<Issue>> {
+fn prompt_entry_comment(entry: &mut Entry) {
+    let comment: String = Input::new()
+        .with_prompt(format!(
+            "Comment for {} (optional, may include #tags)",
+            entry.task.key()
+        ))
+        .with_initial_text(entry.comment.clone().unwrap_or_default())
+        .allow_empty(true)
+        .interact_text()
+        .unwrap();
+    (entry.comment, entry.tags) = split_tags(&comment);
+}
+
+///Let the user pick several tasks at once and split what remains of the day evenly between them,
+///optionally adjusting each share afterwards
+fn select_split_tasks<'a>(
+    day: NaiveDate,
+    today: NaiveDate,
+    unallocated: &[&'a Task],
+    remaining: TimeDelta,
+    target_per_day: TimeDelta,
+    number_format: NumberFormat,
+) -> Result<Vec<Entry<'a>>> {
+    if remaining <= TimeDelta::zero() || unallocated.is_empty() {
+        return Ok(Vec::new());
+    }
+    let labels: Vec<String> = unallocated.iter().map(|task| task.label(today)).collect();
+    let chosen = MultiSelect::new()
+        .with_prompt(format!(
+            "Select tasks to share {} between (space to select, enter to confirm)",
+            format_duration(remaining)
+        ))
+        .items(&labels)
+        .interact()
+        .unwrap();
+    if chosen.is_empty() {
+        return Ok(Vec::new());
+    }
+    let shares = split_evenly(remaining, chosen.len());
+    let entries: Vec<Entry> = chosen
+        .into_iter()
+        .zip(shares)
+        .map(|(i, share)| Entry::new(day, unallocated[i], share))
+        .collect();
+    for entry in &entries {
+        println!("  {} {}", format_duration(entry.time_spent), entry.task);
+    }
+    let adjust = Confirm::new()
+        .with_prompt("Adjust the shares?")
+        .default(false)
+        .interact()
+        .unwrap();
+    if adjust {
+        adjust_times(entries, target_per_day, number_format)
+    } else {
+        Ok(entries)
+    }
+}
+
+///Show the entries the weekly template gives a day and let the user accept, adjust or ignore them
+fn review_template<'a>(
+    templated: Vec<Entry<'a>>,
+    target_per_day: TimeDelta,
+    number_format: NumberFormat,
+) -> Result<Vec<Entry<'a>>> {
+    println!("From template:");
+    for entry in &templated {
+        println!("  {} {}", format_duration(entry.time_spent), entry.task);
+    }
+    let choice = Select::new()
+        .with_prompt("Use the template for this day?")
+        .items(&["Use as is", "Adjust times", "Ignore template"])
+        .default(0)
+        .interact()
+        .unwrap();
+    match choice {
+        0 => Ok(templated),
+        1 => adjust_times(templated, target_per_day, number_format),
+        _ => Ok(Vec::new()),
+    }
+}
+
+///Prompt for a new time for each entry, dropping those set to zero
+fn adjust_times<'a>(
+    entries: Vec<Entry<'a>>,
+    target_per_day: TimeDelta,
+    number_format: NumberFormat,
+) -> Result<Vec<Entry<'a>>> {
+    let mut adjusted = Vec::new();
+    for mut entry in entries {
+        let input: String = Input::new()
+            .with_prompt(format!("Time on {} (0 to drop)", entry.task.key()))
+            .with_initial_text(format_duration(entry.time_spent))
+            .validate_with(|input: &String| {
+                parse_duration(input, target_per_day, number_format)
+                    .map(|_| ())
+                    .map_err(|e| e.to_string())
+            })
+            .interact_text()
+            .unwrap();
+        entry.time_spent = parse_duration(&input, target_per_day, number_format)?;
+        if entry.time_spent > TimeDelta::zero() {
+            adjusted.push(entry);
+        }
+    }
+    Ok(adjusted)
+}
+
+///Prompt for the billable part of each entry, offering the task's default
+fn prompt_billable(work: &mut [Entry], config: &Config) -> Result<()> {
+    let number_format = config.number_format();
+    for entry in work.iter_mut() {
+        let target_per_day = config.daily_target(entry.day);
+        let time_spent = entry.time_spent;
+        let parse = |input: &str| -> Result<TimeDelta> {
+            let billable = parse_duration(input, target_per_day, number_format)?;
+            if billable > time_spent {
+                bail!(
+                    "Billable time cannot exceed {}",
+                    format_duration(time_spent)
+                );
+            }
+            Ok(billable)
+        };
+        let input: String = Input::new()
+            .with_prompt(format!(
+                "Billable time on {} on {} of {}",
+                entry.task.key(),
+                entry.day.format("%a %-d"),
+                format_duration(time_spent)
+            ))
+            .with_initial_text(format_duration(entry.billable_time().unwrap_or(time_spent)))
+            .validate_with(|input: &String| parse(input).map(|_| ()).map_err(|e| e.to_string()))
+            .interact_text()
+            .unwrap();
+        entry.billable = Some(parse(&input)?);
+    }
+    Ok(())
+}
+
+///Search for tasks, reusing the results of the same search if it was made in the last few minutes
+///unless asked to refresh them
+async fn get_tasks(
+    client: &JtClient,
+    done_tasks_from: NaiveDate,
+    jql: Option<&str>,
+    refresh: bool,
+) -> Result<Vec<Issue>> {
+    let cache_key = format!(
+        "{} {done_tasks_from} {}",
+        client.base(),
+        jql.unwrap_or_default()
+    );
+    let mut cached: HashMap<String, CachedTasks> =
+        state::load_cache(TASK_CACHE).unwrap_or_default();
+    let now = chrono::Utc::now();
+    cached.retain(|_, tasks| now - tasks.fetched < TASK_CACHE_TTL);
+    if let Some(tasks) = cached.remove(&cache_key).filter(|_| !refresh) {
+        let age = (now - tasks.fetched).num_minutes();
+        eprintln!(
+            "{} {}",
+            style("Assigned tasks retrieved").green(),
+            style(format!(
+                "(cached {age}m ago, pass --refresh to fetch them again)"
+            ))
+            .dim()
+        );
+        print_unshown_tasks(tasks.issues.len(), tasks.total);
+        return Ok(tasks.issues);
+    }
+
     let spinner = ProgressBar::new_spinner().with_message(
         style("Retrieving assigned tasks from JIRA")
             .bold()
             .to_string(),
     );
     spinner.enable_steady_tick(std::time::Duration::from_millis(100));
-    let tasks = client.get_assigned_issues(done_tasks_from).await?;
+    let started = std::time::Instant::now();
+    let found = client.get_assigned_issues(done_tasks_from, jql).await?;
+    log::info!(
+        "Retrieved {} of {} matching tasks in {:?}",
+        found.issues.len(),
+        found.total,
+        started.elapsed()
+    );
     spinner.finish_and_clear();
-    println!("{}", style("Assigned tasks retrieved").green());
-    Ok(tasks)
+    eprintln!("{}", style("Assigned tasks retrieved").green());
+    print_unshown_tasks(found.issues.len(), found.total);
+
+    cached.insert(
+        cache_key.clone(),
+        CachedTasks {
+            fetched: now,
+            total: found.total,
+            issues: found.issues,
+        },
+    );
+    if let Err(e) = state::write_cache(TASK_CACHE, &cached) {
+        log::warn!("Unable to cache tasks: {e}");
+    }
+    Ok(cached
+        .remove(&cache_key)
+        .map(|tasks| tasks.issues)
+        .unwrap_or_default())
 }
 
-async fn upload_worklogs(
+fn print_unshown_tasks(shown: usize, total: usize) {
+    if total > shown {
+        eprintln!(
+            "{}",
+            style(format!(
+                "Showing {shown} of {total} matching tasks, narrow the configured jql to see others"
+            ))
+            .yellow()
+        );
+    }
+}
+
+///Round each entry's time spent as configured, dropping any rounded down to nothing
+fn round_work<'a>(work: Vec<Entry<'a>>, config: &Config) -> Vec<Entry<'a>> {
+    work::round_work(work, |time_spent| config.round(time_spent))
+}
+
+///Every attribute to give a worklog, from the task itself, its project, the global defaults and
+///its tags
+fn worklog_attributes(
+    entry: &Entry,
+    config: &Config,
+    issue_attributes: &HashMap<String, Vec<WorkAttribute>>,
+) -> Vec<WorkAttribute> {
+    let mut attributes = match entry.task {
+        Task::Static(task) => task.attributes.clone(),
+        Task::FromQuery(issue) => issue_attributes
+            .get(&issue.key)
+            .cloned()
+            .unwrap_or_default(),
+        Task::Remote(_) => Vec::new(),
+    };
+    if let Some(project_attributes) = config.project_attributes.get(&entry.task.project()) {
+        add_missing_attributes(&mut attributes, project_attributes);
+    }
+    if let Task::FromQuery(_) | Task::Remote(_) = entry.task {
+        add_missing_attributes(&mut attributes, &config.static_attributes);
+    }
+    attributes.extend(tag::tag_attributes(&entry.tags, &config.tags));
+    attributes
+}
+
+///Fail before logging anything if a list attribute would be given a value Tempo does not allow,
+///as Tempo rejects or mangles them
+async fn check_attribute_values(
     client: &JtClient,
-    dynamic_attributes: Vec<WorkAttribute>,
-    static_attributes: Vec<WorkAttribute>,
-    worker: &str,
-    worklogs: Vec<(NaiveDate, &Task, TimeDelta)>,
+    worklogs: &[Entry<'_>],
+    attributes: &[Vec<WorkAttribute>],
 ) -> Result<()> {
+    if attributes.iter().all(Vec::is_empty) {
+        return Ok(());
+    }
+    let definitions = match client.get_work_attributes().await {
+        Ok(definitions) => definitions,
+        Err(e) => {
+            log::warn!("Unable to fetch work attributes from Tempo, not checking values: {e}");
+            return Ok(());
+        }
+    };
+    let allowed: HashMap<&str, Vec<String>> = definitions
+        .iter()
+        .filter(|definition| definition.is_static_list())
+        .map(|definition| (definition.key.as_str(), definition.options()))
+        .collect();
+    let mut problems = Vec::new();
+    for (entry, attributes) in worklogs.iter().zip(attributes) {
+        for attribute in attributes {
+            let Some(options) = allowed.get(attribute.key.as_str()) else {
+                continue;
+            };
+            let problem = format!(
+                "{}: \"{}\" is not an allowed {}",
+                entry.task.key(),
+                attribute.value,
+                attribute.name
+            );
+            if !options.contains(&attribute.value) && !problems.contains(&problem) {
+                problems.push(problem);
+            }
+        }
+    }
+    if problems.is_empty() {
+        return Ok(());
+    }
+    bail!(
+        "Not logging work, some attribute values are not allowed by Tempo:\n  {}",
+        problems.join("\n  ")
+    )
+}
+
+///Add defaults for attributes not already set, so that more specific values take precedence
+fn add_missing_attributes(attributes: &mut Vec<WorkAttribute>, defaults: &[WorkAttribute]) {
+    for default in defaults {
+        if !attributes
+            .iter()
+            .any(|attribute| attribute.key == default.key)
+        {
+            attributes.push(default.clone());
+        }
+    }
+}
+
+///When the timed work already logged on each day being logged ends, so that new worklogs start
+///after it rather than overlapping it, no earlier than the start of the working day
+async fn logged_until(
+    client: &JtClient,
+    config: &Config,
+    work: &[Entry<'_>],
+    workday_start: NaiveTime,
+) -> Result<HashMap<NaiveDate, NaiveTime>> {
+    let (Some(first), Some(last)) = (
+        work.iter().map(|entry| entry.day).min(),
+        work.iter().map(|entry| entry.day).max(),
+    ) else {
+        return Ok(HashMap::new());
+    };
+    let mut until = HashMap::new();
+    for worklog in client.get_worklogs(&config.worker, first, last).await? {
+        let (Ok(day), Some(start)) = (worklog.day(), worklog.start_time()) else {
+            continue;
+        };
+        let (end, wrapped) =
+            start.overflowing_add_signed(TimeDelta::seconds(worklog.time_spent_seconds as i64));
+        if start == NaiveTime::MIN || wrapped != 0 {
+            continue;
+        }
+        let next = until.entry(day).or_insert(workday_start);
+        *next = (*next).max(end);
+    }
+    Ok(until)
+}
+
+async fn upload_worklogs(
+    client: &JtClient,
+    config: &Config,
+    issue_attributes: &HashMap<String, Vec<WorkAttribute>>,
+    worklogs: Vec<Entry<'_>>,
+) -> Result<Vec<UploadResult>> {
+    let max_time_spent = config
+        .max_worklog_minutes
+        .map(|minutes| TimeDelta::minutes(minutes as i64));
+    let worklogs: Vec<Entry> = worklogs
+        .into_iter()
+        .flat_map(|entry| split_entry(entry, max_time_spent))
+        .collect();
+    let attributes: Vec<Vec<WorkAttribute>> = worklogs
+        .iter()
+        .map(|entry| worklog_attributes(entry, config, issue_attributes))
+        .collect();
+    check_attribute_values(client, &worklogs, &attributes).await?;
     let bar = ProgressBar::new(worklogs.len() as u64)
         .with_style(ProgressStyle::with_template("{msg}\n{bar} {pos}/{len}").unwrap())
         .with_message(style("Logging work on Tempo").bold().to_string());
-    for (day, log, time_spent) in worklogs {
-        let attributes = match log {
-            Task::Static(task) => task.attributes.clone(),
-            Task::FromQuery(issue) => {
-                resolve_attributes(issue, &static_attributes, &dynamic_attributes)?
+    let mut results: Vec<UploadResult> = Vec::with_capacity(worklogs.len());
+    let mut next_start = match config.workday_start {
+        Some(workday_start) => logged_until(client, config, &worklogs, workday_start).await?,
+        None => HashMap::new(),
+    };
+    for (entry, attributes) in worklogs.into_iter().zip(attributes) {
+        let start_time = entry.start_time.or_else(|| {
+            let workday_start = config.workday_start?;
+            let next = next_start.entry(entry.day).or_insert(workday_start);
+            let (end, wrapped) = next.overflowing_add_signed(entry.time_spent);
+            if wrapped == 0 {
+                return Some(std::mem::replace(next, end));
             }
+            let (latest, _) =
+                NaiveTime::MIN.overflowing_sub_signed(entry.time_spent.min(TimeDelta::days(1)));
+            eprintln!(
+                "{}",
+                style(format!(
+                    "{} on {} would run past midnight, starting it at {} so that it overlaps earlier worklogs",
+                    entry.task.key(),
+                    entry.day,
+                    latest.format("%H:%M")
+                ))
+                .yellow()
+            );
+            Some(latest)
+        });
+        let billable = entry.billable_time();
+        let new_worklog = NewWorklog {
+            task_id: entry.task.worklog_key(),
+            day: entry.day,
+            start_time,
+            time_spent: entry.time_spent,
+            comment: tag::tag_comment(
+                entry.task.worklog_comment(entry.comment),
+                &entry.tags,
+                &config.tags,
+            ),
+            attributes: attributes.clone(),
+            visibility: entry.task.visibility(config),
+            remaining_estimate: config.remaining_estimate,
+            billable,
         };
-        client
-            .create_worklog(worker, day, &log.key(), time_spent, attributes)
-            .await?;
+        let worklog = client
+            .create_worklog(&config.worker, new_worklog)
+            .await
+            .with_context(|| {
+                let logged: Vec<String> = results
+                    .iter()
+                    .map(|result| format!("{} on {}", result.key, result.day))
+                    .collect();
+                if logged.is_empty() {
+                    format!("Unable to log {} on {}", entry.task.key(), entry.day)
+                } else {
+                    format!(
+                        "Unable to log {} on {}, after logging {}",
+                        entry.task.key(),
+                        entry.day,
+                        logged.join(", ")
+                    )
+                }
+            })?;
+        results.push(UploadResult {
+            day: entry.day,
+            key: entry.task.key(),
+            time_spent: entry.time_spent,
+            attributes,
+            worklog,
+        });
         bar.inc(1);
     }
     bar.finish_and_clear();
-    println!("{}", style("Work logged").green().bold());
-    Ok(())
+    if client.is_dry_run() {
+        println!("{}", style("Dry run, no work logged").yellow().bold());
+    } else {
+        println!("{}", style("Work logged").green().bold());
+    }
+    Ok(results)
+}
+
+///Warn about days with work on more than `max` distinct issues, suggesting the shortest entries
+///as candidates for merging, and ask whether to log the work anyway
+fn check_issues_per_day(work: &[Entry], max: usize, interactive: bool) -> bool {
+    let mut per_day: BTreeMap<NaiveDate, Vec<(String, TimeDelta)>> = BTreeMap::new();
+    for entry in work {
+        let issues = per_day.entry(entry.day).or_default();
+        match issues.iter_mut().find(|(key, _)| *key == entry.task.key()) {
+            Some((_, time_spent)) => *time_spent += entry.time_spent,
+            None => issues.push((entry.task.key(), entry.time_spent)),
+        }
+    }
+    let mut busy = false;
+    for (day, mut issues) in per_day {
+        if issues.len() <= max {
+            continue;
+        }
+        busy = true;
+        issues.sort_by_key(|(_, time_spent)| *time_spent);
+        let shortest: Vec<String> = issues
+            .iter()
+            .take(issues.len() - max + 1)
+            .map(|(key, time_spent)| format!("{key} ({})", format_duration(*time_spent)))
+            .collect();
+        println!(
+            "{}",
+            style(format!(
+                "{} has {} issues, more than the maximum of {max}; consider merging {}",
+                day.format("%A, %-d %B"),
+                issues.len(),
+                shortest.join(", ")
+            ))
+            .yellow()
+        );
+    }
+    !busy
+        || !interactive
+        || Confirm::new()
+            .with_prompt("Log work anyway?")
+            .default(false)
+            .interact()
+            .unwrap()
 }
 
-fn resolve_attributes(
-    issue: &Issue,
-    static_attributes: &[WorkAttribute],
-    dynamic_attributes: &[WorkAttribute],
-) -> Result<Vec<WorkAttribute>> {
-    let mut resolved = dynamic_attributes
+fn print_preview(work: &[Entry], days: &[NaiveDate], format: OutputFormat) {
+    let mut days = days.to_vec();
+    days.extend(work.iter().map(|entry| entry.day));
+    days.sort();
+    days.dedup();
+    let entries = work
         .iter()
-        .map(|attr| {
-            let pointable = serde_json::to_value(&issue.fields).unwrap();
-            let pointed = pointable
-                .pointer(&attr.value)
-                .context("Unable to resolve JSON pointer")?
-                .as_str()
-                .context("JSON pointer does not point to string value")?;
-            let mut evaluated = attr.clone();
-            evaluated.value = pointed.to_owned();
-            Ok(evaluated)
+        .map(|entry| (entry.day, entry.task.key(), entry.time_spent));
+    println!("{}", report::render(&WeekGrid::new(days, entries), format));
+}
+
+///Total time per tag group, for entries with tags
+fn print_tag_totals(work: &[Entry], config: &Config) {
+    let mut totals: BTreeMap<&str, TimeDelta> = BTreeMap::new();
+    for entry in work {
+        for tag in &entry.tags {
+            *totals.entry(tag::tag_group(tag, &config.tags)).or_default() += entry.time_spent;
+        }
+    }
+    if totals.is_empty() {
+        return;
+    }
+    let totals: Vec<String> = totals
+        .into_iter()
+        .map(|(group, total)| format!("{group} {}", format_duration(total)))
+        .collect();
+    println!("{} {}\n", style("By tag:").bold(), totals.join(", "));
+}
+
+///Show the worklogs created when verbose, or on a dry run everything that would have been logged
+fn print_results(ctx: &RunContext, client: &JtClient, results: &[UploadResult]) {
+    if ctx.dry_run && ctx.output != OutputFormat::Json {
+        print_dry_run(results);
+    } else if ctx.verbose {
+        print_upload_results(client, results);
+    }
+}
+
+///Every worklog a dry run would have created, with its attributes and each day's total
+fn print_dry_run(results: &[UploadResult]) {
+    let mut results: Vec<&UploadResult> = results.iter().collect();
+    results.sort_by_key(|result| result.day);
+    println!(
+        "{}",
+        style(format!(
+            "{:<12} {:<12} {:>8}  {}",
+            "Day", "Issue", "Time", "Attributes"
+        ))
+        .bold()
+    );
+    for day in results.chunk_by(|a, b| a.day == b.day) {
+        for (i, result) in day.iter().enumerate() {
+            let date = if i == 0 {
+                result.day.format("%a %-d %b").to_string()
+            } else {
+                String::new()
+            };
+            let attributes: Vec<String> = result
+                .attributes
+                .iter()
+                .map(|attribute| format!("{}={}", attribute.name, attribute.value))
+                .collect();
+            println!(
+                "{:<12} {:<12} {:>8}  {}",
+                date,
+                result.key,
+                format_duration(result.time_spent),
+                attributes.join(", ")
+            );
+        }
+        let total: TimeDelta = day.iter().map(|result| result.time_spent).sum();
+        println!(
+            "{}",
+            style(format!(
+                "{:<12} {:<12} {:>8}",
+                "",
+                "Total",
+                format_duration(total)
+            ))
+            .dim()
+        );
+    }
+    let total: TimeDelta = results.iter().map(|result| result.time_spent).sum();
+    println!(
+        "{}",
+        style(format!(
+            "{:<25} {:>8}",
+            "Week total",
+            format_duration(total)
+        ))
+        .bold()
+    );
+}
+
+fn print_upload_results(client: &JtClient, results: &[UploadResult]) {
+    println!(
+        "{}",
+        style(format!(
+            "{:<12} {:<12} {:>8} {:>10}  {}",
+            "Day", "Issue", "Time", "Worklog", "Link"
+        ))
+        .bold()
+    );
+    for result in results {
+        let id = result
+            .worklog
+            .as_ref()
+            .map(|worklog| worklog.tempo_worklog_id.to_string())
+            .unwrap_or_else(|| String::from("-"));
+        let jira_worklog_id = result.worklog.as_ref().and_then(|w| w.jira_worklog_id);
+        println!(
+            "{:<12} {:<12} {:>8} {:>10}  {}",
+            result.day.format("%a %-d %b").to_string(),
+            result.key,
+            format_duration(result.time_spent),
+            id,
+            client.issue_url(&result.key, jira_worklog_id)
+        );
+    }
+}
+
+///Determine values for prompted attributes for each issue being logged against, reusing values
+///remembered from previous runs unless asked to prompt again
+fn prompt_attributes(
+    work: &[Entry],
+    prompted: &[PromptedAttribute],
+    reprompt: bool,
+    interactive: bool,
+) -> Result<HashMap<String, Vec<WorkAttribute>>> {
+    let mut resolved = HashMap::new();
+    if prompted.is_empty() {
+        return Ok(resolved);
+    }
+    let mut memory: HashMap<String, HashMap<String, String>> =
+        state::load_data(ATTRIBUTE_MEMORY).unwrap_or_default();
+    for entry in work {
+        let Task::FromQuery(issue) = entry.task else {
+            continue;
+        };
+        if resolved.contains_key(&issue.key) {
+            continue;
+        }
+        let remembered = memory.entry(issue.key.clone()).or_default();
+        let mut attributes = Vec::new();
+        for attr in prompted {
+            let value = match remembered.get(&attr.key) {
+                Some(value) if !reprompt => {
+                    println!(
+                        "{}: {} = {} {}",
+                        issue.key,
+                        attr.name,
+                        value,
+                        style("(remembered)").dim()
+                    );
+                    value.clone()
+                }
+                None if !interactive => bail!(
+                    "No remembered value for attribute {} on {}, run fill interactively first",
+                    attr.name,
+                    issue.key
+                ),
+                previous => {
+                    let prompt = format!("{} for {}", attr.name, issue);
+                    if attr.options.is_empty() {
+                        let mut input = Input::new().with_prompt(prompt);
+                        if let Some(previous) = previous {
+                            input = input.default(previous.clone());
+                        }
+                        input.interact_text().unwrap()
+                    } else {
+                        let default = previous
+                            .and_then(|p| attr.options.iter().position(|o| o == p))
+                            .unwrap_or(0);
+                        let select = Select::new()
+                            .with_prompt(prompt)
+                            .items(&attr.options)
+                            .default(default)
+                            .interact()
+                            .unwrap();
+                        attr.options[select].clone()
+                    }
+                }
+            };
+            remembered.insert(attr.key.clone(), value.clone());
+            attributes.push(WorkAttribute {
+                key: attr.key.clone(),
+                name: attr.name.clone(),
+                work_attribute_id: attr.work_attribute_id,
+                value,
+            });
+        }
+        resolved.insert(issue.key.clone(), attributes);
+    }
+    state::write_data(ATTRIBUTE_MEMORY, &memory)?;
+    Ok(resolved)
+}
+
+///Attribute values resolved for each issue, along with where fallbacks had to be used
+#[derive(Default)]
+struct IssueAttributes {
+    values: HashMap<String, Vec<WorkAttribute>>,
+    fallbacks: Vec<AttributeFallback>,
+}
+
+impl IssueAttributes {
+    fn merge(&mut self, other: HashMap<String, Vec<WorkAttribute>>) {
+        for (key, attributes) in other {
+            self.values.entry(key).or_default().extend(attributes);
+        }
+    }
+}
+
+///A dynamic attribute that could not be read from an issue, eg because of field-level security
+struct AttributeFallback {
+    key: String,
+    name: String,
+    ///None if the attribute was left out
+    value: Option<String>,
+}
+
+///Issues from JIRA being logged against, rather than static tasks
+fn queried_issues<'a>(work: &[Entry<'a>]) -> Vec<&'a Issue> {
+    work.iter()
+        .filter_map(|entry| match entry.task {
+            Task::FromQuery(issue) => Some(issue),
+            Task::Static(_) | Task::Remote(_) => None,
         })
-        .collect::<Result<Vec<WorkAttribute>>>()?;
-    resolved.extend_from_slice(static_attributes);
+        .collect()
+}
+
+///Read dynamic attributes from each issue being logged against, falling back to the configured
+///default, leaving the attribute out if asked to, or prompting, where the field is missing from an
+///issue
+fn resolve_dynamic_attributes(
+    work: &[Entry],
+    dynamic: &[DynamicAttribute],
+    interactive: bool,
+    skip_unresolved: bool,
+) -> Result<IssueAttributes> {
+    let mut resolved = IssueAttributes::default();
+    for entry in work {
+        let Task::FromQuery(issue) = entry.task else {
+            continue;
+        };
+        if resolved.values.contains_key(&issue.key) {
+            continue;
+        }
+        let mut attributes = Vec::new();
+        for attr in dynamic {
+            let value = match attr.resolve(issue)? {
+                Some(value) => value,
+                None => {
+                    let value = match &attr.fallback {
+                        Some(fallback) => Some(fallback.clone()),
+                        None if skip_unresolved => None,
+                        None if interactive => Some(
+                            Input::new()
+                                .with_prompt(format!(
+                                    "{} for {} (not readable from the issue)",
+                                    attr.attribute.name, issue
+                                ))
+                                .interact_text()
+                                .unwrap(),
+                        ),
+                        None => bail!(
+                            "Unable to resolve {} on {}, the field may be hidden from you; configure a fallback for it or pass --skip-unresolved",
+                            attr.attribute.name,
+                            issue.key
+                        ),
+                    };
+                    resolved.fallbacks.push(AttributeFallback {
+                        key: issue.key.clone(),
+                        name: attr.attribute.name.clone(),
+                        value: value.clone(),
+                    });
+                    let Some(value) = value else {
+                        continue;
+                    };
+                    value
+                }
+            };
+            let mut evaluated = attr.attribute.clone();
+            evaluated.value = value;
+            attributes.push(evaluated);
+        }
+        resolved.values.insert(issue.key.clone(), attributes);
+    }
     Ok(resolved)
 }
 
+fn print_fallbacks(fallbacks: &[AttributeFallback]) {
+    if fallbacks.is_empty() {
+        return;
+    }
+    println!(
+        "{}",
+        style("Attributes not readable from these issues used fallback values or were left out:")
+            .yellow()
+    );
+    for fallback in fallbacks {
+        match &fallback.value {
+            Some(value) => println!("  {}: {} = {value}", fallback.key, fallback.name),
+            None => println!("  {}: {} left out", fallback.key, fallback.name),
+        }
+    }
+}
+
 async fn submit(
     client: &JtClient,
     reviewer: Option<String>,
@@ -337,11 +4236,42 @@ async fn submit(
         let spinner = ProgressBar::new_spinner()
             .with_message(style("Submitting timesheet").bold().to_string());
         spinner.enable_steady_tick(std::time::Duration::from_millis(100));
-        client
+        let approval = client
             .submit_timesheet(worker, &reviewer, first_day)
             .await?;
         spinner.finish_and_clear();
-        println!("{}", style("Timesheet submitted").green().bold());
+        let Some(approval) = approval else {
+            println!(
+                "{}",
+                style("Dry run, timesheet not submitted").yellow().bold()
+            );
+            return Ok(());
+        };
+        if approval.is_open() {
+            println!(
+                "{}",
+                style("Tempo still reports the timesheet as open, the submission may not have stuck; check it in Tempo")
+                    .yellow()
+                    .bold()
+            );
+        } else {
+            println!(
+                "{}",
+                style(format!("Timesheet submitted ({})", approval.status))
+                    .green()
+                    .bold()
+            );
+        }
+        receipt::record(receipt::Receipt {
+            worker: worker.to_owned(),
+            week: first_day,
+            submitted_at: chrono::Local::now(),
+            reviewer: approval
+                .reviewer
+                .and_then(|r| r.display_name)
+                .unwrap_or(reviewer),
+            status: approval.status,
+        })?;
         Ok(())
     } else {
         bail!("No reviewer specified for submission")
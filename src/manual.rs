@@ -0,0 +1,207 @@
+use std::path::Path;
+
+use anyhow::{Context, Result};
+
+pub const FILL_EXAMPLES: &str = "\
+Examples:
+  Fill the current week, picking tasks for each day:
+    jt fill
+  Fill last week and submit it for approval:
+    jt fill --week -1 --submit
+  Catch up on the days since the start of May:
+    jt fill --from 2024-05-01
+  Fill next week from a plan file without any prompts:
+    jt fill --next --plan next-week.toml
+  Split every day evenly between two issues:
+    jt fill --even --tasks ABC-1,ABC-2
+  Preview a week without logging anything:
+    jt fill --dry-run --output json";
+
+pub const LOG_EXAMPLES: &str = "\
+Examples:
+  Log an hour and a half against an issue today:
+    jt log ABC-123 1h30m
+  Log time against yesterday with a comment and tag:
+    jt log ABC-123 45m --date 2024-05-02 --comment \"Code review\" --tag support
+  Log a meeting that started at 2pm:
+    jt log ABC-123 1h --start 14:00
+  Log time without touching the issue's remaining estimate:
+    jt log ABC-123 2h --remaining-estimate leave
+  Log time against the issue named by the current git branch, eg feature/ABC-123-login:
+    jt log 45m";
+
+pub const INIT_EXAMPLES: &str = "\
+Examples:
+  Create the default configuration:
+    jt init
+  Create a configuration for a second JIRA instance:
+    jt init --profile client
+  Create a configuration kept alongside a project:
+    jt --config ./jt.toml init";
+
+pub const PLAN_EXAMPLES: &str = "\
+Examples:
+  Plan next week interactively:
+    jt plan --next next-week.toml
+  Start from last week's plan, keeping the days that still fit:
+    jt plan --next --from this-week.toml next-week.toml
+  Spread the issues of a sprint over the week, an hour each:
+    jt plan generate sprint.toml --jql \"sprint in openSprints()\" --minutes-per-issue 60";
+
+pub const LINT_EXAMPLES: &str = "\
+Examples:
+  Check the current week:
+    jt lint
+  Check last week:
+    jt lint --week -1";
+
+pub const ISSUES_EXAMPLES: &str = "\
+Examples:
+  List the tasks fill would offer:
+    jt issues
+  List the keys of issues due soonest, for use in a script:
+    jt issues --output json | jq -r '.[] | select(.due) | .key'";
+
+pub const VIEW_EXAMPLES: &str = "\
+Examples:
+  Show an issue:
+    jt view ABC-123
+  Read its status in a script:
+    jt view ABC-123 --output json | jq -r .status";
+
+pub const REPORT_EXAMPLES: &str = "\
+Examples:
+  Show what has been logged this week:
+    jt report
+  Show last week as bars, to spot unevenly filled days:
+    jt report --week -1 --output blocks
+  Total last month by issue and write it out for an invoice:
+    jt report --month -1 --csv may.csv";
+
+pub const TUI_EXAMPLES: &str = "\
+Examples:
+  Lay out this week from scratch:
+    jt tui
+  Adjust next week's plan before logging it:
+    jt tui --next --plan next-week.toml
+
+Keys:
+  arrows or hjkl  move between cells
+  + and -         add or take away a step (rounding_minutes, or 15m)
+  f               fill the rest of the day on the current task
+  0               clear the cell
+  a               add a task, typing to narrow the list
+  x               remove the current task
+  p               log the whole grid to Tempo
+  q or Esc        quit without logging";
+
+pub const EXPORT_EXAMPLES: &str = "\
+Examples:
+  Archive everything logged so far this year as CSV:
+    jt export --from 2024-01-01
+  Write May's worklogs as JSON to standard output for another tool:
+    jt export --from 2024-05-01 --to 2024-05-31 --format json --file -";
+
+pub const BALANCE_EXAMPLES: &str = "\
+Examples:
+  Check this week's worklogs against your project commitments:
+    jt balance
+  Check next week's plan:
+    jt balance --next --plan next-week.toml
+  Rescale the plan so each project gets exactly its committed share:
+    jt balance --next --plan next-week.toml --auto";
+
+pub const COPY_WEEK_EXAMPLES: &str = "\
+Examples:
+  Copy last week into this week:
+    jt copy-week
+  Copy the week before last into next week, previewing it first:
+    jt copy-week --from -2 --to 1 --dry-run";
+
+pub const IMPORT_EXAMPLES: &str = "\
+Examples:
+  Log this week's meetings from Google Calendar:
+    jt import google
+  Log last week's meetings, previewing them first:
+    jt import google --week -1 --dry-run
+  Log this week's accepted meetings from Outlook:
+    jt import outlook
+  Log last week's events from a calendar exported from Outlook:
+    jt import ics --week -1 calendar.ics
+  Log the time tracked in Toggl Track this week:
+    jt import toggl
+  Preview last week's Harvest time without logging it:
+    jt import harvest --week -1 --dry-run
+  Log the time tracked in Clockify last week:
+    jt import clockify --week -1
+  Migrate worklogs kept in a spreadsheet:
+    jt import csv worklogs.csv";
+
+pub const STATUS_EXAMPLES: &str = "\
+Examples:
+  Show whether this week's timesheet has been approved:
+    jt status
+  Check on last week's timesheet:
+    jt status --week -1
+  Show the same as JSON for another tool:
+    jt status --output json";
+
+pub const APPROVE_EXAMPLES: &str = "\
+Examples:
+  Approve last week's timesheet of someone in your team, picking them from a list:
+    jt approve --week -1
+  Approve a particular worker's timesheet:
+    jt approve jsmith --week -1";
+
+pub const REJECT_EXAMPLES: &str = "\
+Examples:
+  Reject a timesheet, giving the reason:
+    jt reject jsmith --week -1 --comment \"Missing Friday\"";
+
+pub const CONFIG_EXAMPLES: &str = "\
+Examples:
+  Check the configuration before relying on it:
+    jt config validate
+  See which endpoint and reviewer a profile ends up with:
+    jt config show --profile client
+  Edit the configuration in VS Code rather than $EDITOR:
+    EDITOR=\"code --wait\" jt config edit
+  Add a static task, eg for time off:
+    jt config add-task
+  Add a task to a group when static tasks are grouped:
+    jt config add-task --group meetings";
+
+pub const AUTH_EXAMPLES: &str = "\
+Examples:
+  Store a token in the OS keyring:
+    jt auth login
+  Store a username and password for an instance without tokens:
+    jt auth login --basic
+  Sign in through the browser, with auth = \"oauth\" configured:
+    jt auth login
+  Store the token for another instance whose issues are offered as tasks:
+    jt auth login --instance client
+  Forget the token of a profile:
+    jt auth logout --profile client";
+
+pub const COMPLETIONS_EXAMPLES: &str = "\
+Examples:
+  Complete jt in bash for the current user:
+    jt completions bash > ~/.local/share/bash-completion/completions/jt
+  Complete jt in zsh, with ~/.zfunc on the fpath:
+    jt completions zsh > ~/.zfunc/_jt
+  Complete jt in fish:
+    jt completions fish > ~/.config/fish/completions/jt.fish";
+
+///Print a completion script for the shell to standard output
+pub fn write_completions(mut command: clap::Command, shell: clap_complete::Shell) {
+    let name = command.get_name().to_owned();
+    clap_complete::generate(shell, &mut command, name, &mut std::io::stdout());
+}
+
+///Write a man page for jt and one for each of its commands into a directory
+pub fn write_man_pages(command: clap::Command, dir: &Path) -> Result<()> {
+    std::fs::create_dir_all(dir).with_context(|| format!("Unable to create {}", dir.display()))?;
+    clap_mangen::generate_to(command, dir)
+        .with_context(|| format!("Unable to write man pages to {}", dir.display()))
+}
@@ -0,0 +1,43 @@
+use std::{
+    cell::Cell,
+    io::Write,
+    time::{Duration, Instant},
+};
+
+use notify_rust::Notification;
+
+///How long jt must have been working without input before it is worth getting the user's attention
+const LONG_RUNNING: Duration = Duration::from_secs(10);
+
+///Gets the user's attention with a terminal bell and desktop notification when jt finishes or
+///needs input after working for a while
+pub struct Notifier {
+    enabled: bool,
+    since: Cell<Instant>,
+}
+
+impl Notifier {
+    pub fn new(enabled: bool) -> Notifier {
+        Notifier {
+            enabled,
+            since: Cell::new(Instant::now()),
+        }
+    }
+
+    pub fn attention(&self, message: &str) {
+        let waited = self.since.replace(Instant::now()).elapsed();
+        if !self.enabled || waited < LONG_RUNNING {
+            return;
+        }
+        eprint!("\x07");
+        let _ = std::io::stderr().flush();
+        if let Err(e) = Notification::new().summary("jt").body(message).show() {
+            log::warn!("Unable to show desktop notification: {e}");
+        }
+    }
+
+    ///Restart the clock, eg once the user has answered a prompt
+    pub fn reset(&self) {
+        self.since.set(Instant::now());
+    }
+}
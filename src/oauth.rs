@@ -0,0 +1,366 @@
+use std::{
+    io::{BufRead, BufReader, Write},
+    net::TcpListener,
+    time::Duration,
+};
+
+use anyhow::{anyhow, bail, Context, Result};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use console::style;
+use rand::{distributions::Alphanumeric, Rng};
+use reqwest::{Client, Url};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use jt_core::{client, options::HttpOptions};
+
+use crate::auth;
+
+const DEVICE_CODE_GRANT: &str = "urn:ietf:params:oauth:grant-type:device_code";
+
+///An OAuth client authorized with the device authorization grant, so that no browser redirect to
+///jt is needed
+pub struct DeviceFlow<'a> {
+    ///Keyring name the refresh token is stored under
+    pub credential: &'a str,
+    pub device_code_url: &'a str,
+    pub token_url: &'a str,
+    pub client_id: &'a str,
+    pub client_secret: Option<&'a str>,
+    pub scope: &'a str,
+}
+
+#[derive(Deserialize)]
+struct DeviceCode {
+    device_code: String,
+    user_code: String,
+    #[serde(alias = "verification_url")]
+    verification_uri: String,
+    #[serde(default = "default_interval")]
+    interval: u64,
+}
+
+fn default_interval() -> u64 {
+    5
+}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    access_token: Option<String>,
+    refresh_token: Option<String>,
+    error: Option<String>,
+    error_description: Option<String>,
+}
+
+impl DeviceFlow<'_> {
+    ///An access token, from the stored refresh token if it is still good and otherwise by asking
+    ///the user to authorize jt in their browser
+    pub async fn access_token(&self) -> Result<String> {
+        let client = Client::new();
+        let refreshed = refresh(
+            &client,
+            self.token_url,
+            self.credential,
+            self.client_id,
+            self.client_secret,
+        )
+        .await?;
+        match refreshed {
+            Some(access_token) => Ok(access_token),
+            None => self.authorize(&client).await,
+        }
+    }
+
+    async fn authorize(&self, client: &Client) -> Result<String> {
+        let device_code: DeviceCode = client
+            .post(self.device_code_url)
+            .form(&[("client_id", self.client_id), ("scope", self.scope)])
+            .send()
+            .await?
+            .error_for_status()
+            .context("Unable to start authorization")?
+            .json()
+            .await?;
+        println!(
+            "To let jt read your data, visit {} and enter the code {}",
+            style(&device_code.verification_uri).bold(),
+            style(&device_code.user_code).bold()
+        );
+        let mut params = vec![
+            ("client_id", self.client_id),
+            ("grant_type", DEVICE_CODE_GRANT),
+            ("device_code", device_code.device_code.as_str()),
+        ];
+        params.extend(self.client_secret.map(|secret| ("client_secret", secret)));
+        let mut interval = device_code.interval;
+        loop {
+            tokio::time::sleep(Duration::from_secs(interval)).await;
+            let response = request_token(client, self.token_url, &params).await?;
+            match (response.access_token, response.error.as_deref()) {
+                (Some(access_token), _) => {
+                    if let Some(refresh_token) = response.refresh_token {
+                        auth::store_credential(self.credential, &refresh_token)?;
+                    }
+                    return Ok(access_token);
+                }
+                (None, Some("authorization_pending")) => {}
+                (None, Some("slow_down")) => interval += 5,
+                (None, error) => bail!(
+                    "Authorization failed: {}",
+                    response
+                        .error_description
+                        .as_deref()
+                        .or(error)
+                        .unwrap_or("no token received")
+                ),
+            }
+        }
+    }
+}
+
+///How jt is registered with Jira as an OAuth 2.0 client, for signing in through the browser rather
+///than with a personal access token
+#[derive(Serialize, Deserialize, Clone)]
+pub struct OauthConfig {
+    pub client_id: String,
+    pub client_secret: Option<String>,
+    ///Space separated scopes requested, `WRITE` letting jt log and submit time on Jira Data Center
+    #[serde(default = "default_scope")]
+    pub scope: String,
+    ///Local port Jira redirects back to, registered as `http://localhost:<port>/callback`
+    #[serde(default = "default_redirect_port")]
+    pub redirect_port: u16,
+    ///Authorization endpoint, defaulting to Jira Data Center's
+    pub authorize_url: Option<String>,
+    ///Token endpoint, defaulting to Jira Data Center's
+    pub token_url: Option<String>,
+}
+
+fn default_scope() -> String {
+    String::from("WRITE")
+}
+
+fn default_redirect_port() -> u16 {
+    8765
+}
+
+///An OAuth client authorized with the authorization code grant and PKCE, Jira sending the user's
+///browser back to a server jt briefly listens with on localhost
+pub struct CodeFlow<'a> {
+    ///Keyring name the refresh token is stored under
+    pub credential: &'a str,
+    pub config: &'a OauthConfig,
+    ///Base URL of the Jira instance, below which the endpoints are found by default
+    pub base: &'a Url,
+    ///Proxy and certificates to reach Jira with, as for the API itself
+    pub http_options: &'a HttpOptions,
+}
+
+impl CodeFlow<'_> {
+    ///An access token, from the stored refresh token if it is still good and otherwise by asking
+    ///the user to sign in to Jira in their browser
+    pub async fn access_token(&self) -> Result<String> {
+        let client = client::http_client(self.http_options)?;
+        let refreshed = refresh(
+            &client,
+            self.token_url()?.as_str(),
+            self.credential,
+            &self.config.client_id,
+            self.config.client_secret.as_deref(),
+        )
+        .await?;
+        match refreshed {
+            Some(access_token) => Ok(access_token),
+            None => self.authorize().await,
+        }
+    }
+
+    ///Sign in through the browser, storing the refresh token received for later runs
+    pub async fn authorize(&self) -> Result<String> {
+        let redirect_uri = format!("http://localhost:{}/callback", self.config.redirect_port);
+        let listener =
+            TcpListener::bind(("127.0.0.1", self.config.redirect_port)).with_context(|| {
+                format!(
+                    "Unable to listen for the sign-in redirect on port {}",
+                    self.config.redirect_port
+                )
+            })?;
+        let verifier = random_string(64);
+        let state = random_string(32);
+        let challenge = URL_SAFE_NO_PAD.encode(Sha256::digest(verifier.as_bytes()));
+        let mut authorize_url = endpoint_url(
+            self.config.authorize_url.as_deref(),
+            self.base,
+            "rest/oauth2/latest/authorize",
+        )?;
+        authorize_url
+            .query_pairs_mut()
+            .append_pair("client_id", &self.config.client_id)
+            .append_pair("redirect_uri", &redirect_uri)
+            .append_pair("response_type", "code")
+            .append_pair("scope", &self.config.scope)
+            .append_pair("state", &state)
+            .append_pair("code_challenge", &challenge)
+            .append_pair("code_challenge_method", "S256");
+        println!(
+            "To sign in to Jira, visit {}",
+            style(authorize_url.as_str()).bold()
+        );
+        let code = receive_code(&listener, &state)?;
+        let mut params = vec![
+            ("client_id", self.config.client_id.as_str()),
+            ("grant_type", "authorization_code"),
+            ("code", code.as_str()),
+            ("redirect_uri", redirect_uri.as_str()),
+            ("code_verifier", verifier.as_str()),
+        ];
+        params.extend(
+            self.config
+                .client_secret
+                .as_deref()
+                .map(|secret| ("client_secret", secret)),
+        );
+        let client = client::http_client(self.http_options)?;
+        let response = request_token(&client, self.token_url()?.as_str(), &params).await?;
+        match response.access_token {
+            Some(access_token) => {
+                if let Some(refresh_token) = response.refresh_token {
+                    auth::store_credential(self.credential, &refresh_token)?;
+                }
+                Ok(access_token)
+            }
+            None => bail!(
+                "Authorization failed: {}",
+                response
+                    .error_description
+                    .or(response.error)
+                    .unwrap_or_else(|| String::from("no token received"))
+            ),
+        }
+    }
+
+    fn token_url(&self) -> Result<Url> {
+        endpoint_url(
+            self.config.token_url.as_deref(),
+            self.base,
+            "rest/oauth2/latest/token",
+        )
+    }
+}
+
+fn endpoint_url(configured: Option<&str>, base: &Url, default_path: &str) -> Result<Url> {
+    match configured {
+        Some(url) => Url::parse(url).with_context(|| format!("Invalid OAuth endpoint {url}")),
+        None => Ok(base.join(default_path)?),
+    }
+}
+
+fn random_string(len: usize) -> String {
+    rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(len)
+        .map(char::from)
+        .collect()
+}
+
+///Wait for the browser to be sent back with an authorization code, ignoring stray requests such as
+///for a favicon
+fn receive_code(listener: &TcpListener, state: &str) -> Result<String> {
+    for stream in listener.incoming() {
+        let mut stream = stream?;
+        let mut request_line = String::new();
+        BufReader::new(&stream).read_line(&mut request_line)?;
+        let Some(target) = request_line.split_whitespace().nth(1) else {
+            continue;
+        };
+        let url = Url::parse("http://localhost")?.join(target)?;
+        let param = |name: &str| {
+            url.query_pairs()
+                .find(|(key, _)| key == name)
+                .map(|(_, value)| value.into_owned())
+        };
+        let result = match (param("code"), param("error")) {
+            (_, Some(error)) => Err(anyhow!(
+                "Authorization failed: {}",
+                param("error_description").unwrap_or(error)
+            )),
+            (Some(_), _) if param("state").as_deref() != Some(state) => Err(anyhow!(
+                "Authorization failed: the response did not match the request"
+            )),
+            (Some(code), _) => Ok(code),
+            (None, None) => {
+                write!(
+                    stream,
+                    "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\n\r\n"
+                )?;
+                continue;
+            }
+        };
+        let message = match &result {
+            Ok(_) => "jt has been authorized, this tab can be closed",
+            Err(_) => "jt could not sign in to Jira, see the terminal for details",
+        };
+        write!(
+            stream,
+            "HTTP/1.1 200 OK\r\nContent-Type: text/plain\r\nContent-Length: {}\r\n\r\n{message}",
+            message.len()
+        )?;
+        return result;
+    }
+    bail!("Stopped listening for the sign-in redirect")
+}
+
+///A new access token from the refresh token stored under `credential`, storing any replacement
+///refresh token, or none if there is no stored token or it is no longer accepted
+async fn refresh(
+    client: &Client,
+    token_url: &str,
+    credential: &str,
+    client_id: &str,
+    client_secret: Option<&str>,
+) -> Result<Option<String>> {
+    let Some(refresh_token) = auth::load_credential(credential) else {
+        return Ok(None);
+    };
+    let mut params = vec![
+        ("client_id", client_id),
+        ("grant_type", "refresh_token"),
+        ("refresh_token", refresh_token.as_str()),
+    ];
+    params.extend(client_secret.map(|secret| ("client_secret", secret)));
+    match request_token(client, token_url, &params).await? {
+        TokenResponse {
+            access_token: Some(access_token),
+            refresh_token,
+            ..
+        } => {
+            if let Some(refresh_token) = refresh_token {
+                auth::store_credential(credential, &refresh_token)?;
+            }
+            Ok(Some(access_token))
+        }
+        response => {
+            log::info!(
+                "Stored {credential} no longer accepted: {}",
+                response.error.unwrap_or_default()
+            );
+            Ok(None)
+        }
+    }
+}
+
+///Token endpoints report errors such as a pending authorization in the body of a 400 response
+async fn request_token(
+    client: &Client,
+    token_url: &str,
+    params: &[(&str, &str)],
+) -> Result<TokenResponse> {
+    client
+        .post(token_url)
+        .form(params)
+        .send()
+        .await?
+        .json()
+        .await
+        .context("Unexpected response from token endpoint")
+}
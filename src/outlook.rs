@@ -0,0 +1,160 @@
+use anyhow::{Context, Result};
+use chrono::{NaiveDate, NaiveDateTime, TimeDelta};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    import::{self, Event},
+    oauth::DeviceFlow,
+    plan::Plan,
+    week::Timezone,
+};
+
+const CALENDAR_VIEW_API: &str = "https://graph.microsoft.com/v1.0/me/calendarView";
+
+///An app registered in Microsoft Entra ID allowing public client flows, and the task to log
+///meetings against
+#[derive(Serialize, Deserialize)]
+pub struct OutlookConfig {
+    pub client_id: String,
+    ///Directory the app is registered in, defaulting to any work or school account's
+    #[serde(default = "default_tenant")]
+    pub tenant: String,
+    ///Task meetings are logged against
+    pub task: String,
+}
+
+fn default_tenant() -> String {
+    String::from("organizations")
+}
+
+#[derive(Deserialize)]
+struct EventPage {
+    #[serde(default)]
+    value: Vec<GraphEvent>,
+    #[serde(rename = "@odata.nextLink")]
+    next_link: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GraphEvent {
+    #[serde(default)]
+    subject: String,
+    #[serde(default)]
+    is_all_day: bool,
+    #[serde(default)]
+    is_cancelled: bool,
+    show_as: Option<String>,
+    response_status: Option<ResponseStatus>,
+    start: GraphTime,
+    end: GraphTime,
+}
+
+#[derive(Deserialize)]
+struct ResponseStatus {
+    response: String,
+}
+
+///A time in UTC, as asked for with the `Prefer` header, without an offset
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GraphTime {
+    date_time: String,
+}
+
+impl GraphTime {
+    fn local_time(&self, timezone: Timezone) -> Option<NaiveDateTime> {
+        NaiveDateTime::parse_from_str(&self.date_time, "%Y-%m-%dT%H:%M:%S%.f")
+            .ok()
+            .map(|time| timezone.local_time(time.and_utc()))
+    }
+}
+
+impl GraphEvent {
+    ///A timed meeting the user organized or accepted and is busy for
+    fn into_meeting(self, timezone: Timezone) -> Option<Event> {
+        let accepted = self
+            .response_status
+            .as_ref()
+            .is_some_and(|status| matches!(status.response.as_str(), "organizer" | "accepted"));
+        let meeting = accepted
+            && !self.is_all_day
+            && !self.is_cancelled
+            && self.show_as.as_deref() != Some("free");
+        meeting.then_some(Event {
+            start: self.start.local_time(timezone)?,
+            end: self.end.local_time(timezone)?,
+            title: self.subject,
+        })
+    }
+}
+
+///A plan logging the accepted meetings in the user's calendar between two days inclusive against
+///the configured task
+pub async fn import(
+    config: &OutlookConfig,
+    from: NaiveDate,
+    to: NaiveDate,
+    timezone: Timezone,
+) -> Result<Plan> {
+    let authority = format!(
+        "https://login.microsoftonline.com/{}/oauth2/v2.0",
+        config.tenant
+    );
+    let flow = DeviceFlow {
+        credential: "outlook-calendar",
+        device_code_url: &format!("{authority}/devicecode"),
+        token_url: &format!("{authority}/token"),
+        client_id: &config.client_id,
+        client_secret: None,
+        scope: "Calendars.Read offline_access",
+    };
+    let token = flow.access_token().await?;
+    let events = calendar_events(&token, from, to, timezone).await?;
+    Ok(import::events_plan(events, from, to, |_| {
+        Some(config.task.clone())
+    }))
+}
+
+///Events from a day either side of the range, as their days depend on the configured timezone
+async fn calendar_events(
+    token: &str,
+    from: NaiveDate,
+    to: NaiveDate,
+    timezone: Timezone,
+) -> Result<Vec<Event>> {
+    let client = Client::new();
+    let start = format!("{}T00:00:00Z", from - TimeDelta::days(1));
+    let end = format!("{}T00:00:00Z", to + TimeDelta::days(2));
+    let mut request = client.get(CALENDAR_VIEW_API).query(&[
+        ("startDateTime", start.as_str()),
+        ("endDateTime", end.as_str()),
+        (
+            "$select",
+            "subject,isAllDay,isCancelled,showAs,responseStatus,start,end",
+        ),
+        ("$top", "100"),
+    ]);
+    let mut events = Vec::new();
+    loop {
+        let page: EventPage = request
+            .bearer_auth(token)
+            .header("Prefer", "outlook.timezone=\"UTC\"")
+            .send()
+            .await?
+            .error_for_status()
+            .context("Unable to read Outlook calendar")?
+            .json()
+            .await?;
+        events.extend(
+            page.value
+                .into_iter()
+                .filter_map(|event| event.into_meeting(timezone)),
+        );
+        match page.next_link {
+            Some(next_link) => request = client.get(next_link),
+            None => return Ok(events),
+        }
+    }
+}
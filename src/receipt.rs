@@ -0,0 +1,35 @@
+use anyhow::Result;
+use chrono::{DateTime, Local, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+use crate::state;
+
+const RECEIPTS: &str = "submission-receipts.json";
+
+///A record of a timesheet submission as Tempo reported it immediately afterwards
+#[derive(Serialize, Deserialize)]
+pub struct Receipt {
+    pub worker: String,
+    ///Monday of the week submitted
+    pub week: NaiveDate,
+    pub submitted_at: DateTime<Local>,
+    pub reviewer: String,
+    pub status: String,
+}
+
+///Store a receipt, replacing any earlier one for the same worker and period
+pub fn record(receipt: Receipt) -> Result<()> {
+    let mut receipts: Vec<Receipt> = state::load_data(RECEIPTS).unwrap_or_default();
+    receipts.retain(|r| r.worker != receipt.worker || r.week != receipt.week);
+    receipts.push(receipt);
+    receipts.sort_by_key(|r| r.week);
+    state::write_data(RECEIPTS, &receipts)
+}
+
+pub fn receipts_for(worker: &str) -> Vec<Receipt> {
+    let receipts: Vec<Receipt> = state::load_data(RECEIPTS).unwrap_or_default();
+    receipts
+        .into_iter()
+        .filter(|r| r.worker == worker)
+        .collect()
+}
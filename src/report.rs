@@ -0,0 +1,281 @@
+use std::{collections::BTreeMap, fmt::Write, path::Path};
+
+use anyhow::{Context, Result};
+
+use chrono::{NaiveDate, TimeDelta};
+use clap::ValueEnum;
+use console::{pad_str, style, Alignment, Color};
+use serde::Serialize;
+
+use crate::duration::format_duration;
+
+const PROJECT_COLOURS: [Color; 6] = [
+    Color::Cyan,
+    Color::Magenta,
+    Color::Yellow,
+    Color::Blue,
+    Color::Green,
+    Color::Red,
+];
+const KEY_WIDTH: usize = 14;
+const CELL_WIDTH: usize = 10;
+const MINUTES_PER_BLOCK: i64 = 60;
+
+#[derive(ValueEnum, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    Table,
+    Blocks,
+    Json,
+}
+
+///Time spent per task per day over a range of days
+#[derive(Serialize)]
+pub struct WeekGrid {
+    pub days: Vec<NaiveDate>,
+    pub rows: Vec<GridRow>,
+}
+
+#[derive(Serialize)]
+pub struct GridRow {
+    pub key: String,
+    pub project: String,
+    #[serde(serialize_with = "serialize_minutes")]
+    pub cells: Vec<TimeDelta>,
+}
+
+impl GridRow {
+    pub fn total(&self) -> TimeDelta {
+        self.cells.iter().sum()
+    }
+}
+
+fn serialize_minutes<S>(cells: &[TimeDelta], s: S) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    s.collect_seq(cells.iter().map(TimeDelta::num_minutes))
+}
+
+impl WeekGrid {
+    pub fn new<I>(days: Vec<NaiveDate>, entries: I) -> WeekGrid
+    where
+        I: IntoIterator<Item = (NaiveDate, String, TimeDelta)>,
+    {
+        let mut rows: Vec<GridRow> = Vec::new();
+        for (day, key, time_spent) in entries {
+            let Some(column) = days.iter().position(|d| *d == day) else {
+                continue;
+            };
+            let row = match rows.iter().position(|row| row.key == key) {
+                Some(row) => &mut rows[row],
+                None => {
+                    rows.push(GridRow {
+                        project: project_of(&key).to_owned(),
+                        key,
+                        cells: vec![TimeDelta::zero(); days.len()],
+                    });
+                    rows.last_mut().unwrap()
+                }
+            };
+            row.cells[column] += time_spent;
+        }
+        rows.sort_by(|a, b| a.project.cmp(&b.project));
+        WeekGrid { days, rows }
+    }
+
+    pub fn day_totals(&self) -> Vec<TimeDelta> {
+        (0..self.days.len())
+            .map(|column| self.rows.iter().map(|row| row.cells[column]).sum())
+            .collect()
+    }
+
+    fn colour_of(&self, project: &str) -> Color {
+        let mut projects: Vec<&str> = self.rows.iter().map(|row| row.project.as_str()).collect();
+        projects.dedup();
+        let index = projects.iter().position(|p| *p == project).unwrap_or(0);
+        PROJECT_COLOURS[index % PROJECT_COLOURS.len()]
+    }
+}
+
+pub fn project_of(key: &str) -> &str {
+    key.rsplit_once('-')
+        .map(|(project, _)| project)
+        .unwrap_or(key)
+}
+
+pub fn render(grid: &WeekGrid, format: OutputFormat) -> String {
+    match format {
+        OutputFormat::Table => render_table(grid, false),
+        OutputFormat::Blocks => render_table(grid, true),
+        OutputFormat::Json => serde_json::to_string_pretty(grid).unwrap(),
+    }
+}
+
+fn render_table(grid: &WeekGrid, blocks: bool) -> String {
+    let mut out = String::new();
+    let cell = |text: &str| pad_str(text, CELL_WIDTH, Alignment::Right, None).into_owned();
+
+    let mut header = pad_str("", KEY_WIDTH, Alignment::Left, None).into_owned();
+    for day in &grid.days {
+        header.push_str(&cell(&day.format("%a %-d").to_string()));
+    }
+    header.push_str(&cell("Total"));
+    writeln!(out, "{}", style(header).bold()).unwrap();
+
+    for row in &grid.rows {
+        let colour = grid.colour_of(&row.project);
+        let key = style(&row.key).fg(colour).to_string();
+        write!(out, "{}", pad_str(&key, KEY_WIDTH, Alignment::Left, None)).unwrap();
+        for time_spent in &row.cells {
+            let text = if time_spent.is_zero() {
+                String::from("·")
+            } else if blocks {
+                let count = (time_spent.num_minutes() / MINUTES_PER_BLOCK).max(1) as usize;
+                let bar = "█".repeat(count.min(CELL_WIDTH - 1));
+                style(bar).fg(colour).to_string()
+            } else {
+                style(format_duration(*time_spent)).fg(colour).to_string()
+            };
+            write!(out, "{}", cell(&text)).unwrap();
+        }
+        writeln!(out, "{}", cell(&format_duration(row.total()))).unwrap();
+    }
+
+    let totals = grid.day_totals();
+    let mut footer = pad_str("Total", KEY_WIDTH, Alignment::Left, None).into_owned();
+    for total in &totals {
+        footer.push_str(&cell(&format_duration(*total)));
+    }
+    footer.push_str(&cell(&format_duration(totals.iter().sum())));
+    writeln!(out, "{}", style(footer).bold()).unwrap();
+    out
+}
+
+///Time spent per issue over a calendar month, as needed for invoicing
+#[derive(Serialize)]
+pub struct MonthSummary {
+    ///The month, eg "2024-05"
+    pub month: String,
+    pub rows: Vec<MonthRow>,
+}
+
+#[derive(Serialize)]
+pub struct MonthRow {
+    pub project: String,
+    pub issue: String,
+    #[serde(rename = "minutes", serialize_with = "serialize_delta_minutes")]
+    pub time_spent: TimeDelta,
+}
+
+fn serialize_delta_minutes<S>(time_spent: &TimeDelta, s: S) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    s.serialize_i64(time_spent.num_minutes())
+}
+
+///A row of the CSV export, with hours as a decimal for spreadsheets
+#[derive(Serialize)]
+struct CsvRow<'a> {
+    project: &'a str,
+    issue: &'a str,
+    hours: String,
+    minutes: i64,
+}
+
+impl MonthSummary {
+    pub fn new<I>(first_day: NaiveDate, entries: I) -> MonthSummary
+    where
+        I: IntoIterator<Item = (String, TimeDelta)>,
+    {
+        let mut totals: BTreeMap<(String, String), TimeDelta> = BTreeMap::new();
+        for (key, time_spent) in entries {
+            *totals
+                .entry((project_of(&key).to_owned(), key))
+                .or_default() += time_spent;
+        }
+        MonthSummary {
+            month: first_day.format("%Y-%m").to_string(),
+            rows: totals
+                .into_iter()
+                .map(|((project, issue), time_spent)| MonthRow {
+                    project,
+                    issue,
+                    time_spent,
+                })
+                .collect(),
+        }
+    }
+
+    pub fn total(&self) -> TimeDelta {
+        self.rows.iter().map(|row| row.time_spent).sum()
+    }
+
+    ///Write a row per issue to a CSV file
+    pub fn write_csv(&self, path: &Path) -> Result<()> {
+        let mut writer = csv::Writer::from_path(path)
+            .with_context(|| format!("Unable to create {}", path.display()))?;
+        for row in &self.rows {
+            writer.serialize(CsvRow {
+                project: &row.project,
+                issue: &row.issue,
+                hours: format!("{:.2}", row.time_spent.num_minutes() as f64 / 60.0),
+                minutes: row.time_spent.num_minutes(),
+            })?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+pub fn render_month(summary: &MonthSummary, format: OutputFormat) -> String {
+    if format == OutputFormat::Json {
+        return serde_json::to_string_pretty(summary).unwrap();
+    }
+    let mut out = String::new();
+    let cell = |text: &str| pad_str(text, CELL_WIDTH, Alignment::Right, None).into_owned();
+    let mut projects: Vec<&str> = summary
+        .rows
+        .iter()
+        .map(|row| row.project.as_str())
+        .collect();
+    projects.dedup();
+    for (index, project) in projects.into_iter().enumerate() {
+        let colour = PROJECT_COLOURS[index % PROJECT_COLOURS.len()];
+        let rows: Vec<&MonthRow> = summary
+            .rows
+            .iter()
+            .filter(|row| row.project == project)
+            .collect();
+        for row in &rows {
+            let key = style(&row.issue).fg(colour).to_string();
+            writeln!(
+                out,
+                "{}{}",
+                pad_str(&key, KEY_WIDTH, Alignment::Left, None),
+                cell(&format_duration(row.time_spent))
+            )
+            .unwrap();
+        }
+        let subtotal: TimeDelta = rows.iter().map(|row| row.time_spent).sum();
+        let label = pad_str(project, KEY_WIDTH, Alignment::Left, None).into_owned();
+        writeln!(
+            out,
+            "{}",
+            style(format!("{label}{}", cell(&format_duration(subtotal)))).bold()
+        )
+        .unwrap();
+    }
+    let label = pad_str("Total", KEY_WIDTH, Alignment::Left, None).into_owned();
+    writeln!(
+        out,
+        "{}",
+        style(format!(
+            "{label}{}",
+            cell(&format_duration(summary.total()))
+        ))
+        .bold()
+    )
+    .unwrap();
+    out
+}
@@ -0,0 +1,83 @@
+use std::collections::HashMap;
+
+use anyhow::Result;
+use jt_core::options::WorkAttribute;
+use serde_json::Value;
+
+use crate::{
+    client::{Issue, JtClient},
+    config::ServiceDesk,
+};
+
+///Attributes chosen by the service desk rules for each Jira Service Management request among the
+///issues being logged against, by issue key
+pub async fn request_attributes(
+    client: &JtClient,
+    service_desk: &ServiceDesk,
+    issues: &[&Issue],
+) -> Result<HashMap<String, Vec<WorkAttribute>>> {
+    let mut attributes = HashMap::new();
+    if service_desk.rules.is_empty() {
+        return Ok(attributes);
+    }
+    let by_organization = service_desk
+        .rules
+        .iter()
+        .any(|rule| rule.organization.is_some());
+    let by_customer = service_desk
+        .rules
+        .iter()
+        .any(|rule| rule.customer.is_some());
+    let organization_field = match &service_desk.organization_field {
+        Some(field) => Some(field.clone()),
+        None if by_organization => client.find_organization_field().await?,
+        None => None,
+    };
+    if by_organization && organization_field.is_none() {
+        log::warn!("No organisations field found, service desk rules can only match customers");
+    }
+
+    for issue in issues {
+        if attributes.contains_key(&issue.key) {
+            continue;
+        }
+        let organizations = match &organization_field {
+            Some(field) if issue.fields.contains_key(field) => organization_names(issue, field),
+            Some(field) => organization_names(&client.get_issue(&issue.key).await?, field),
+            None => Vec::new(),
+        };
+        let customer = if by_customer {
+            client.get_request_customer(&issue.key).await?
+        } else {
+            None
+        };
+        let rule = service_desk
+            .rules
+            .iter()
+            .find(|rule| rule.matches(&organizations, customer.as_ref()));
+        if let Some(rule) = rule {
+            log::info!(
+                "Service desk rule for {} applies to {}",
+                rule.organization
+                    .as_deref()
+                    .or(rule.customer.as_deref())
+                    .unwrap_or_default(),
+                issue.key
+            );
+            attributes.insert(issue.key.clone(), rule.attributes.clone());
+        }
+    }
+    Ok(attributes)
+}
+
+///Names of the organisations a request belongs to
+fn organization_names(issue: &Issue, field: &str) -> Vec<String> {
+    match issue.fields.get(field) {
+        Some(Value::Array(organizations)) => organizations
+            .iter()
+            .filter_map(|organization| organization.get("name")?.as_str())
+            .map(str::to_owned)
+            .collect(),
+        _ => Vec::new(),
+    }
+}
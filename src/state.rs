@@ -0,0 +1,185 @@
+use std::{fs, path::PathBuf, sync::OnceLock};
+
+use anyhow::Result;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+const STATE_DIR_NAME: &str = "jt";
+
+///Where state kept between runs is stored, as chosen by `storage` in the configuration
+#[derive(Serialize, Deserialize, Clone, Copy, Default, PartialEq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum StorageKind {
+    ///A JSON file per value, in the cache and data directories
+    #[default]
+    File,
+    ///A single SQLite database in the data directory, safe to share between running instances
+    Sqlite,
+}
+
+static STORAGE: OnceLock<StorageKind> = OnceLock::new();
+
+///Store state in the given kind of storage for the rest of the run
+pub fn use_storage(kind: StorageKind) -> Result<()> {
+    #[cfg(not(feature = "sqlite"))]
+    if kind == StorageKind::Sqlite {
+        anyhow::bail!("SQLite storage is not available, jt must be built with --features sqlite");
+    }
+    if STORAGE.get_or_init(|| kind) != &kind {
+        log::warn!("Storage already chosen for this run, ignoring {kind:?}");
+    }
+    Ok(())
+}
+
+#[derive(Clone, Copy)]
+enum Area {
+    ///Values that can be fetched again if lost
+    Cache,
+    ///Values expected to be kept between runs
+    Data,
+}
+
+trait Storage {
+    fn load(&self, area: Area, name: &str) -> Result<Option<String>>;
+    fn write(&self, area: Area, name: &str, content: &str) -> Result<()>;
+}
+
+fn storage() -> Box<dyn Storage> {
+    match STORAGE.get().copied().unwrap_or_default() {
+        StorageKind::File => Box::new(FileStorage),
+        #[cfg(feature = "sqlite")]
+        StorageKind::Sqlite => Box::new(sqlite::SqliteStorage),
+        #[cfg(not(feature = "sqlite"))]
+        StorageKind::Sqlite => unreachable!("SQLite storage is rejected by use_storage"),
+    }
+}
+
+struct FileStorage;
+
+impl FileStorage {
+    fn location(area: Area, name: &str) -> PathBuf {
+        let dir = match area {
+            Area::Cache => dirs::cache_dir().expect("Unable to determine cache directory"),
+            Area::Data => dirs::data_dir().expect("Unable to determine data directory"),
+        };
+        dir.join(STATE_DIR_NAME).join(name)
+    }
+}
+
+impl Storage for FileStorage {
+    fn load(&self, area: Area, name: &str) -> Result<Option<String>> {
+        match fs::read_to_string(Self::location(area, name)) {
+            Ok(content) => Ok(Some(content)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn write(&self, area: Area, name: &str, content: &str) -> Result<()> {
+        let location = Self::location(area, name);
+        if let Some(dir) = location.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(location, content).map_err(|e| e.into())
+    }
+}
+
+#[cfg(feature = "sqlite")]
+mod sqlite {
+    use std::{fs, time::Duration};
+
+    use anyhow::Result;
+    use rusqlite::{params, Connection, OptionalExtension};
+
+    use super::{Area, FileStorage, Storage, STATE_DIR_NAME};
+
+    const DATABASE_NAME: &str = "state.sqlite3";
+
+    pub struct SqliteStorage;
+
+    impl SqliteStorage {
+        fn connect() -> Result<Connection> {
+            let dir = dirs::data_dir()
+                .expect("Unable to determine data directory")
+                .join(STATE_DIR_NAME);
+            fs::create_dir_all(&dir)?;
+            let connection = Connection::open(dir.join(DATABASE_NAME))?;
+            connection.busy_timeout(Duration::from_secs(5))?;
+            connection.execute(
+                "CREATE TABLE IF NOT EXISTS state (
+                    area TEXT NOT NULL,
+                    name TEXT NOT NULL,
+                    content TEXT NOT NULL,
+                    PRIMARY KEY (area, name)
+                )",
+                [],
+            )?;
+            Ok(connection)
+        }
+    }
+
+    fn area_name(area: Area) -> &'static str {
+        match area {
+            Area::Cache => "cache",
+            Area::Data => "data",
+        }
+    }
+
+    impl Storage for SqliteStorage {
+        ///Values not yet in the database are read from any file left by file storage, so that
+        ///switching storage keeps existing state
+        fn load(&self, area: Area, name: &str) -> Result<Option<String>> {
+            let stored = Self::connect()?
+                .query_row(
+                    "SELECT content FROM state WHERE area = ?1 AND name = ?2",
+                    params![area_name(area), name],
+                    |row| row.get(0),
+                )
+                .optional()?;
+            match stored {
+                Some(content) => Ok(Some(content)),
+                None => FileStorage.load(area, name),
+            }
+        }
+
+        fn write(&self, area: Area, name: &str, content: &str) -> Result<()> {
+            Self::connect()?.execute(
+                "INSERT INTO state (area, name, content) VALUES (?1, ?2, ?3)
+                 ON CONFLICT (area, name) DO UPDATE SET content = excluded.content",
+                params![area_name(area), name, content],
+            )?;
+            Ok(())
+        }
+    }
+}
+
+fn load<T: DeserializeOwned>(area: Area, name: &str) -> Option<T> {
+    let content = storage()
+        .load(area, name)
+        .map_err(|e| log::warn!("Unable to read {name}: {e}"))
+        .ok()??;
+    serde_json::from_str(&content)
+        .map_err(|e| log::warn!("Ignoring unreadable {name}: {e}"))
+        .ok()
+}
+
+fn write<T: Serialize>(area: Area, name: &str, value: &T) -> Result<()> {
+    storage().write(area, name, &serde_json::to_string(value)?)
+}
+
+///Read a previously cached value, treating a missing or unreadable cache as empty
+pub fn load_cache<T: DeserializeOwned>(name: &str) -> Option<T> {
+    load(Area::Cache, name)
+}
+
+pub fn write_cache<T: Serialize>(name: &str, value: &T) -> Result<()> {
+    write(Area::Cache, name, value)
+}
+
+///Read persistent data that, unlike the cache, is expected to be kept between runs
+pub fn load_data<T: DeserializeOwned>(name: &str) -> Option<T> {
+    load(Area::Data, name)
+}
+
+pub fn write_data<T: Serialize>(name: &str, value: &T) -> Result<()> {
+    write(Area::Data, name, value)
+}
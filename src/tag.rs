@@ -0,0 +1,37 @@
+use std::collections::HashMap;
+
+use jt_core::options::WorkAttribute;
+
+use crate::config::TagMapping;
+
+///Attributes set by the configured mappings of a set of tags
+pub fn tag_attributes(
+    tags: &[String],
+    mappings: &HashMap<String, TagMapping>,
+) -> Vec<WorkAttribute> {
+    tags.iter()
+        .filter_map(|tag| mappings.get(tag))
+        .flat_map(|mapping| mapping.attributes.iter().cloned())
+        .collect()
+}
+
+///A comment with the suffixes of any mapped tags appended
+pub fn tag_comment(
+    comment: Option<String>,
+    tags: &[String],
+    mappings: &HashMap<String, TagMapping>,
+) -> Option<String> {
+    let suffixes = tags
+        .iter()
+        .filter_map(|tag| mappings.get(tag)?.comment.as_deref());
+    let parts: Vec<&str> = comment.as_deref().into_iter().chain(suffixes).collect();
+    Some(parts.join(" ")).filter(|comment| !comment.is_empty())
+}
+
+///Name under which work with a tag is totalled in reports
+pub fn tag_group<'a>(tag: &'a str, mappings: &'a HashMap<String, TagMapping>) -> &'a str {
+    mappings
+        .get(tag)
+        .and_then(|mapping| mapping.group.as_deref())
+        .unwrap_or(tag)
+}
@@ -0,0 +1,202 @@
+use std::str::FromStr;
+
+use anyhow::{bail, Context, Result};
+use serde_json::Value;
+
+///A value composed from an issue's fields, eg `{fields.project.key}-{fields.issuetype.name | upper}`
+pub struct Template {
+    parts: Vec<Part>,
+}
+
+enum Part {
+    Literal(String),
+    Field {
+        path: Vec<String>,
+        filters: Vec<Filter>,
+    },
+}
+
+enum Filter {
+    Upper,
+    Lower,
+    Trim,
+    Replace { from: String, to: String },
+}
+
+impl Filter {
+    fn apply(&self, value: String) -> String {
+        match self {
+            Filter::Upper => value.to_uppercase(),
+            Filter::Lower => value.to_lowercase(),
+            Filter::Trim => value.trim().to_owned(),
+            Filter::Replace { from, to } => value.replace(from.as_str(), to),
+        }
+    }
+}
+
+impl FromStr for Filter {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let mut args = s.split(':');
+        let filter = match (args.next().unwrap_or_default(), args.next(), args.next()) {
+            ("upper", None, _) => Filter::Upper,
+            ("lower", None, _) => Filter::Lower,
+            ("trim", None, _) => Filter::Trim,
+            ("replace", Some(from), to) if !from.is_empty() => Filter::Replace {
+                from: from.to_owned(),
+                to: to.unwrap_or_default().to_owned(),
+            },
+            _ => bail!("Unknown filter \"{s}\", expected upper, lower, trim or replace:from:to"),
+        };
+        if args.next().is_some() {
+            bail!("Too many arguments to filter \"{s}\"");
+        }
+        Ok(filter)
+    }
+}
+
+impl Template {
+    ///Whether a configured value is a template rather than a JSON pointer
+    pub fn is_template(value: &str) -> bool {
+        value.contains('{')
+    }
+
+    ///Issue fields the template reads, so that they can be requested when searching
+    pub fn fields(&self) -> impl Iterator<Item = &str> {
+        self.parts.iter().filter_map(|part| match part {
+            Part::Field { path, .. } if path.len() > 1 && path[0] == "fields" => {
+                Some(path[1].as_str())
+            }
+            _ => None,
+        })
+    }
+
+    ///The template filled in from an issue as JSON, or None if a field it reads is missing
+    pub fn render(&self, issue: &Value) -> Result<Option<String>> {
+        let mut rendered = String::new();
+        for part in &self.parts {
+            match part {
+                Part::Literal(text) => rendered.push_str(text),
+                Part::Field { path, filters } => {
+                    let value = path.iter().try_fold(issue, |value, segment| match value {
+                        Value::Array(values) => values.get(segment.parse::<usize>().ok()?),
+                        _ => value.get(segment),
+                    });
+                    let value = match value {
+                        None | Some(Value::Null) => return Ok(None),
+                        Some(Value::String(text)) => text.clone(),
+                        Some(Value::Number(number)) => number.to_string(),
+                        Some(Value::Bool(flag)) => flag.to_string(),
+                        Some(_) => bail!("{} is not a single value", path.join(".")),
+                    };
+                    let value = filters
+                        .iter()
+                        .fold(value, |value, filter| filter.apply(value));
+                    rendered.push_str(&value);
+                }
+            }
+        }
+        Ok(Some(rendered))
+    }
+}
+
+impl FromStr for Template {
+    type Err = anyhow::Error;
+
+    ///Parse a template, in which `{{` and `}}` stand for literal braces
+    fn from_str(s: &str) -> Result<Self> {
+        let mut parts = Vec::new();
+        let mut literal = String::new();
+        let mut chars = s.chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                '{' if chars.peek() == Some(&'{') => {
+                    chars.next();
+                    literal.push('{');
+                }
+                '}' if chars.peek() == Some(&'}') => {
+                    chars.next();
+                    literal.push('}');
+                }
+                '{' => {
+                    let mut expression = String::new();
+                    loop {
+                        match chars.next() {
+                            Some('}') => break,
+                            Some(c) => expression.push(c),
+                            None => bail!("Unclosed {{ in \"{s}\""),
+                        }
+                    }
+                    if !literal.is_empty() {
+                        parts.push(Part::Literal(std::mem::take(&mut literal)));
+                    }
+                    parts.push(parse_field(&expression).with_context(|| {
+                        format!("Invalid expression \"{{{expression}}}\" in \"{s}\"")
+                    })?);
+                }
+                '}' => bail!("Unmatched }} in \"{s}\", write }}}} for a literal brace"),
+                _ => literal.push(c),
+            }
+        }
+        if !literal.is_empty() {
+            parts.push(Part::Literal(literal));
+        }
+        Ok(Template { parts })
+    }
+}
+
+fn parse_field(expression: &str) -> Result<Part> {
+    let mut pieces = expression.split('|').map(str::trim);
+    let path = pieces.next().unwrap_or_default();
+    if path.is_empty() || path.split('.').any(str::is_empty) {
+        bail!("Expected a field path such as fields.project.key");
+    }
+    Ok(Part::Field {
+        path: path.split('.').map(String::from).collect(),
+        filters: pieces.map(str::parse).collect::<Result<_>>()?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn render(template: &str, issue: &Value) -> Option<String> {
+        template.parse::<Template>().unwrap().render(issue).unwrap()
+    }
+
+    #[test]
+    fn composes_fields_with_filters() {
+        let issue = json!({
+            "key": "PROJ-1",
+            "fields": {"project": {"key": "proj"}, "issuetype": {"name": "Bug Fix"}}
+        });
+        assert_eq!(
+            render(
+                "{fields.project.key | upper}-{fields.issuetype.name | replace: :_}",
+                &issue
+            ),
+            Some(String::from("PROJ-Bug_Fix"))
+        );
+    }
+
+    #[test]
+    fn missing_field_renders_nothing() {
+        let issue = json!({"key": "PROJ-1", "fields": {}});
+        assert_eq!(render("{fields.customfield_1.value}", &issue), None);
+    }
+
+    #[test]
+    fn doubled_braces_are_literal() {
+        let issue = json!({"key": "PROJ-1"});
+        assert_eq!(render("{{{key}}}", &issue), Some(String::from("{PROJ-1}")));
+    }
+
+    #[test]
+    fn rejects_unknown_filter_and_unclosed_brace() {
+        assert!("{key | shout}".parse::<Template>().is_err());
+        assert!("{key".parse::<Template>().is_err());
+    }
+}
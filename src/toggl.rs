@@ -0,0 +1,95 @@
+use std::collections::HashMap;
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, NaiveDate, TimeDelta, Utc};
+use reqwest::Client;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use crate::{
+    import::{self, TrackedTime, TrackerMapping},
+    plan::Plan,
+    week::Timezone,
+};
+
+const TOGGL_API: &str = "https://api.track.toggl.com/api/v9";
+
+///How time tracked in Toggl Track maps to tasks
+#[derive(Serialize, Deserialize)]
+pub struct TogglConfig {
+    #[serde(flatten)]
+    pub mapping: TrackerMapping,
+}
+
+#[derive(Deserialize)]
+struct TimeEntry {
+    #[serde(default)]
+    description: Option<String>,
+    start: DateTime<Utc>,
+    ///Seconds, negative while the timer is still running
+    duration: i64,
+    project_id: Option<u64>,
+    #[serde(default)]
+    tags: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct Project {
+    id: u64,
+    name: String,
+}
+
+///A plan logging the time tracked in Toggl between two days inclusive against the tasks it maps to
+pub async fn import(
+    config: &TogglConfig,
+    from: NaiveDate,
+    to: NaiveDate,
+    timezone: Timezone,
+) -> Result<Plan> {
+    let token = import::api_token("toggl-api-token", "Toggl Track API token")?;
+    let client = Client::new();
+    let start_date = (from - TimeDelta::days(1)).to_string();
+    let end_date = (to + TimeDelta::days(2)).to_string();
+    let entries: Vec<TimeEntry> = get(
+        &client,
+        &token,
+        "me/time_entries",
+        &[("start_date", &start_date), ("end_date", &end_date)],
+    )
+    .await?;
+    let projects: HashMap<u64, String> = get::<Vec<Project>>(&client, &token, "me/projects", &[])
+        .await?
+        .into_iter()
+        .map(|project| (project.id, project.name))
+        .collect();
+    let tracked = entries
+        .into_iter()
+        .filter(|entry| entry.duration > 0)
+        .map(|entry| TrackedTime {
+            day: timezone.local_time(entry.start).date(),
+            time_spent: TimeDelta::seconds(entry.duration),
+            description: entry.description.unwrap_or_default(),
+            project: entry.project_id.and_then(|id| projects.get(&id).cloned()),
+            tags: entry.tags,
+        })
+        .collect();
+    Ok(import::tracked_plan(tracked, &config.mapping, from, to))
+}
+
+async fn get<T: DeserializeOwned>(
+    client: &Client,
+    token: &str,
+    path: &str,
+    query: &[(&str, &str)],
+) -> Result<T> {
+    client
+        .get(format!("{TOGGL_API}/{path}"))
+        .basic_auth(token, Some("api_token"))
+        .query(query)
+        .send()
+        .await?
+        .error_for_status()
+        .context("Unable to read from Toggl Track, check the API token")?
+        .json()
+        .await
+        .map_err(|e| e.into())
+}
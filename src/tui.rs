@@ -0,0 +1,331 @@
+use anyhow::Result;
+use chrono::{NaiveDate, TimeDelta};
+use ratatui::{
+    crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Style, Stylize},
+    text::Line,
+    widgets::{Block, Cell, Clear, List, ListState, Paragraph, Row, Table},
+    DefaultTerminal, Frame,
+};
+
+use crate::duration::format_duration;
+
+const TASK_WIDTH: u16 = 40;
+const CELL_WIDTH: u16 = 9;
+const HELP: &str = "←↑↓→ move  +/- change  f fill day  0 clear  a add task  x remove task  p push to Tempo  q quit";
+
+///A task that can be added to the grid
+pub struct Choice {
+    pub key: String,
+    pub label: String,
+}
+
+struct GridRow {
+    key: String,
+    label: String,
+    cells: Vec<TimeDelta>,
+}
+
+///Choosing a task to add to the grid by typing part of its name
+struct Picker {
+    query: String,
+    selected: usize,
+}
+
+///A full-screen editor for a week as a grid of tasks by days
+pub struct WeekEditor {
+    days: Vec<NaiveDate>,
+    targets: Vec<TimeDelta>,
+    step: TimeDelta,
+    choices: Vec<Choice>,
+    rows: Vec<GridRow>,
+    row: usize,
+    column: usize,
+    picker: Option<Picker>,
+}
+
+///What the user did with the grid
+enum Outcome {
+    Continue,
+    Push,
+    Quit,
+}
+
+impl WeekEditor {
+    ///An empty grid for the days, with times changed in `step`s and checked against each day's
+    ///target
+    pub fn new(
+        days: Vec<NaiveDate>,
+        targets: Vec<TimeDelta>,
+        step: TimeDelta,
+        choices: Vec<Choice>,
+    ) -> WeekEditor {
+        WeekEditor {
+            days,
+            targets,
+            step,
+            choices,
+            rows: Vec::new(),
+            row: 0,
+            column: 0,
+            picker: None,
+        }
+    }
+
+    ///Add time to a task on a day, adding the task to the grid if needed
+    pub fn add(&mut self, key: &str, day: NaiveDate, time_spent: TimeDelta) {
+        let Some(column) = self.days.iter().position(|d| *d == day) else {
+            return;
+        };
+        let row = self.row_for(key);
+        self.rows[row].cells[column] += time_spent;
+    }
+
+    fn row_for(&mut self, key: &str) -> usize {
+        if let Some(row) = self.rows.iter().position(|row| row.key == key) {
+            return row;
+        }
+        let label = self
+            .choices
+            .iter()
+            .find(|choice| choice.key == key)
+            .map_or_else(|| key.to_owned(), |choice| choice.label.clone());
+        self.rows.push(GridRow {
+            key: key.to_owned(),
+            label,
+            cells: vec![TimeDelta::zero(); self.days.len()],
+        });
+        self.rows.len() - 1
+    }
+
+    ///Let the user edit the grid, returning the time on each task for each day if they push it,
+    ///or None if they quit
+    pub fn run(mut self) -> Result<Option<Vec<(String, NaiveDate, TimeDelta)>>> {
+        let mut terminal = ratatui::init();
+        let outcome = self.edit(&mut terminal);
+        ratatui::restore();
+        match outcome? {
+            Outcome::Push => Ok(Some(self.entries())),
+            _ => Ok(None),
+        }
+    }
+
+    fn edit(&mut self, terminal: &mut DefaultTerminal) -> Result<Outcome> {
+        loop {
+            terminal.draw(|frame| self.draw(frame))?;
+            let Event::Key(key) = event::read()? else {
+                continue;
+            };
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            let outcome = match self.picker {
+                Some(_) => {
+                    self.pick(key);
+                    Outcome::Continue
+                }
+                None => self.edit_grid(key),
+            };
+            if !matches!(outcome, Outcome::Continue) {
+                return Ok(outcome);
+            }
+        }
+    }
+
+    fn edit_grid(&mut self, key: KeyEvent) -> Outcome {
+        let last_column = self.days.len().saturating_sub(1);
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => return Outcome::Quit,
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                return Outcome::Quit
+            }
+            KeyCode::Char('p') => return Outcome::Push,
+            KeyCode::Left | KeyCode::Char('h') => self.column = self.column.saturating_sub(1),
+            KeyCode::Right | KeyCode::Char('l') => self.column = (self.column + 1).min(last_column),
+            KeyCode::Up | KeyCode::Char('k') => self.row = self.row.saturating_sub(1),
+            KeyCode::Down | KeyCode::Char('j') => {
+                self.row = (self.row + 1).min(self.rows.len().saturating_sub(1))
+            }
+            KeyCode::Char('+') | KeyCode::Char('=') => self.change(self.step),
+            KeyCode::Char('-') => self.change(-self.step),
+            KeyCode::Char('f') => {
+                let remaining = self.targets[self.column] - self.day_total(self.column);
+                self.change(remaining.max(TimeDelta::zero()));
+            }
+            KeyCode::Char('0') | KeyCode::Delete | KeyCode::Backspace => {
+                if let Some(row) = self.rows.get_mut(self.row) {
+                    row.cells[self.column] = TimeDelta::zero();
+                }
+            }
+            KeyCode::Char('a') => {
+                self.picker = Some(Picker {
+                    query: String::new(),
+                    selected: 0,
+                })
+            }
+            KeyCode::Char('x') if self.row < self.rows.len() => {
+                self.rows.remove(self.row);
+                self.row = self.row.min(self.rows.len().saturating_sub(1));
+            }
+            _ => {}
+        }
+        Outcome::Continue
+    }
+
+    fn change(&mut self, by: TimeDelta) {
+        if let Some(row) = self.rows.get_mut(self.row) {
+            let cell = &mut row.cells[self.column];
+            *cell = (*cell + by).max(TimeDelta::zero());
+        }
+    }
+
+    fn pick(&mut self, key: KeyEvent) {
+        let matching: Vec<String> = self
+            .matching_choices()
+            .iter()
+            .map(|choice| choice.key.clone())
+            .collect();
+        let Some(picker) = self.picker.as_mut() else {
+            return;
+        };
+        match key.code {
+            KeyCode::Esc => self.picker = None,
+            KeyCode::Enter => {
+                let chosen = matching.get(picker.selected).cloned();
+                self.picker = None;
+                if let Some(key) = chosen {
+                    self.row = self.row_for(&key);
+                }
+            }
+            KeyCode::Up => picker.selected = picker.selected.saturating_sub(1),
+            KeyCode::Down => {
+                picker.selected = (picker.selected + 1).min(matching.len().saturating_sub(1))
+            }
+            KeyCode::Backspace => {
+                picker.query.pop();
+                picker.selected = 0;
+            }
+            KeyCode::Char(c) => {
+                picker.query.push(c);
+                picker.selected = 0;
+            }
+            _ => {}
+        }
+    }
+
+    fn matching_choices(&self) -> Vec<&Choice> {
+        let query = self
+            .picker
+            .as_ref()
+            .map(|picker| picker.query.to_lowercase())
+            .unwrap_or_default();
+        self.choices
+            .iter()
+            .filter(|choice| choice.label.to_lowercase().contains(&query))
+            .collect()
+    }
+
+    fn day_total(&self, column: usize) -> TimeDelta {
+        self.rows.iter().map(|row| row.cells[column]).sum()
+    }
+
+    fn entries(&self) -> Vec<(String, NaiveDate, TimeDelta)> {
+        self.rows
+            .iter()
+            .flat_map(|row| {
+                self.days
+                    .iter()
+                    .zip(&row.cells)
+                    .filter(|(_, time_spent)| !time_spent.is_zero())
+                    .map(|(day, time_spent)| (row.key.clone(), *day, *time_spent))
+            })
+            .collect()
+    }
+
+    fn draw(&self, frame: &mut Frame) {
+        let [grid_area, help_area] =
+            Layout::vertical([Constraint::Min(0), Constraint::Length(1)]).areas(frame.area());
+
+        let header = Row::new(
+            std::iter::once(Cell::from("Task"))
+                .chain(
+                    self.days
+                        .iter()
+                        .map(|day| Cell::from(day.format("%a %-d").to_string())),
+                )
+                .chain(std::iter::once(Cell::from("Total"))),
+        )
+        .bold();
+        let rows = self.rows.iter().enumerate().map(|(r, row)| {
+            let cells = row.cells.iter().enumerate().map(|(c, time_spent)| {
+                let text = if time_spent.is_zero() {
+                    String::from("·")
+                } else {
+                    format_duration(*time_spent)
+                };
+                let cell = Cell::from(text);
+                if r == self.row && c == self.column {
+                    cell.reversed()
+                } else {
+                    cell
+                }
+            });
+            let total: TimeDelta = row.cells.iter().sum();
+            Row::new(
+                std::iter::once(Cell::from(row.label.clone()))
+                    .chain(cells)
+                    .chain(std::iter::once(Cell::from(format_duration(total)))),
+            )
+        });
+        let totals = (0..self.days.len()).map(|column| {
+            let total = self.day_total(column);
+            let colour = match total.cmp(&self.targets[column]) {
+                std::cmp::Ordering::Less => Color::Yellow,
+                std::cmp::Ordering::Equal => Color::Green,
+                std::cmp::Ordering::Greater => Color::Red,
+            };
+            Cell::from(format_duration(total)).style(Style::new().fg(colour))
+        });
+        let week_total: TimeDelta = (0..self.days.len()).map(|c| self.day_total(c)).sum();
+        let footer = Row::new(
+            std::iter::once(Cell::from("Total"))
+                .chain(totals)
+                .chain(std::iter::once(Cell::from(format_duration(week_total)))),
+        )
+        .bold();
+        let widths = std::iter::once(Constraint::Length(TASK_WIDTH))
+            .chain(self.days.iter().map(|_| Constraint::Length(CELL_WIDTH)))
+            .chain(std::iter::once(Constraint::Length(CELL_WIDTH)));
+        let table = Table::new(rows, widths)
+            .header(header)
+            .footer(footer)
+            .block(Block::bordered().title(" Week "));
+        frame.render_widget(table, grid_area);
+        frame.render_widget(Paragraph::new(Line::from(HELP).dim()), help_area);
+
+        if let Some(picker) = &self.picker {
+            let area = centered(grid_area, TASK_WIDTH + 20, 15);
+            let matching = self.matching_choices();
+            let items = matching.iter().map(|choice| choice.label.as_str());
+            let list = List::new(items)
+                .block(Block::bordered().title(format!(" Add task: {}_ ", picker.query)))
+                .highlight_style(Style::new().reversed());
+            let mut state = ListState::default().with_selected(Some(picker.selected));
+            frame.render_widget(Clear, area);
+            frame.render_stateful_widget(list, area, &mut state);
+        }
+    }
+}
+
+///A rectangle of at most the given size in the middle of an area
+fn centered(area: Rect, width: u16, height: u16) -> Rect {
+    let width = width.min(area.width);
+    let height = height.min(area.height);
+    Rect {
+        x: area.x + (area.width - width) / 2,
+        y: area.y + (area.height - height) / 2,
+        width,
+        height,
+    }
+}
@@ -0,0 +1,88 @@
+use std::fs;
+
+use common::{home, jt_configured};
+use serde_json::json;
+use wiremock::{
+    matchers::{method, path},
+    Mock, MockServer, ResponseTemplate,
+};
+
+mod common;
+
+const CONFIG: &str = r#"tempo_api_version = 4
+
+[commitments]
+ALPHA = 50
+BETA = 50
+
+[[static_tasks]]
+key = "ALPHA-1"
+
+[[static_tasks]]
+key = "BETA-1"
+"#;
+
+#[tokio::test]
+async fn rebalances_a_plan_keeping_each_day_and_entry_form() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/rest/api/2/myself"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "key": "jdoe" })))
+        .mount(&server)
+        .await;
+
+    let home = home("balance-auto");
+    let plan = home.join("plan.toml");
+    fs::write(
+        &plan,
+        r#"
+[[days]]
+day = "mon"
+entries = [
+  { task = "ALPHA-1", duration = "6h" },
+  { task = "BETA-1" },
+]
+
+[[days]]
+day = "tue"
+entries = [
+  { task = "ALPHA-1", percent = 50.0 },
+  { task = "BETA-1", minutes = 240 },
+]
+"#,
+    )
+    .unwrap();
+    let output = jt_configured(
+        &server,
+        &home,
+        CONFIG,
+        &[
+            "--week",
+            "2024-W19",
+            "balance",
+            "--plan",
+            plan.to_str().unwrap(),
+            "--auto",
+        ],
+    );
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let rebalanced: toml::Value = toml::from_str(&fs::read_to_string(&plan).unwrap()).unwrap();
+    let days = rebalanced["days"].as_array().unwrap();
+    let monday = days[0]["entries"].as_array().unwrap();
+    let tuesday = days[1]["entries"].as_array().unwrap();
+    let monday_alpha = monday[0]["duration"].as_str().unwrap();
+    assert!(monday[1].get("minutes").is_none() && monday[1].get("duration").is_none());
+    assert!(monday[1].get("percent").is_none());
+    let tuesday_alpha = tuesday[0]["percent"].as_float().unwrap();
+    let tuesday_beta = tuesday[1]["minutes"].as_integer().unwrap();
+    assert_eq!(
+        (tuesday_alpha * 480.0 / 100.0).round() as i64 + tuesday_beta,
+        480
+    );
+    assert_ne!(monday_alpha, "6h");
+}
@@ -0,0 +1,58 @@
+//!Helpers shared by the integration tests, not every one of which uses them all
+#![allow(dead_code)]
+
+use std::{
+    fs,
+    path::PathBuf,
+    process::{Command, Output},
+};
+
+use serde_json::Value;
+use wiremock::MockServer;
+
+///A recorded request or response body from `tests/fixtures`
+pub fn fixture(name: &str) -> Value {
+    let path = format!("{}/tests/fixtures/{name}.json", env!("CARGO_MANIFEST_DIR"));
+    serde_json::from_str(&fs::read_to_string(path).unwrap()).unwrap()
+}
+
+///A directory of its own for each test, standing in for the user's home so that nothing is read
+///from or written to the real configuration, cache or state
+pub fn home(test: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("jt-{test}-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+///Run jt against the mock server with a configuration logging as `jdoe`, whose token comes from
+///`token_command` so that the keyring is never consulted and a real token never reaches the server
+pub fn jt(server: &MockServer, home: &PathBuf, args: &[&str]) -> Output {
+    jt_configured(server, home, "tempo_api_version = 4\n", args)
+}
+
+///Run jt as [`jt`] does, with `extra` appended to its configuration in place of the pinned Tempo
+///API version
+pub fn jt_configured(server: &MockServer, home: &PathBuf, extra: &str, args: &[&str]) -> Output {
+    let config = home.join("jt.toml");
+    fs::write(
+        &config,
+        format!(
+            "api_endpoint = \"{}\"\nworker = \"jdoe\"\nreviewer = \"manager\"\ntoken_command = \"echo secret\"\n{extra}",
+            server.uri()
+        ),
+    )
+    .unwrap();
+    Command::new(env!("CARGO_BIN_EXE_jt"))
+        .arg("--config")
+        .arg(&config)
+        .args(args)
+        .env_remove("JIRA_TOKEN")
+        .env("HOME", home)
+        .env("XDG_CACHE_HOME", home.join("cache"))
+        .env("XDG_DATA_HOME", home.join("data"))
+        .env("XDG_CONFIG_HOME", home.join("config"))
+        .env("NO_COLOR", "1")
+        .output()
+        .unwrap()
+}
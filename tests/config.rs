@@ -0,0 +1,47 @@
+use common::{home, jt};
+use serde_json::json;
+use wiremock::{
+    matchers::{method, path, query_param},
+    Mock, MockServer, ResponseTemplate,
+};
+
+mod common;
+
+#[tokio::test]
+async fn reports_every_problem_even_when_some_checks_fail() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/rest/api/2/serverInfo"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "version": "9.12.0" })))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/rest/api/2/myself"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "key": "jdoe" })))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/rest/api/2/user"))
+        .and(query_param("key", "jdoe"))
+        .respond_with(ResponseTemplate::new(500))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/rest/api/2/user"))
+        .and(query_param("key", "manager"))
+        .respond_with(ResponseTemplate::new(404))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let output = jt(&server, &home("config-validate"), &["config", "validate"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(!output.status.success());
+    assert!(stdout.contains("Unable to check Worker jdoe"), "{stdout}");
+    assert!(
+        stdout.contains("Reviewer manager does not exist"),
+        "{stdout}"
+    );
+    assert!(stderr.contains("2 problems found"), "{stderr}");
+}
@@ -0,0 +1,64 @@
+use common::{home, jt};
+use serde_json::json;
+use wiremock::{
+    matchers::{body_partial_json, method, path},
+    Mock, MockServer, ResponseTemplate,
+};
+
+mod common;
+
+#[tokio::test]
+async fn dry_run_shows_the_copies_without_asking_or_logging() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/rest/api/2/myself"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "key": "jdoe" })))
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/rest/tempo-timesheets/4/worklogs/search"))
+        .and(body_partial_json(json!({ "from": "2024-04-29" })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!([{
+            "tempoWorklogId": 1,
+            "timeSpentSeconds": 5400,
+            "started": "2024-04-29 14:30:00.000",
+            "comment": "Pairing",
+            "issue": { "key": "PROJ-1" }
+        }])))
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/rest/tempo-timesheets/4/worklogs/search"))
+        .and(body_partial_json(json!({ "from": "2024-05-06" })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!([])))
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/rest/tempo-timesheets/4/worklogs"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(0)
+        .mount(&server)
+        .await;
+
+    let output = jt(
+        &server,
+        &home("copy-week-dry-run"),
+        &[
+            "--dry-run",
+            "copy-week",
+            "--from",
+            "2024-W18",
+            "--to",
+            "2024-W19",
+        ],
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(stdout.contains("Dry run, no work logged"), "{stdout}");
+    assert!(!stdout.contains("Work logged"), "{stdout}");
+    assert!(stdout.contains("PROJ-1"), "{stdout}");
+}
@@ -0,0 +1,259 @@
+use std::{fs, process::Output};
+
+use common::{fixture, home, jt, jt_configured};
+use serde_json::json;
+use wiremock::{
+    matchers::{body_json, body_partial_json, header, method, path},
+    Mock, MockServer, Request, ResponseTemplate,
+};
+
+mod common;
+
+const PLAN: &str = r#"
+[[days]]
+day = "mon"
+entries = [
+  { task = "PROJ-1", minutes = 90, comment = "Pairing" },
+  { task = "PROJ-2" },
+]
+"#;
+
+///Fill the week of 6 May 2024 from the plan, in a home directory named for the test
+fn fill(server: &MockServer, test: &str, args: &[&str]) -> Output {
+    fill_configured(server, test, "tempo_api_version = 4\n", args)
+}
+
+///Fill the week as [`fill`] does, with `extra` appended to the configuration
+fn fill_configured(server: &MockServer, test: &str, extra: &str, args: &[&str]) -> Output {
+    let home = home(test);
+    let plan = home.join("plan.toml");
+    fs::write(&plan, PLAN).unwrap();
+    let mut fill_args = vec![
+        "--week",
+        "2024-W19",
+        "fill",
+        "--plan",
+        plan.to_str().unwrap(),
+        "--skip-permission-check",
+    ];
+    fill_args.extend(args);
+    jt_configured(server, &home, extra, &fill_args)
+}
+
+///Responses for everything jt reads from JIRA and Tempo while filling the week of 6 May 2024
+async fn mount_week(server: &MockServer) {
+    Mock::given(method("GET"))
+        .and(path("/rest/api/2/myself"))
+        .and(header("Authorization", "Bearer secret"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "key": "jdoe" })))
+        .mount(server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/rest/api/2/search"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(fixture("search.response")))
+        .mount(server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/rest/tempo-timesheets/4/worklogs/search"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!([])))
+        .mount(server)
+        .await;
+}
+
+///Tempo's response to creating a worklog on the Monday
+fn created(id: u64, key: &str, seconds: u64) -> ResponseTemplate {
+    ResponseTemplate::new(200).set_body_json(json!([{
+        "tempoWorklogId": id,
+        "jiraWorklogId": id + 1000,
+        "timeSpentSeconds": seconds,
+        "started": "2024-05-06",
+        "issue": { "key": key }
+    }]))
+}
+
+///Requests the server received, with their bodies, for working out why a run failed
+fn describe(requests: Vec<Request>) -> Vec<String> {
+    requests
+        .iter()
+        .map(|request| {
+            format!(
+                "{} {} {}",
+                request.method,
+                request.url.path(),
+                String::from_utf8_lossy(&request.body)
+            )
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn fills_and_submits_week_from_plan() {
+    let server = MockServer::start().await;
+    mount_week(&server).await;
+    Mock::given(method("POST"))
+        .and(path("/rest/tempo-timesheets/4/worklogs"))
+        .and(body_json(fixture("fill-pairing.request")))
+        .respond_with(created(101, "PROJ-1", 5400))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/rest/tempo-timesheets/4/worklogs"))
+        .and(body_json(fixture("fill-rest-of-day.request")))
+        .respond_with(created(102, "PROJ-2", 23400))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/rest/tempo-timesheets/4/timesheet-approval"))
+        .and(body_partial_json(json!({
+            "period": { "dateFrom": "2024-05-04" },
+            "action": { "name": "submit" }
+        })))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/rest/tempo-timesheets/4/timesheet-approval/current"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(fixture("timesheet-approval.response")),
+        )
+        .mount(&server)
+        .await;
+
+    let output = fill(&server, "fill", &["--submit"]);
+    let received = describe(server.received_requests().await.unwrap());
+    assert!(
+        output.status.success(),
+        "{}\n{}\n{received:#?}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
+
+#[tokio::test]
+async fn starts_each_worklog_as_the_one_before_ends() {
+    let server = MockServer::start().await;
+    mount_week(&server).await;
+    for (key, started, seconds) in [
+        ("PROJ-1", "2024-05-06 09:00:00.000", 5400),
+        ("PROJ-2", "2024-05-06 10:30:00.000", 23400),
+    ] {
+        Mock::given(method("POST"))
+            .and(path("/rest/tempo-timesheets/4/worklogs"))
+            .and(body_partial_json(
+                json!({ "originTaskId": key, "started": started }),
+            ))
+            .respond_with(created(101, key, seconds))
+            .expect(1)
+            .mount(&server)
+            .await;
+    }
+
+    let output = fill_configured(
+        &server,
+        "workday-start",
+        "tempo_api_version = 4\nworkday_start = \"09:00\"\n",
+        &[],
+    );
+    let received = describe(server.received_requests().await.unwrap());
+    assert!(
+        output.status.success(),
+        "{}\n{received:#?}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
+
+#[tokio::test]
+async fn dry_run_logs_nothing() {
+    let server = MockServer::start().await;
+    mount_week(&server).await;
+    Mock::given(method("POST"))
+        .and(path("/rest/tempo-timesheets/4/worklogs"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(0)
+        .mount(&server)
+        .await;
+
+    let output = fill(&server, "dry-run", &["--submit", "--dry-run"]);
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("Dry run, timesheet not submitted"),
+        "{stdout}"
+    );
+    assert!(!stdout.contains("Timesheet submitted"), "{stdout}");
+}
+
+#[tokio::test]
+async fn explains_expired_token() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/rest/api/2/myself"))
+        .respond_with(ResponseTemplate::new(401))
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(0)
+        .mount(&server)
+        .await;
+
+    let output = fill(&server, "expired", &[]);
+    assert_eq!(output.status.code(), Some(77));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("did not accept the credentials"),
+        "{stderr}"
+    );
+}
+
+#[tokio::test]
+async fn refuses_range_without_working_days() {
+    let server = MockServer::start().await;
+    mount_week(&server).await;
+
+    let home = home("weekend");
+    let output = jt(
+        &server,
+        &home,
+        &["fill", "--from", "2024-05-11", "--to", "2024-05-12"],
+    );
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert_eq!(output.status.code(), Some(1), "{stderr}");
+    assert!(
+        stderr.contains("No working days between 2024-05-11 and 2024-05-12"),
+        "{stderr}"
+    );
+}
+
+#[tokio::test]
+async fn does_not_submit_after_rejected_worklog() {
+    let server = MockServer::start().await;
+    mount_week(&server).await;
+    Mock::given(method("POST"))
+        .and(path("/rest/tempo-timesheets/4/worklogs"))
+        .respond_with(ResponseTemplate::new(400).set_body_json(json!({
+            "errors": [{ "message": "Issue PROJ-2 is closed" }]
+        })))
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/rest/tempo-timesheets/4/timesheet-approval"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(0)
+        .mount(&server)
+        .await;
+
+    let output = fill(&server, "rejected", &["--submit"]);
+    let received = describe(server.received_requests().await.unwrap());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert_eq!(
+        output.status.code(),
+        Some(76),
+        "{}\n{stderr}\n{received:#?}",
+        String::from_utf8_lossy(&output.stdout)
+    );
+    assert!(stderr.contains("Issue PROJ-2 is closed"), "{stderr}");
+}
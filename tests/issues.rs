@@ -0,0 +1,147 @@
+use common::{fixture, home, jt, jt_configured};
+use serde_json::{json, Value};
+use wiremock::{
+    matchers::{method, path},
+    Mock, MockServer, ResponseTemplate,
+};
+
+mod common;
+
+#[tokio::test]
+async fn lists_tasks_as_json_alone_on_stdout() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/rest/api/2/myself"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "key": "jdoe" })))
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/rest/api/2/search"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(fixture("search.response")))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let home = home("issues-json");
+    for (run, status) in [("fetched", "retrieved"), ("cached", "cached")] {
+        let output = jt(&server, &home, &["--output", "json", "issues"]);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(output.status.success(), "{run}: {stderr}");
+        let tasks: Value = serde_json::from_slice(&output.stdout)
+            .unwrap_or_else(|e| panic!("{run}: {e}\n{}", String::from_utf8_lossy(&output.stdout)));
+        let keys: Vec<&str> = tasks
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter_map(|task| task["key"].as_str())
+            .collect();
+        assert_eq!(keys, ["PROJ-1", "PROJ-2"], "{run}");
+        assert!(stderr.contains(status), "{run}: {stderr}");
+    }
+}
+
+#[tokio::test]
+async fn keeps_the_verbose_api_summary_off_stdout() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/rest/api/2/myself"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "key": "jdoe" })))
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/rest/api/2/search"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(fixture("search.response")))
+        .mount(&server)
+        .await;
+
+    let output = jt(
+        &server,
+        &home("issues-verbose"),
+        &["-v", "--output", "json", "issues"],
+    );
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "{stderr}");
+    serde_json::from_slice::<Value>(&output.stdout)
+        .unwrap_or_else(|e| panic!("{e}\n{}", String::from_utf8_lossy(&output.stdout)));
+    assert!(stderr.contains("API calls"), "{stderr}");
+}
+
+#[tokio::test]
+async fn orders_tasks_by_the_instance_priority_order() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/rest/api/2/myself"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "key": "jdoe" })))
+        .mount(&server)
+        .await;
+    let mut search = fixture("search.response");
+    search["issues"][0]["fields"]["priority"] = json!({ "id": "3", "name": "Medium" });
+    search["issues"][1]["fields"]["priority"] = json!({ "id": "10000", "name": "Blocker" });
+    Mock::given(method("POST"))
+        .and(path("/rest/api/2/search"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(search))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/rest/api/2/priority"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!([
+            { "id": "10000", "name": "Blocker" },
+            { "id": "1", "name": "Highest" },
+            { "id": "3", "name": "Medium" },
+        ])))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let output = jt(
+        &server,
+        &home("issues-priority"),
+        &["--output", "json", "issues"],
+    );
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let tasks: Value = serde_json::from_slice(&output.stdout).unwrap();
+    let priorities: Vec<(&str, &str)> = tasks
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter_map(|task| Some((task["key"].as_str()?, task["priority"].as_str()?)))
+        .collect();
+    assert_eq!(priorities, [("PROJ-2", "Blocker"), ("PROJ-1", "Medium")]);
+}
+
+#[tokio::test]
+async fn detects_tempo_v4_without_a_pinned_version() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/rest/api/2/myself"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "key": "jdoe" })))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/rest/tempo-timesheets/4/worklogs"))
+        .respond_with(ResponseTemplate::new(405))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/rest/api/2/search"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(fixture("search.response")))
+        .mount(&server)
+        .await;
+
+    let output = jt_configured(
+        &server,
+        &home("issues-detect-tempo"),
+        "",
+        &["--output", "json", "issues"],
+    );
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}